@@ -238,14 +238,6 @@ impl Parse for Fields {
 }
 
 impl Fields {
-    pub fn is_empty(&self) -> bool {
-        match self {
-            Fields::Struct(fields) => fields.is_empty(),
-            Fields::Tuple(fields) => fields.is_empty(),
-            Fields::Unit => true,
-        }
-    }
-
     pub fn idents(&self) -> Box<dyn Iterator<Item = syn::Ident> + '_> {
         match self {
             Fields::Struct(fields) => Box::new(fields.iter().map(|f| f.ident.clone())),
@@ -259,18 +251,6 @@ impl Fields {
         }
     }
 
-    pub fn members(&self) -> Box<dyn Iterator<Item = syn::Member> + '_> {
-        match self {
-            Fields::Struct(fields) => {
-                Box::new(fields.iter().map(|f| syn::Member::Named(f.ident.clone())))
-            }
-            Fields::Tuple(fields) => {
-                Box::new(fields.iter().enumerate().map(|(i, _)| syn::Member::from(i)))
-            }
-            Fields::Unit => Box::new([].iter().cloned()),
-        }
-    }
-
     pub fn types(&self) -> Box<dyn Iterator<Item = &ast::Type> + '_> {
         match self {
             Fields::Struct(fields) => Box::new(fields.iter().map(|f| &f.typ)),
@@ -278,10 +258,6 @@ impl Fields {
             Fields::Unit => Box::new([].iter()),
         }
     }
-
-    pub fn defs(&self) -> Box<dyn Iterator<Item = (syn::Member, &ast::Type)> + '_> {
-        Box::new(std::iter::zip(self.members(), self.types()))
-    }
 }
 
 /// An unnamed (anonymous) field in a tuple struct or enum variant
@@ -31,6 +31,7 @@ impl Parse for DeriveInput {
 /// These settings are determined through the #[uniplate(...)] and #[biplate(...)] helper
 /// attributes.
 #[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum InstanceMeta {
     Uniplate(UniplateInstanceMeta),
     Biplate(BiplateInstanceMeta),
@@ -84,11 +85,38 @@ impl InstanceMeta {
 }
 
 #[derive(Clone, Debug, Default)]
-pub struct UniplateInstanceMeta {}
+pub struct UniplateInstanceMeta {
+    /// Whether to also derive `UniplateRef` and `BiplateRef` instances.
+    pub by_ref: bool,
+
+    /// Whether to also derive `UniplateMut` and `BiplateMut` instances.
+    pub by_mut: bool,
+}
 
 impl InstanceMetaKind for UniplateInstanceMeta {
     fn from_attribute(attr: syn::Attribute) -> syn::Result<InstanceMeta> {
-        Ok(InstanceMeta::Uniplate(UniplateInstanceMeta {}))
+        let mut instance = UniplateInstanceMeta::default();
+
+        // #[uniplate] and #[uniplate()] take no options.
+        if let syn::Meta::List(_) = attr.meta {
+            attr.parse_nested_meta(|meta| {
+                // #[uniplate(by_ref)]
+                if meta.path.is_ident("by_ref") {
+                    instance.by_ref = true;
+                    return Ok(());
+                }
+
+                // #[uniplate(by_mut)]
+                if meta.path.is_ident("by_mut") {
+                    instance.by_mut = true;
+                    return Ok(());
+                }
+
+                Err(meta.error("unrecognized property"))
+            })?;
+        }
+
+        Ok(InstanceMeta::Uniplate(instance))
     }
 }
 
@@ -3,8 +3,13 @@ use lazy_static::lazy_static;
 use syn::{PathArguments, parse_quote};
 
 lazy_static! {
-    static ref BOX_PREFIXES: Vec<&'static str> =
-        vec!("::std::boxed::Box", "std::boxed::Box", "Box");
+    static ref BOX_PREFIXES: Vec<&'static str> = vec!(
+        "::std::boxed::Box",
+        "std::boxed::Box",
+        "::alloc::boxed::Box",
+        "alloc::boxed::Box",
+        "Box"
+    );
 }
 
 /// A type
@@ -72,9 +77,9 @@ impl Parse for Type {
 
                 // To check whether this type is boxed: store the type without any parameters, and
                 // stringify it so that we can compare it against our list of known box types.
-                let mut type_segments = type_path.path.segments.clone();
-                type_segments.last_mut().unwrap().arguments = PathArguments::None;
-                let type_prefix: String = quote!(#type_segments).to_string();
+                let mut type_prefix = type_path.path.clone();
+                type_prefix.segments.last_mut().unwrap().arguments = PathArguments::None;
+                let type_prefix: String = quote!(#type_prefix).to_string().replace(' ', "");
 
                 if BOX_PREFIXES.contains(&type_prefix.as_str()) {
                     // Type is inside a box
@@ -2,8 +2,6 @@ mod ast;
 mod prelude;
 mod state;
 
-use std::collections::VecDeque;
-
 use prelude::*;
 use quote::format_ident;
 use syn::parse_macro_input;
@@ -15,7 +13,7 @@ pub fn uniplate_derive(input: TokenStream) -> TokenStream {
 
     let mut out_tokens: Vec<TokenStream2> = Vec::new();
     out_tokens.push(quote! {
-        use std::borrow::Borrow as _;
+        use ::core::borrow::Borrow as _;
     });
 
     while state.next_instance().is_some() {
@@ -24,6 +22,24 @@ pub fn uniplate_derive(input: TokenStream) -> TokenStream {
             Some(ast::InstanceMeta::Biplate(_)) => derive_a_biplate(&mut state),
             _ => unreachable!(),
         });
+
+        // With #[uniplate(by_ref)], every instance has a borrowing counterpart.
+        if state.by_ref {
+            out_tokens.push(match &state.current_instance {
+                Some(ast::InstanceMeta::Uniplate(_)) => derive_a_uniplate_ref(&mut state),
+                Some(ast::InstanceMeta::Biplate(_)) => derive_a_biplate_ref(&mut state),
+                _ => unreachable!(),
+            });
+        }
+
+        // With #[uniplate(by_mut)], every instance has a mutable counterpart.
+        if state.by_mut {
+            out_tokens.push(match &state.current_instance {
+                Some(ast::InstanceMeta::Uniplate(_)) => derive_a_uniplate_mut(&mut state),
+                Some(ast::InstanceMeta::Biplate(_)) => derive_a_biplate_mut(&mut state),
+                _ => unreachable!(),
+            });
+        }
     }
 
     out_tokens.into_iter().collect::<TokenStream2>().into()
@@ -31,10 +47,8 @@ pub fn uniplate_derive(input: TokenStream) -> TokenStream {
 
 fn derive_a_uniplate(state: &mut ParserState) -> TokenStream2 {
     let from = state.from.to_token_stream();
-    let tokens: TokenStream2 = match state.data.clone() {
-        ast::Data::DataEnum(x) => _derive_a_enum_uniplate(state, x),
-        ast::Data::DataStruct(x) => _derive_a_struct_uniplate(state, x),
-    };
+    let children_into = _derive_for_each_child(state, Visit::ChildrenInto);
+    let rebuild = _derive_rebuild(state);
 
     let mut generics = state.data.generics().clone();
     for (_, bounds) in generics.type_parameters.iter_mut() {
@@ -46,499 +60,421 @@ fn derive_a_uniplate(state: &mut ParserState) -> TokenStream2 {
     let where_clause = generics.impl_type_where_block();
     quote! {
         impl<#impl_bounds> ::uniplate::Uniplate for #from #where_clause {
-            fn uniplate(&self) -> (::uniplate::Tree<#from>, Box<dyn Fn(::uniplate::Tree<#from>) -> #from>) {
-                #tokens
+            fn uniplate(&self) -> (::uniplate::Tree<#from>, ::uniplate::impl_helpers::Box<dyn Fn(::uniplate::Tree<#from>) -> #from>) {
+                ::uniplate::impl_helpers::uniplate_via_rebuild(self)
+            }
+
+            fn children_into(&self, __out: &mut ::uniplate::impl_helpers::Vec<#from>) {
+                #children_into
+            }
+
+            fn rebuild(&self, __children: &mut impl ::core::iter::Iterator<Item = #from>) -> #from {
+                #rebuild
             }
         }
     }
 }
 
-fn _derive_a_enum_uniplate(state: &mut ParserState, data: ast::DataEnum) -> TokenStream2 {
-    let mut variant_tokens = VecDeque::<TokenStream2>::new();
-    for variant in data.variants {
-        let fields = &variant.fields;
-        let field_idents: Vec<_> = fields.idents().collect();
+fn derive_a_biplate(state: &mut ParserState) -> TokenStream2 {
+    let from = state.from.to_token_stream();
+    let to = state.to.to_token_stream();
 
-        let field_defs: Vec<_> = fields
-            .defs()
-            .map(|(mem, typ)| _derive_for_field_enum(state, typ, &mem))
-            .collect();
+    if from.to_string() == to.to_string() {
+        return _derive_identity_biplate(state, from);
+    }
 
-        let children_def = _derive_children(state, fields);
-        let ctx_def = _derive_ctx(state, fields, Some(&variant.ident));
-        let ident = variant.ident;
-        let enum_ident = state.data.ident();
+    let children_into = _derive_for_each_child(state, Visit::ChildrenInto);
+    let rebuild = _derive_rebuild(state);
 
-        match variant.fields {
-            ast::Fields::Struct(_) => {
-                variant_tokens.push_back(quote! {
-                    #enum_ident::#ident{#(#field_idents),*} => {
-                        #(#field_defs)*
+    let mut generics = state.data.generics().clone();
+    for (typ, bounds) in generics.type_parameters.iter_mut() {
+        // Add 'static bounds to all generic type parameters.
+        bounds.push(syn::TypeParamBound::Verbatim(quote!('static)));
 
-                        #children_def
+        // If we are deriving Biplate<T>, T must be Uniplate
+        if to.to_string() == typ.to_token_stream().to_string() {
+            bounds.push(syn::TypeParamBound::Verbatim(quote!(Uniplate)));
+        }
+    }
 
-                        #ctx_def
+    let impl_bounds = generics.impl_parameters();
+    let where_clause = generics.impl_type_where_block();
 
-                        (children,ctx)
-                    },
-                });
+    quote! {
+        impl<#impl_bounds> ::uniplate::Biplate<#to> for #from #where_clause{
+            fn biplate(&self) -> (::uniplate::Tree<#to>, ::uniplate::impl_helpers::Box<dyn Fn(::uniplate::Tree<#to>) -> #from>) {
+                ::uniplate::impl_helpers::biplate_via_rebuild(self)
             }
 
-            ast::Fields::Tuple(_) => {
-                variant_tokens.push_back(quote! {
-                    #enum_ident::#ident(#(#field_idents),*) => {
-                        #(#field_defs)*
-
-                        #children_def
-
-                        #ctx_def
-
-                        (children,ctx)
-                    },
-                });
+            fn children_bi_into(&self, __out: &mut ::uniplate::impl_helpers::Vec<#to>) {
+                #children_into
             }
-            ast::Fields::Unit => {
-                variant_tokens.push_back(quote! {
-                    #enum_ident::#ident => {
-                        #children_def
-
-                        #ctx_def
 
-                        (children,ctx)
-                    },
-                });
+            fn rebuild_bi(&self, __children: &mut impl ::core::iter::Iterator<Item = #to>) -> #from {
+                #rebuild
             }
         }
     }
-
-    let variant_tokens = variant_tokens.iter();
-    quote! {
-        match self {
-            #(#variant_tokens)*
-        }
-    }
 }
 
-fn _derive_a_struct_uniplate(state: &mut ParserState, data: ast::DataStruct) -> TokenStream2 {
-    let struct_ident = state.data.ident();
-    if data.fields.is_empty() {
-        // Unit-like or empty struct
-        return quote! {
-            (::uniplate::Tree::Zero,Box::new(|_| #struct_ident))
-        };
+fn _derive_identity_biplate(state: &mut ParserState, from: TokenStream2) -> TokenStream2 {
+    let mut generics = state.data.generics().clone();
+    // Add 'static bounds to all generic type parameters.
+    for (_, bounds) in generics.type_parameters.iter_mut() {
+        bounds.push(syn::TypeParamBound::Verbatim(quote!('static)));
     }
 
-    let field_defs: Vec<_> = data
-        .fields
-        .defs()
-        .map(|(mem, typ)| _derive_for_field_struct(state, typ, mem))
-        .collect();
-    let children_def = _derive_children(state, &data.fields);
-    let ctx_def = _derive_ctx(state, &data.fields, None);
+    let impl_bounds = generics.impl_parameters();
+    let where_clause = generics.impl_type_where_block();
 
     quote! {
-        #(#field_defs)*
-
-        #children_def
+        impl<#impl_bounds> ::uniplate::Biplate<#from> for #from #where_clause{
+            fn biplate(&self) -> (::uniplate::Tree<#from>, ::uniplate::impl_helpers::Box<dyn Fn(::uniplate::Tree<#from>) -> #from>) {
+                ::uniplate::impl_helpers::biplate_via_rebuild(self)
+            }
 
-        #ctx_def
+            fn children_bi_into(&self, __out: &mut ::uniplate::impl_helpers::Vec<#from>) {
+                __out.push(self.clone());
+            }
 
-        (children,ctx)
+            fn rebuild_bi(&self, __children: &mut impl ::core::iter::Iterator<Item = #from>) -> #from {
+                __children.next().expect("rebuild_bi() given too few children")
+            }
+        }
     }
 }
 
-fn _derive_for_field_enum(
-    state: &mut ParserState,
-    field_type: &ast::Type,
-    member: &syn::Member,
-) -> TokenStream2 {
-    // the identifier used in the match clause.
-    // either _1, or the field name.
-    let match_ident = match member {
-        syn::Member::Named(ident) => ident.clone(),
-        syn::Member::Unnamed(index) => format_ident!("_{}", index),
-    };
-
-    let children_ident = format_ident!("_{}_children", member);
-    let ctx_ident = format_ident!("_{}_ctx", member);
-
-    let to_t = state.to.clone().expect("").to_token_stream();
-
-    match field_type {
-        ast::Type::BoxedBasic(_) => {
-            quote! {
-                let (#children_ident,#ctx_ident) = ::uniplate::spez::try_biplate_to!((**#match_ident).clone(), #to_t);
-            }
-        }
-        ast::Type::Basic(_) => {
-            quote! {
-                let (#children_ident,#ctx_ident) = ::uniplate::spez::try_biplate_to!(#match_ident.clone(), #to_t);
-            }
-        }
-        ast::Type::Tuple(tuple_type) => {
-            // destructure the tuple
-            let tuple_field_idents =
-                (0..tuple_type.n).map(|i| format_ident!("_{}_tuple_field_{i}", member));
-            let destructure_tuple = quote! {
-                    let (#(#tuple_field_idents),*) = #match_ident;
-            };
-
-            // call biplate on each tuple field
-            let call_biplate_for_each_field = tuple_type.fields.iter().enumerate().map(|(i,_)| {
-                let field_ident = format_ident!("_{}_tuple_field_{i}",member);
-                let field_children_ident = format_ident!("_{}_tuple_field_{i}_children",member);
-                let field_ctx_ident = format_ident!("_{}_tuple_field_{i}_ctx", member);
-
-                // let index = syn::Index::from(i);
-                quote!{
-                    let (#field_children_ident,#field_ctx_ident) = ::uniplate::spez::try_biplate_to!(#field_ident.clone(), #to_t);
+/// Generates the body of `rebuild(_bi)` for the current target type.
+///
+/// The generated code rebuilds `self`, taking each child of the target type from `__children`,
+/// from left to right.
+fn _derive_rebuild(state: &mut ParserState) -> TokenStream2 {
+    match state.data.clone() {
+        ast::Data::DataEnum(data) => {
+            let enum_ident = state.data.ident();
+            let variant_tokens = data.variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                let field_idents: Vec<_> = variant.fields.idents().collect();
+                let construct =
+                    _derive_construct(state, &variant.fields, quote!(#enum_ident::#ident));
+                match variant.fields {
+                    ast::Fields::Struct(_) => quote! {
+                        #enum_ident::#ident{#(#field_idents),*} => #construct,
+                    },
+                    ast::Fields::Tuple(_) => quote! {
+                        #enum_ident::#ident(#(#field_idents),*) => #construct,
+                    },
+                    ast::Fields::Unit => quote! {
+                        #enum_ident::#ident => #construct,
+                    },
                 }
             });
 
-            let tuple_field_children_idents =
-                (0..tuple_type.n).map(|i| format_ident!("_{}_tuple_field_{i}_children", member));
-            let tuple_field_ctx_idents =
-                (0..tuple_type.n).map(|i| format_ident!("_{}_tuple_field_{i}_ctx", member));
-
-            // build the children tree by combining each fields' tree
-            let build_child_tree = quote! {
-                let #children_ident = ::uniplate::Tree::Many(::std::collections::VecDeque::from([#(#tuple_field_children_idents),*]));
-            };
-
-            // build the context function
-            let build_child_ctx = quote! {
-                let #ctx_ident = Box::new(move |x| {
-                    let ::uniplate::Tree::Many(xs) = x else {
-                        panic!()
-                    };
-
-                    let mut xs = xs.into_iter();
-                    (#(#tuple_field_ctx_idents(xs.next().unwrap())),*)
-                });
-            };
-
             quote! {
-                #destructure_tuple
-                #(#call_biplate_for_each_field);*
-                #build_child_tree
-                #build_child_ctx
+                match self {
+                    #(#variant_tokens)*
+                }
             }
         }
-        ast::Type::BoxedTuple(tuple_type) => {
-            // destructure the tuple
-            let tuple_field_idents =
-                (0..tuple_type.n).map(|i| format_ident!("_{}_tuple_field_{i}", member));
-            let destructure_tuple = quote! {
-                    let (#(#tuple_field_idents),*) = (**#match_ident).clone();
-            };
-
-            // call biplate on each tuple field
-            let call_biplate_for_each_field = tuple_type.fields.iter().enumerate().map(|(i,_)| {
-                let field_ident = format_ident!("_{}_tuple_field_{i}",member);
-                let field_children_ident = format_ident!("_{}_tuple_field_{i}_children",member);
-                let field_ctx_ident = format_ident!("_{}_tuple_field_{i}_ctx", member);            
-
-                // let index = syn::Index::from(i);
-                quote!{
-                    let (#field_children_ident,#field_ctx_ident) = ::uniplate::spez::try_biplate_to!(#field_ident.clone(), #to_t);
-                }
-            });
-
-            let tuple_field_children_idents =
-                (0..tuple_type.n).map(|i| format_ident!("_{}_tuple_field_{i}_children", member));
-            let tuple_field_ctx_idents =
-                (0..tuple_type.n).map(|i| format_ident!("_{}_tuple_field_{i}_ctx", member));
-
-            // build the children tree by combining each fields' tree
-            let build_child_tree = quote! {
-                let #children_ident = ::uniplate::Tree::Many(::std::collections::VecDeque::from([#(#tuple_field_children_idents),*]));
-            };
-
-            // build the context function
-            let build_child_ctx = quote! {
-                let #ctx_ident = Box::new(move |x| {
-                    let ::uniplate::Tree::Many(xs) = x else {
-                        panic!()
-                    };
-
-                    let mut xs = xs.into_iter();
-                    (#(#tuple_field_ctx_idents(xs.next().unwrap())),*)
-                });
-            };
-
-            quote! {
-                #destructure_tuple
-                #(#call_biplate_for_each_field);*
-                #build_child_tree
-                #build_child_ctx
+        ast::Data::DataStruct(data) => {
+            let struct_ident = state.data.ident();
+            let field_idents: Vec<_> = data.fields.idents().collect();
+            let construct = _derive_construct(state, &data.fields, quote!(#struct_ident));
+            match data.fields {
+                ast::Fields::Struct(_) => quote! {
+                    let #struct_ident{#(#field_idents),*} = self;
+                    #construct
+                },
+                ast::Fields::Tuple(_) => quote! {
+                    let #struct_ident(#(#field_idents),*) = self;
+                    #construct
+                },
+                ast::Fields::Unit => construct,
             }
         }
     }
 }
 
-fn _derive_for_field_struct(
-    state: &mut ParserState,
-    field_type: &ast::Type,
-    member: syn::Member,
+/// Generates an expression that constructs `constructor` from the given fields, taking their
+/// children of the target type from `__children`.
+///
+/// Assumes that each field has been bound by reference to its identifier (as given by
+/// `fields.idents()`).
+fn _derive_construct(
+    state: &ParserState,
+    fields: &ast::Fields,
+    constructor: TokenStream2,
 ) -> TokenStream2 {
-    let children_ident = format_ident!("_{}_children", member);
-    let ctx_ident = format_ident!("_{}_ctx", member);
-
     let to_t = state.to.clone().expect("").to_token_stream();
-
-    match field_type {
-        ast::Type::BoxedBasic(_) => {
-            quote! {
-                let (#children_ident,#ctx_ident) = ::uniplate::spez::try_biplate_to!((*self.#member).clone(), #to_t);
+    let rebuild_bi = quote!(::uniplate::spez::try_rebuild_bi);
+    let new_box = quote!(::uniplate::impl_helpers::Box::new);
+
+    let field_values =
+        std::iter::zip(fields.idents(), fields.types()).map(|(ident, typ)| match typ {
+            ast::Type::Basic(_) => quote! {
+                #rebuild_bi!(#ident, #to_t, __children)
+            },
+            ast::Type::BoxedBasic(_) => quote! {
+                #new_box(#rebuild_bi!(&**#ident, #to_t, __children))
+            },
+            ast::Type::Tuple(tuple_type) | ast::Type::BoxedTuple(tuple_type) => {
+                let tuple_field_idents: Vec<_> = (0..tuple_type.n)
+                    .map(|i| format_ident!("_{}_tuple_field_{i}", ident))
+                    .collect();
+                let (tuple, wrap) = match typ {
+                    ast::Type::BoxedTuple(_) => (quote!(&**#ident), new_box.clone()),
+                    _ => (quote!(#ident), quote!()),
+                };
+                quote! {{
+                    let (#(#tuple_field_idents),*) = #tuple;
+                    #wrap((#(#rebuild_bi!(#tuple_field_idents, #to_t, __children)),*))
+                }}
             }
-        }
-        ast::Type::Basic(_) => {
+        });
+
+    match fields {
+        ast::Fields::Struct(_) => {
+            let field_idents = fields.idents();
             quote! {
-                let (#children_ident,#ctx_ident) = ::uniplate::try_biplate_to!(self.#member.clone(), #to_t);
+                #constructor { #(#field_idents: #field_values),* }
             }
         }
-        ast::Type::Tuple(tuple_type) => {
-            // destructure the tuple
-            let tuple_field_idents =
-                (0..tuple_type.n).map(|i| format_ident!("_{}_tuple_field_{i}", member));
-            let destructure_tuple = quote! {
-                    let (#(#tuple_field_idents),*) = self.#member.clone();
-            };
-
-            // call biplate on each tuple field
-            let call_biplate_for_each_field = tuple_type.fields.iter().enumerate().map(|(i,_)| {
-                let field_ident = format_ident!("_{}_tuple_field_{i}",member);
-                let field_children_ident = format_ident!("_{}_tuple_field_{i}_children",member);
-                let field_ctx_ident = format_ident!("_{}_tuple_field_{i}_ctx", member);
-
-                // let index = syn::Index::from(i);
-                quote!{
-                    let (#field_children_ident,#field_ctx_ident) = ::uniplate::spez::try_biplate_to!(#field_ident.clone(), #to_t);
-                }
-            });
-
-            let tuple_field_children_idents =
-                (0..tuple_type.n).map(|i| format_ident!("_{}_tuple_field_{i}_children", member));
-            let tuple_field_ctx_idents =
-                (0..tuple_type.n).map(|i| format_ident!("_{}_tuple_field_{i}_ctx", member));
-
-            // build the children tree by combining each fields' tree
-            let build_child_tree = quote! {
-                let #children_ident = ::uniplate::Tree::Many(::std::collections::VecDeque::from([#(#tuple_field_children_idents),*]));
-            };
+        ast::Fields::Tuple(_) => quote! {
+            #constructor(#(#field_values),*)
+        },
+        ast::Fields::Unit => constructor,
+    }
+}
 
-            // build the context function
-            let build_child_ctx = quote! {
-                let #ctx_ident = Box::new(move |x| {
-                    let ::uniplate::Tree::Many(xs) = x else {
-                        panic!()
-                    };
+fn derive_a_uniplate_ref(state: &mut ParserState) -> TokenStream2 {
+    let from = state.from.to_token_stream();
+    let tokens = _derive_for_each_child(state, Visit::Ref);
 
-                    let mut xs = xs.into_iter();
-                    (#(#tuple_field_ctx_idents(xs.next().unwrap())),*)
-                });
-            };
+    let mut generics = state.data.generics().clone();
+    for (_, bounds) in generics.type_parameters.iter_mut() {
+        // Add 'static bounds to all generic type parameters.
+        bounds.push(syn::TypeParamBound::Verbatim(quote!('static)));
+    }
 
-            quote! {
-                #destructure_tuple
-                #(#call_biplate_for_each_field);*
-                #build_child_tree
-                #build_child_ctx
+    let impl_bounds = generics.impl_parameters();
+    let where_clause = generics.impl_type_where_block();
+    quote! {
+        impl<#impl_bounds> ::uniplate::UniplateRef for #from #where_clause {
+            fn for_each_child<'__a>(&'__a self, __f: &mut impl FnMut(&'__a #from)) {
+                #tokens
             }
         }
-        ast::Type::BoxedTuple(tuple_type) => {
-            // destructure the tuple
-            let tuple_field_idents =
-                (0..tuple_type.n).map(|i| format_ident!("_{}_tuple_field_{i}", member));
-            let destructure_tuple = quote! {
-                    let (#(#tuple_field_idents),*) = (*self.#member).clone();
-            };
-
-            // call biplate on each tuple field
-            let call_biplate_for_each_field = tuple_type.fields.iter().enumerate().map(|(i,_)| {
-                let field_ident = format_ident!("_{}_tuple_field_{i}",member);
-                let field_children_ident = format_ident!("_{}_tuple_field_{i}_children",member);
-                let field_ctx_ident = format_ident!("_{}_tuple_field_{i}_ctx", member);
-
-                // let index = syn::Index::from(i);
-                quote!{
-                    let (#field_children_ident,#field_ctx_ident) = ::uniplate::spez::try_biplate_to!(#field_ident.clone(), #to_t);
-                }
-            });
+    }
+}
+
+fn derive_a_biplate_ref(state: &mut ParserState) -> TokenStream2 {
+    let from = state.from.to_token_stream();
+    let to = state.to.to_token_stream();
 
-            let tuple_field_children_idents =
-                (0..tuple_type.n).map(|i| format_ident!("_{}_tuple_field_{i}_children", member));
-            let tuple_field_ctx_idents =
-                (0..tuple_type.n).map(|i| format_ident!("_{}_tuple_field_{i}_ctx", member));
+    let mut generics = state.data.generics().clone();
+    for (typ, bounds) in generics.type_parameters.iter_mut() {
+        // Add 'static bounds to all generic type parameters.
+        bounds.push(syn::TypeParamBound::Verbatim(quote!('static)));
 
-            // build the children tree by combining each fields' tree
-            let build_child_tree = quote! {
-                let #children_ident = ::uniplate::Tree::Many(::std::collections::VecDeque::from([#(#tuple_field_children_idents),*]));
-            };
+        // If we are deriving BiplateRef<T>, T must be UniplateRef
+        if to.to_string() == typ.to_token_stream().to_string() {
+            bounds.push(syn::TypeParamBound::Verbatim(quote!(
+                ::uniplate::UniplateRef
+            )));
+        }
+    }
 
-            // build the context function
-            let build_child_ctx = quote! {
-                let #ctx_ident = Box::new(move |x| {
-                    let ::uniplate::Tree::Many(xs) = x else {
-                        panic!()
-                    };
+    let impl_bounds = generics.impl_parameters();
+    let where_clause = generics.impl_type_where_block();
 
-                    let mut xs = xs.into_iter();
-                    (#(#tuple_field_ctx_idents(xs.next().unwrap())),*)
-                });
-            };
+    let tokens = if from.to_string() == to.to_string() {
+        // BiplateRef<T> for T visits the root.
+        quote!(__f(self))
+    } else {
+        _derive_for_each_child(state, Visit::Ref)
+    };
 
-            quote! {
-                #destructure_tuple
-                #(#call_biplate_for_each_field);*
-                #build_child_tree
-                #build_child_ctx
+    quote! {
+        impl<#impl_bounds> ::uniplate::BiplateRef<#to> for #from #where_clause {
+            fn for_each_child_bi<'__a>(&'__a self, __f: &mut impl FnMut(&'__a #to)) {
+                #tokens
             }
         }
     }
 }
 
-fn _derive_children(_state: &mut ParserState, fields: &ast::Fields) -> TokenStream2 {
-    let mut subtrees: VecDeque<TokenStream2> = VecDeque::new();
-    for (member, _) in fields.defs() {
-        subtrees.push_back({
-            let children_ident = format_ident!("_{}_children", member);
-            quote!(#children_ident)
-        });
-    }
+fn derive_a_uniplate_mut(state: &mut ParserState) -> TokenStream2 {
+    let from = state.from.to_token_stream();
+    let tokens = _derive_for_each_child(state, Visit::Mut);
 
-    match subtrees.len() {
-        0 => quote! {let children = ::uniplate::Tree::Zero;},
-        _ => {
-            let subtrees = subtrees.iter();
-            quote! {let children = ::uniplate::Tree::Many(::std::collections::VecDeque::from([#(#subtrees),*]));}
-        }
+    let mut generics = state.data.generics().clone();
+    for (_, bounds) in generics.type_parameters.iter_mut() {
+        // Add 'static bounds to all generic type parameters.
+        bounds.push(syn::TypeParamBound::Verbatim(quote!('static)));
     }
-}
 
-fn _derive_ctx(
-    state: &mut ParserState,
-    fields: &ast::Fields,
-    var_ident: Option<&syn::Ident>,
-) -> TokenStream2 {
-    let field_ctxs: Vec<_> = fields
-        .defs()
-        .enumerate()
-        .map(|(_i, (mem, typ))| match typ {
-            ast::Type::Basic(_) | ast::Type::Tuple(_) => {
-                let ctx_ident = format_ident!("_{}_ctx", mem);
-                quote! {#ctx_ident(x.next().unwrap())}
-            }
-
-            ast::Type::BoxedBasic(_) | ast::Type::BoxedTuple(_) => {
-                let ctx_ident = format_ident!("_{}_ctx", mem);
-                quote! {Box::new(#ctx_ident(x.next().unwrap()))}
+    let impl_bounds = generics.impl_parameters();
+    let where_clause = generics.impl_type_where_block();
+    quote! {
+        impl<#impl_bounds> ::uniplate::UniplateMut for #from #where_clause {
+            fn for_each_child_mut(&mut self, __f: &mut impl FnMut(&mut #from)) {
+                #tokens
             }
-        })
-        .collect();
-
-    let data_ident = state.data.ident(); // The enum or struct name
-    let typ = state.to.clone();
-
-    // If this is an enum, use the passed variant identifier
-    let construct_ident = match var_ident {
-        Some(var) => quote! {#data_ident::#var},
-        None => quote! {#data_ident},
-    };
-    if fields.is_empty() {
-        quote! {
-            let ctx = Box::new(move |x: ::uniplate::Tree<#typ>| {
-                let ::uniplate::Tree::Zero = x else { panic!()};
-                #construct_ident
-            });
         }
-    } else {
-        // If this is an enum, use the passed variant identifier
-        let construct_ident = match var_ident {
-            Some(var) => quote! {#data_ident::#var},
-            None => quote! {#data_ident},
-        };
-        let construct = match fields {
-            ast::Fields::Tuple(_) => {
-                quote! {
-                    #construct_ident(#(#field_ctxs),*)
-                }
-            }
-            ast::Fields::Struct(_) => {
-                let items = std::iter::zip(fields.idents(), field_ctxs.iter())
-                    .map(|(ident, ctx)| quote! {#ident: #ctx});
-                quote! {
-                    #construct_ident {
-                        #(#items),*
-                    }
-                }
-            }
-            ast::Fields::Unit => quote! {#var_ident},
-        };
-        quote! {
-            let ctx = Box::new(move |x: ::uniplate::Tree<#typ>| {
-                let ::uniplate::Tree::Many(x) = x else { panic!()};
-                let mut x = x.into_iter();
-                #construct
-        });}
     }
 }
 
-fn derive_a_biplate(state: &mut ParserState) -> TokenStream2 {
+fn derive_a_biplate_mut(state: &mut ParserState) -> TokenStream2 {
     let from = state.from.to_token_stream();
     let to = state.to.to_token_stream();
 
-    if from.to_string() == to.to_string() {
-        return _derive_identity_biplate(state, from);
-    }
-
-    let tokens: TokenStream2 = match state.data.clone() {
-        ast::Data::DataEnum(x) => _derive_a_enum_uniplate(state, x),
-        ast::Data::DataStruct(x) => _derive_a_struct_uniplate(state, x),
-    };
-
     let mut generics = state.data.generics().clone();
     for (typ, bounds) in generics.type_parameters.iter_mut() {
         // Add 'static bounds to all generic type parameters.
         bounds.push(syn::TypeParamBound::Verbatim(quote!('static)));
 
-        // If we are deriving Biplate<T>, T must be Uniplate
+        // If we are deriving BiplateMut<T>, T must be UniplateMut
         if to.to_string() == typ.to_token_stream().to_string() {
-            bounds.push(syn::TypeParamBound::Verbatim(quote!(Uniplate)));
+            bounds.push(syn::TypeParamBound::Verbatim(quote!(
+                ::uniplate::UniplateMut
+            )));
         }
     }
 
     let impl_bounds = generics.impl_parameters();
     let where_clause = generics.impl_type_where_block();
 
+    let tokens = if from.to_string() == to.to_string() {
+        // BiplateMut<T> for T visits the root.
+        quote!(__f(self))
+    } else {
+        _derive_for_each_child(state, Visit::Mut)
+    };
+
     quote! {
-        impl<#impl_bounds> ::uniplate::Biplate<#to> for #from #where_clause{
-            fn biplate(&self) -> (::uniplate::Tree<#to>, Box<dyn Fn(::uniplate::Tree<#to>) -> #from>) {
+        impl<#impl_bounds> ::uniplate::BiplateMut<#to> for #from #where_clause {
+            fn for_each_child_bi_mut(&mut self, __f: &mut impl FnMut(&mut #to)) {
                 #tokens
             }
         }
     }
 }
 
-fn _derive_identity_biplate(state: &mut ParserState, from: TokenStream2) -> TokenStream2 {
-    let mut generics = state.data.generics().clone();
-    // Add 'static bounds to all generic type parameters.
-    for (_, bounds) in generics.type_parameters.iter_mut() {
-        bounds.push(syn::TypeParamBound::Verbatim(quote!('static)));
-    }
+/// A traversal over the children of the target type generated by [`_derive_for_each_child`].
+#[derive(Clone, Copy)]
+enum Visit {
+    /// `for_each_child(_bi)`: calls `__f` on a reference to each child.
+    Ref,
 
-    let impl_bounds = generics.impl_parameters();
-    let where_clause = generics.impl_type_where_block();
+    /// `for_each_child(_bi)_mut`: calls `__f` on a mutable reference to each child.
+    Mut,
 
-    quote! {
-        impl<#impl_bounds> ::uniplate::Biplate<#from> for #from #where_clause{
-            fn biplate(&self) -> (::uniplate::Tree<#from>, Box<dyn Fn(::uniplate::Tree<#from>) -> #from>) {
-                let val = self.clone();
-                (::uniplate::Tree::One(val.clone()),Box::new(move |x| {
-                    let ::uniplate::Tree::One(x) = x else {todo!()};
-                    x
-                }))
+    /// `children(_bi)_into`: pushes a clone of each child onto `__out`.
+    ChildrenInto,
+}
+
+/// Generates the body of the given traversal for the current target type.
+///
+/// The generated code visits each child of the target type, from left to right.
+fn _derive_for_each_child(state: &mut ParserState, visit: Visit) -> TokenStream2 {
+    match state.data.clone() {
+        ast::Data::DataEnum(data) => {
+            let enum_ident = state.data.ident();
+            let variant_tokens = data.variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                let field_idents: Vec<_> = variant.fields.idents().collect();
+                let field_visits = _derive_field_visits(state, &variant.fields, visit);
+                match variant.fields {
+                    ast::Fields::Struct(_) => quote! {
+                        #enum_ident::#ident{#(#field_idents),*} => {
+                            #(#field_visits)*
+                        },
+                    },
+                    ast::Fields::Tuple(_) => quote! {
+                        #enum_ident::#ident(#(#field_idents),*) => {
+                            #(#field_visits)*
+                        },
+                    },
+                    ast::Fields::Unit => quote! {
+                        #enum_ident::#ident => {},
+                    },
+                }
+            });
+
+            quote! {
+                match self {
+                    #(#variant_tokens)*
+                }
+            }
+        }
+        ast::Data::DataStruct(data) => {
+            let struct_ident = state.data.ident();
+            let field_idents: Vec<_> = data.fields.idents().collect();
+            let field_visits = _derive_field_visits(state, &data.fields, visit);
+            match data.fields {
+                ast::Fields::Struct(_) => quote! {
+                    let #struct_ident{#(#field_idents),*} = self;
+                    #(#field_visits)*
+                },
+                ast::Fields::Tuple(_) => quote! {
+                    let #struct_ident(#(#field_idents),*) = self;
+                    #(#field_visits)*
+                },
+                ast::Fields::Unit => quote! {},
             }
         }
     }
 }
+
+/// Generates code that visits each child of the target type inside the given fields.
+///
+/// Assumes that each field has been bound by (mutable) reference to its identifier (as given by
+/// `fields.idents()`).
+fn _derive_field_visits(
+    state: &ParserState,
+    fields: &ast::Fields,
+    visit: Visit,
+) -> Vec<TokenStream2> {
+    let to_t = state.to.clone().expect("").to_token_stream();
+    let (try_biplate_to, unbox, arg) = match visit {
+        Visit::Ref => (
+            quote!(::uniplate::spez::try_biplate_ref_to),
+            quote!(&**),
+            quote!(__f),
+        ),
+        Visit::Mut => (
+            quote!(::uniplate::spez::try_biplate_mut_to),
+            quote!(&mut **),
+            quote!(__f),
+        ),
+        Visit::ChildrenInto => (
+            quote!(::uniplate::spez::try_children_bi_into),
+            quote!(&**),
+            quote!(__out),
+        ),
+    };
+
+    std::iter::zip(fields.idents(), fields.types())
+        .map(|(ident, typ)| match typ {
+            ast::Type::Basic(_) => quote! {
+                #try_biplate_to!(#ident, #to_t, #arg);
+            },
+            ast::Type::BoxedBasic(_) => quote! {
+                #try_biplate_to!(#unbox #ident, #to_t, #arg);
+            },
+            ast::Type::Tuple(tuple_type) | ast::Type::BoxedTuple(tuple_type) => {
+                let tuple_field_idents: Vec<_> = (0..tuple_type.n)
+                    .map(|i| format_ident!("_{}_tuple_field_{i}", ident))
+                    .collect();
+                let tuple = match typ {
+                    ast::Type::BoxedTuple(_) => quote!(#unbox #ident),
+                    _ => quote!(#ident),
+                };
+                quote! {
+                    let (#(#tuple_field_idents),*) = #tuple;
+                    #(#try_biplate_to!(#tuple_field_idents, #to_t, #arg);)*
+                }
+            }
+        })
+        .collect()
+}
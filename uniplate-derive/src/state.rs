@@ -23,6 +23,12 @@ pub struct ParserState {
 
     /// Instances generated
     pub instances_generated: VecDeque<ast::InstanceMeta>,
+
+    /// Whether to derive borrowing instances alongside each instance.
+    pub by_ref: bool,
+
+    /// Whether to derive mutable instances alongside each instance.
+    pub by_mut: bool,
 }
 
 impl ParserState {
@@ -32,6 +38,16 @@ impl ParserState {
 
         let mut instances_to_generate: VecDeque<ast::InstanceMeta> = inp.instance_metadata.into();
 
+        let by_ref = instances_to_generate.iter().any(|instance| match instance {
+            ast::InstanceMeta::Uniplate(uniplate) => uniplate.by_ref,
+            ast::InstanceMeta::Biplate(_) => false,
+        });
+
+        let by_mut = instances_to_generate.iter().any(|instance| match instance {
+            ast::InstanceMeta::Uniplate(uniplate) => uniplate.by_mut,
+            ast::InstanceMeta::Biplate(_) => false,
+        });
+
         // always generate Biplate<From,From>
         instances_to_generate.push_front(ast::InstanceMeta::Biplate(ast::BiplateInstanceMeta {
             to: from.clone(),
@@ -42,6 +58,8 @@ impl ParserState {
             to: None,
             instances_to_generate,
             instances_generated: Default::default(),
+            by_ref,
+            by_mut,
             from,
             data,
         }
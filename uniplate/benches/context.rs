@@ -1,6 +1,7 @@
 //! Benchmarks for `context`,`context_bi`
 
-use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
 use uniplate::{Biplate as _, Uniplate};
 
 #[derive(PartialEq, Eq, Clone, Uniplate)]
@@ -0,0 +1,59 @@
+//! Benchmarks for `Tree::list`
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::collections::VecDeque;
+use std::hint::black_box;
+use uniplate::Tree;
+
+/// `list` on the tree of a leaf, which has one child
+fn benchmark_list_one(c: &mut Criterion) {
+    c.bench_function("list one", |b| {
+        b.iter(|| list_and_rebuild(black_box(Tree::One(1))))
+    });
+}
+
+/// `list` on the tree of a binary node, such as `Add(Box<Expr>, Box<Expr>)`
+fn benchmark_list_binary(c: &mut Criterion) {
+    c.bench_function("list binary", |b| {
+        b.iter(|| {
+            list_and_rebuild(black_box(Tree::Many(VecDeque::from([
+                Tree::One(1),
+                Tree::One(2),
+            ]))))
+        })
+    });
+}
+
+/// `list` on a deeply nested tree, such as the biplate of a large struct
+fn benchmark_list_nested(c: &mut Criterion) {
+    let tree = generate_tree(3, 6);
+    c.bench_function("list nested", |b| {
+        b.iter(|| list_and_rebuild(black_box(tree.clone())))
+    });
+}
+
+// deterministically make a tree where every Many node has `arity` subtrees
+fn generate_tree(arity: usize, depth: usize) -> Tree<i32> {
+    if depth == 0 {
+        return Tree::One(depth as i32);
+    }
+
+    Tree::Many(
+        (0..arity)
+            .map(|_| generate_tree(arity, depth - 1))
+            .collect(),
+    )
+}
+
+fn list_and_rebuild(tree: Tree<i32>) -> Tree<i32> {
+    let (children, rebuild) = tree.list();
+    rebuild(black_box(children))
+}
+
+criterion_group!(
+    benches,
+    benchmark_list_one,
+    benchmark_list_binary,
+    benchmark_list_nested
+);
+criterion_main!(benches);
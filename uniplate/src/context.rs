@@ -0,0 +1,360 @@
+//! Contexts: values with a hole in them.
+//!
+//! A context is a value with one of its subterms taken out. Filling the hole with a new subterm
+//! gives back a value of the original type. The contexts of the children of a value are returned
+//! by [`hole_contexts`](crate::Uniplate::hole_contexts), and those of all its subterms by
+//! [`universe_contexts`](crate::Uniplate::universe_contexts).
+//!
+//! Unlike the functions returned by [`holes`](crate::Uniplate::holes) and
+//! [`contexts`](crate::Uniplate::contexts), [`Context`] and [`ContextBi`] can be cloned, stored
+//! and compared, and know where their hole is. This lets rewrite candidates be collected, ranked,
+//! and applied later:
+//!
+//! ```
+//! use uniplate::Uniplate;
+//! use uniplate::path::Path;
+//!
+//! #[derive(Clone, PartialEq, Eq, Debug, Uniplate)]
+//! enum Expr {
+//!     Val(i32),
+//!     Neg(Box<Expr>),
+//!     Add(Box<Expr>, Box<Expr>),
+//! }
+//!
+//! use Expr::*;
+//!
+//! // -(-1) + -(-2)
+//! let expr = Add(
+//!     Box::new(Neg(Box::new(Neg(Box::new(Val(1)))))),
+//!     Box::new(Neg(Box::new(Neg(Box::new(Val(2)))))),
+//! );
+//!
+//! // find all double negations, and remove the deepest one.
+//! let mut candidates: Vec<_> = expr
+//!     .universe_contexts()
+//!     .filter_map(|(node, ctx)| match node {
+//!         Neg(x) => match *x {
+//!             Neg(y) => Some((*y, ctx)),
+//!             _ => None,
+//!         },
+//!         _ => None,
+//!     })
+//!     .collect();
+//! candidates.sort_by_key(|(_, ctx)| ctx.depth());
+//!
+//! let (new_node, ctx) = candidates.pop().unwrap();
+//! assert_eq!(ctx.path(), Path::from([1]));
+//! assert_eq!(
+//!     ctx.fill(new_node),
+//!     Add(
+//!         Box::new(Neg(Box::new(Neg(Box::new(Val(1)))))),
+//!         Box::new(Val(2)),
+//!     )
+//! );
+//! ```
+//!
+//! Contexts share the path back to the root: only the root itself is stored, and the context of
+//! each node is just a reference to the context of its parent and an index. Keeping all contexts
+//! of a tree therefore only needs memory linear in its size.
+//!
+//! The cost is paid when a hole is filled. Its ancestors are found again by walking down from the
+//! root, cloning the children of each ancestor on the way. Filling a hole therefore takes time
+//! proportional to the total size of the subtrees of its ancestors. For a balanced tree this is
+//! about twice the size of the tree, but for a deep and narrow one, such as a long list, it is up
+//! to the size of the tree times the depth of the hole.
+
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use core::{fmt, mem};
+
+use crate::path::Path;
+use crate::{Biplate, Uniplate};
+
+/// A node that has children, as it was before any holes were filled.
+#[derive(PartialEq, Eq)]
+enum Frame<To, From> {
+    /// The root of a `Biplate` traversal.
+    Top(From),
+
+    /// The root of a `Uniplate` traversal.
+    Root(To),
+
+    /// The node at this position below the root.
+    ///
+    /// The node itself is not stored, so that frames do not hold copies of each other's
+    /// subtrees.
+    Node(Position<To, From>),
+}
+
+/// The position of a node within the root: its parent, and its index among the children of the
+/// parent.
+///
+/// This is `None` for the root of a `Uniplate` traversal. `Uniplate` traversals use `Infallible`
+/// as `From`, as they never have a [`Frame::Top`].
+struct Position<To, From>(Option<(Arc<Frame<To, From>>, usize)>);
+
+impl<To, From> Position<To, From> {
+    /// Returns the position of the `index`th child of `frame`.
+    fn below(frame: &Arc<Frame<To, From>>, index: usize) -> Position<To, From> {
+        Position(Some((Arc::clone(frame), index)))
+    }
+
+    /// Returns the root frame, and the indices of the path from it to this position.
+    ///
+    /// This is `None` for the root of a `Uniplate` traversal.
+    fn root_and_indices(&self) -> Option<(&Frame<To, From>, Vec<usize>)> {
+        let mut indices = Vec::new();
+        let mut position = self;
+        while let Some((frame, index)) = &position.0 {
+            indices.push(*index);
+            match &**frame {
+                Frame::Node(parent_position) => position = parent_position,
+                root => {
+                    indices.reverse();
+                    return Some((root, indices));
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the path from the root to this position.
+    fn path(&self) -> Path {
+        match self.root_and_indices() {
+            Some((_, indices)) => Path::from(indices),
+            None => Path::root(),
+        }
+    }
+
+    /// Returns the depth of this position: the length of its path.
+    fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut position = self;
+        while let Some((frame, _)) = &position.0 {
+            depth += 1;
+            match &**frame {
+                Frame::Node(parent_position) => position = parent_position,
+                _ => break,
+            }
+        }
+        depth
+    }
+}
+
+/// Fills the hole at `indices` below a root with `node`, given the children of the root.
+///
+/// The ancestors of the hole are found by walking down from the root, and then rebuilt on the way
+/// back up. Returns the new children of the root.
+fn fill_children<To: Uniplate>(
+    children: VecDeque<To>,
+    indices: &[usize],
+    node: To,
+) -> VecDeque<To> {
+    let Some((&last, parents)) = indices.split_last() else {
+        unreachable!("holes are below the root");
+    };
+
+    // the children of each ancestor of the hole, from the root down.
+    let mut levels = Vec::with_capacity(parents.len());
+    let mut children = children;
+    for &index in parents {
+        let grandchildren = children[index].children();
+        levels.push(mem::replace(&mut children, grandchildren));
+    }
+
+    children[last] = node;
+    for (mut level, &index) in levels.into_iter().rev().zip(parents.iter().rev()) {
+        level[index] = level[index].rebuild(&mut children.into_iter());
+        children = level;
+    }
+    children
+}
+
+impl<To, From> Clone for Position<To, From> {
+    fn clone(&self) -> Self {
+        Position(self.0.clone())
+    }
+}
+
+impl<To: PartialEq, From: PartialEq> PartialEq for Position<To, From> {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (None, None) => true,
+            (Some((frame, index)), Some((other_frame, other_index))) => {
+                index == other_index && (Arc::ptr_eq(frame, other_frame) || frame == other_frame)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<To: Eq, From: Eq> Eq for Position<To, From> {}
+
+/// A value of type `T` with a hole in it, where a subterm of type `T` was.
+///
+/// Two contexts are equal if their holes are at the same path, and they have equal ancestors.
+///
+/// See the module-level documentation.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Context<T: Uniplate> {
+    position: Position<T, Infallible>,
+}
+
+impl<T: Uniplate> Context<T> {
+    /// Fills the hole with `node`, returning the new root.
+    ///
+    /// This clones the children of every ancestor of the hole: see the module-level
+    /// documentation.
+    pub fn fill(&self, node: T) -> T {
+        let (root, indices) = match self.position.root_and_indices() {
+            // the hole is the root itself.
+            None => return node,
+            Some((Frame::Root(root), indices)) => (root, indices),
+            Some(_) => unreachable!("positions in a Uniplate traversal are below a Frame::Root"),
+        };
+        let children = fill_children(root.children(), &indices, node);
+        root.rebuild(&mut children.into_iter())
+    }
+
+    /// Returns the path from the root to the hole.
+    pub fn path(&self) -> Path {
+        self.position.path()
+    }
+
+    /// Returns the depth of the hole from the root.
+    pub fn depth(&self) -> usize {
+        self.position.depth()
+    }
+}
+
+impl<T: Uniplate> fmt::Debug for Context<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Context")
+            .field("path", &self.path())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A value of type `From` with a hole in it, where a subterm of type `To` was.
+///
+/// As for [`ZipperBi`](crate::zipper::ZipperBi), the first index of the path of the hole is into
+/// [`children_bi`](Biplate::children_bi) of the root, so its depth is at least one.
+///
+/// Two contexts are equal if their holes are at the same path, and they have equal ancestors.
+///
+/// See the module-level documentation.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ContextBi<To: Uniplate, From: Biplate<To>> {
+    position: Position<To, From>,
+}
+
+impl<To: Uniplate, From: Biplate<To>> ContextBi<To, From> {
+    /// Fills the hole with `node`, returning the new root.
+    ///
+    /// This clones the children of every ancestor of the hole: see the module-level
+    /// documentation.
+    pub fn fill(&self, node: To) -> From {
+        let Some((Frame::Top(root), indices)) = self.position.root_and_indices() else {
+            unreachable!("positions in a Biplate traversal are below a Frame::Top");
+        };
+        let children = fill_children(root.children_bi(), &indices, node);
+        root.rebuild_bi(&mut children.into_iter())
+    }
+
+    /// Returns the path from the root to the hole.
+    pub fn path(&self) -> Path {
+        self.position.path()
+    }
+
+    /// Returns the depth of the hole from the root.
+    pub fn depth(&self) -> usize {
+        self.position.depth()
+    }
+}
+
+impl<To: Uniplate, From: Biplate<To>> fmt::Debug for ContextBi<To, From> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContextBi")
+            .field("path", &self.path())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the children of `node`, with their contexts.
+pub(crate) fn hole_contexts<T: Uniplate>(node: T) -> impl Iterator<Item = (T, Context<T>)> {
+    let children = node.children();
+    let frame = Arc::new(Frame::Root(node));
+    children.into_iter().enumerate().map(move |(i, child)| {
+        let position = Position::below(&frame, i);
+        (child, Context { position })
+    })
+}
+
+/// Returns the children of type `To` of `node`, with their contexts.
+pub(crate) fn hole_contexts_bi<To: Uniplate, From: Biplate<To>>(
+    node: From,
+) -> impl Iterator<Item = (To, ContextBi<To, From>)> {
+    let children = node.children_bi();
+    let frame = Arc::new(Frame::Top(node));
+    children.into_iter().enumerate().map(move |(i, child)| {
+        let position = Position::below(&frame, i);
+        (child, ContextBi { position })
+    })
+}
+
+/// Returns the universe of `root` in pre-order, with the context of each node.
+pub(crate) fn universe_contexts<T: Uniplate>(root: T) -> impl Iterator<Item = (T, Context<T>)> {
+    ContextIter {
+        stack: vec![(root, Position(None))],
+    }
+    .map(|(node, position)| (node, Context { position }))
+}
+
+/// Returns the universe of type `To` of `root` in pre-order, with the context of each node.
+pub(crate) fn universe_contexts_bi<To: Uniplate, From: Biplate<To>>(
+    root: From,
+) -> impl Iterator<Item = (To, ContextBi<To, From>)> {
+    let children = root.children_bi();
+    let mut stack = Vec::with_capacity(children.len());
+    push_children(&mut stack, children, Arc::new(Frame::Top(root)));
+    ContextIter { stack }.map(|(node, position)| (node, ContextBi { position }))
+}
+
+/// Yields each node in pre-order, with its position.
+struct ContextIter<To, From> {
+    /// The nodes still to visit, next node last.
+    stack: Vec<(To, Position<To, From>)>,
+}
+
+/// Pushes `children` onto `stack` in reverse order, with their positions below `frame`.
+fn push_children<To, From>(
+    stack: &mut Vec<(To, Position<To, From>)>,
+    children: VecDeque<To>,
+    frame: Arc<Frame<To, From>>,
+) {
+    let children = children.into_iter().enumerate().rev();
+    stack.extend(children.map(|(i, child)| (child, Position::below(&frame, i))));
+}
+
+impl<To: Uniplate, From> Iterator for ContextIter<To, From> {
+    type Item = (To, Position<To, From>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, position) = self.stack.pop()?;
+
+        let children = node.children();
+        if !children.is_empty() {
+            // only the root is stored: other nodes are found from their position when needed.
+            let frame = match position.0 {
+                None => Frame::Root(node.clone()),
+                Some(_) => Frame::Node(position.clone()),
+            };
+            push_children(&mut self.stack, children, Arc::new(frame));
+        }
+
+        Some((node, position))
+    }
+}
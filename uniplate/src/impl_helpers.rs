@@ -1,12 +1,48 @@
 //! Helper functions for manually implementing Uniplate and Biplate instances.
 
+// Used by macro-generated code, which may be in a `no_std` crate.
+#[doc(hidden)]
+pub use alloc::{boxed::Box, collections::VecDeque, vec::Vec};
+
+use crate::{Biplate, Tree, Uniplate};
+
+/// Implements [`Uniplate::uniplate`] using [`children_into`](Uniplate::children_into) and
+/// [`rebuild`](Uniplate::rebuild).
+///
+/// Only use this in instances that implement both of those methods: their default
+/// implementations call `uniplate`, so this would recurse forever.
+#[allow(clippy::type_complexity)]
+pub fn uniplate_via_rebuild<T: Uniplate>(node: &T) -> (Tree<T>, Box<dyn Fn(Tree<T>) -> T>) {
+    let node2 = node.clone();
+    (
+        node.children().into_iter().collect(),
+        Box::new(move |tree| node2.rebuild(&mut tree.into_iter())),
+    )
+}
+
+/// Implements [`Biplate::biplate`] using [`children_bi_into`](Biplate::children_bi_into) and
+/// [`rebuild_bi`](Biplate::rebuild_bi).
+///
+/// Only use this in instances that implement both of those methods: their default
+/// implementations call `biplate`, so this would recurse forever.
+#[allow(clippy::type_complexity)]
+pub fn biplate_via_rebuild<To: Uniplate, From: Biplate<To>>(
+    node: &From,
+) -> (Tree<To>, Box<dyn Fn(Tree<To>) -> From>) {
+    let node2 = node.clone();
+    (
+        node.children_bi().into_iter().collect(),
+        Box::new(move |tree| node2.rebuild_bi(&mut tree.into_iter())),
+    )
+}
+
 /// If `T` and `U` are the same type, turns a `&T` into a `&U`. Otherwise, returns `None`.
 #[inline(always)]
 pub fn transmute_if_same_type<T: 'static, U: 'static>(src: &T) -> Option<&U> {
-    if std::any::TypeId::of::<T>() == std::any::TypeId::of::<U>() {
+    if core::any::TypeId::of::<T>() == core::any::TypeId::of::<U>() {
         unsafe {
             // SAFETY: already checked that T and U are the same type
-            Some(std::mem::transmute::<&T, &U>(src))
+            Some(core::mem::transmute::<&T, &U>(src))
         }
     } else {
         None
@@ -20,12 +56,39 @@ pub fn transmute_if_same_type<T: 'static, U: 'static>(src: &T) -> Option<&U> {
 /// If `T` and `U` are not the same type.
 #[inline(always)]
 pub fn try_transmute_if_same_type<T: 'static, U: 'static>(src: &T) -> &U {
-    if std::any::TypeId::of::<T>() == std::any::TypeId::of::<U>() {
+    if core::any::TypeId::of::<T>() == core::any::TypeId::of::<U>() {
         unsafe {
             // SAFETY: already checked that T and U are the same type
-            std::mem::transmute::<&T, &U>(src)
+            core::mem::transmute::<&T, &U>(src)
         }
     } else {
         panic!("T and U are not the same type");
     }
 }
+
+/// If `T` and `U` are the same type, turns a `&mut T` into a `&mut U`. Otherwise, returns `None`.
+#[inline(always)]
+pub fn transmute_if_same_type_mut<T: 'static, U: 'static>(src: &mut T) -> Option<&mut U> {
+    if core::any::TypeId::of::<T>() == core::any::TypeId::of::<U>() {
+        unsafe {
+            // SAFETY: already checked that T and U are the same type
+            Some(core::mem::transmute::<&mut T, &mut U>(src))
+        }
+    } else {
+        None
+    }
+}
+
+/// If `T` and `U` are the same type, turns a `T` into a `U`. Otherwise, returns `src` unchanged.
+#[inline(always)]
+pub fn cast_if_same_type<T: 'static, U: 'static>(src: T) -> Result<U, T> {
+    if core::any::TypeId::of::<T>() == core::any::TypeId::of::<U>() {
+        let src = core::mem::ManuallyDrop::new(src);
+        unsafe {
+            // SAFETY: already checked that T and U are the same type, and src is not dropped
+            Ok(core::mem::transmute_copy::<T, U>(&src))
+        }
+    } else {
+        Err(src)
+    }
+}
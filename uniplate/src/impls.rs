@@ -7,11 +7,16 @@
 // NOTE (niklasdewally): my assumption is that we can do all this here, and that llvm will inline
 // this and/or devirtualise the Box<dyn Fn()> when necessary to make this fast.
 // https://users.rust-lang.org/t/why-box-dyn-fn-is-the-same-fast-as-normal-fn/96392
+//
+// Most of these instances now implement the closure-free `children_into` and `rebuild` methods
+// instead, so no longer allocate a context at all. The tuple instances still use closures.
 
 mod option;
 mod tuple;
 
-use std::collections::VecDeque;
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
 
 use crate::derive_iter;
 use crate::derive_unplateable;
@@ -1,23 +1,37 @@
 //! Default Uniplate impl for Option<T>
 
 use crate::Biplate;
+use crate::BiplateMut;
+use crate::BiplateRef;
 use crate::Tree;
 use crate::Uniplate;
+use crate::UniplateMut;
+use crate::UniplateRef;
+use crate::impl_helpers::{
+    biplate_via_rebuild, cast_if_same_type, transmute_if_same_type, transmute_if_same_type_mut,
+    uniplate_via_rebuild,
+};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
 
 impl<T> Uniplate for Option<T>
 where
     T: Uniplate + Biplate<Option<T>>,
 {
-    fn uniplate(&self) -> (crate::Tree<Self>, Box<dyn Fn(crate::Tree<Self>) -> Self>) {
-        match self {
-            Some(x) => {
-                let (tree, ctx) = <T as Biplate<Option<T>>>::biplate(x);
-
-                (tree, Box::new(move |x| Some(ctx(x))))
-            }
-            None => (Tree::Zero, Box::new(move |_| None)),
+    fn uniplate(&self) -> (Tree<Self>, Box<dyn Fn(Tree<Self>) -> Self>) {
+        uniplate_via_rebuild(self)
+    }
+
+    fn children_into(&self, out: &mut Vec<Self>) {
+        if let Some(x) = self {
+            <T as Biplate<Option<T>>>::children_bi_into(x, out);
         }
     }
+
+    fn rebuild(&self, children: &mut impl Iterator<Item = Self>) -> Self {
+        self.as_ref()
+            .map(|x| <T as Biplate<Option<T>>>::rebuild_bi(x, children))
+    }
 }
 
 impl<From, To> Biplate<To> for Option<From>
@@ -26,40 +40,104 @@ where
     From: Uniplate + Biplate<Option<From>> + Biplate<To>,
 {
     fn biplate(&self) -> (Tree<To>, Box<dyn Fn(Tree<To>) -> Self>) {
-        if std::any::TypeId::of::<To>() == std::any::TypeId::of::<Option<From>>() {
-            unsafe {
-                // Convert self: Option<From> to self: To, and return self.
-                // SAFETY: checked the types above.
-                let self_as_to: &To = std::mem::transmute(self);
-                (
-                    Tree::One(self_as_to.clone()),
-                    Box::new(move |x| {
-                        let Tree::One(x) = x else {
-                            panic!();
-                        };
-
-                        let x_as_option_from: &Option<From> = std::mem::transmute(&x);
-                        x_as_option_from.clone()
-                    }),
-                )
-            }
+        biplate_via_rebuild(self)
+    }
+
+    fn children_bi_into(&self, out: &mut Vec<To>) {
+        if let Some(self_as_to) = transmute_if_same_type::<Self, To>(self) {
+            out.push(self_as_to.clone());
+        } else if let Some(x) = self {
+            <From as Biplate<To>>::children_bi_into(x, out);
+        }
+    }
+
+    fn rebuild_bi(&self, children: &mut impl Iterator<Item = To>) -> Self {
+        if transmute_if_same_type::<Self, To>(self).is_some() {
+            let child = children
+                .next()
+                .expect("rebuild_bi() given too few children");
+            cast_if_same_type::<To, Self>(child).unwrap_or_else(|_| unreachable!())
         } else {
-            match self {
-                Some(x) => {
-                    let (tree, ctx) = <From as Biplate<To>>::biplate(x);
-                    (tree, Box::new(move |x| Some(ctx(x))))
-                }
-                None => (Tree::Zero, Box::new(move |_| None)),
-            }
+            self.as_ref()
+                .map(|x| <From as Biplate<To>>::rebuild_bi(x, children))
+        }
+    }
+}
+
+impl<T> UniplateRef for Option<T>
+where
+    T: UniplateRef + BiplateRef<Option<T>>,
+{
+    fn for_each_child<'a>(&'a self, f: &mut impl FnMut(&'a Self)) {
+        if let Some(x) = self {
+            <T as BiplateRef<Option<T>>>::for_each_child_bi(x, f);
+        }
+    }
+}
+
+impl<From, To> BiplateRef<To> for Option<From>
+where
+    To: UniplateRef,
+    From: UniplateRef + BiplateRef<Option<From>> + BiplateRef<To>,
+{
+    fn for_each_child_bi<'a>(&'a self, f: &mut impl FnMut(&'a To)) {
+        if let Some(self_as_to) = transmute_if_same_type::<Self, To>(self) {
+            f(self_as_to);
+        } else if let Some(x) = self {
+            <From as BiplateRef<To>>::for_each_child_bi(x, f);
+        }
+    }
+}
+
+impl<T> UniplateMut for Option<T>
+where
+    T: UniplateMut + BiplateMut<Option<T>>,
+{
+    fn for_each_child_mut(&mut self, f: &mut impl FnMut(&mut Self)) {
+        if let Some(x) = self {
+            <T as BiplateMut<Option<T>>>::for_each_child_bi_mut(x, f);
+        }
+    }
+}
+
+impl<From, To> BiplateMut<To> for Option<From>
+where
+    To: UniplateMut,
+    From: UniplateMut + BiplateMut<Option<From>> + BiplateMut<To>,
+{
+    fn for_each_child_bi_mut(&mut self, f: &mut impl FnMut(&mut To)) {
+        if let Some(self_as_to) = transmute_if_same_type_mut::<Self, To>(self) {
+            f(self_as_to);
+        } else if let Some(x) = self {
+            <From as BiplateMut<To>>::for_each_child_bi_mut(x, f);
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::VecDeque;
+    use alloc::collections::VecDeque;
 
     use crate::Biplate as _;
+    use crate::BiplateMut;
+    use crate::BiplateRef;
+
+    #[test]
+    fn option_children_bi_ref_test() {
+        let expr = Some(10);
+        assert_eq!(BiplateRef::<i32>::children_bi_ref(&expr), vec![&10]);
+        assert_eq!(
+            BiplateRef::<i32>::children_bi_ref(&None::<i32>),
+            Vec::<&i32>::new()
+        );
+    }
+
+    #[test]
+    fn option_transform_bi_mut_test() {
+        let mut expr = Some(10);
+        BiplateMut::<i32>::transform_bi_mut(&mut expr, &mut |x| *x += 1);
+        assert_eq!(expr, Some(11));
+    }
 
     #[test]
     fn option_with_children_bi_test() {
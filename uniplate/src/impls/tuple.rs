@@ -1,9 +1,17 @@
 //! Uniplate and Biplate instances for tuples
-use std::collections::VecDeque;
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
 
 use crate::Biplate;
+use crate::BiplateMut;
+use crate::BiplateRef;
 use crate::Tree;
 use crate::Uniplate;
+use crate::UniplateMut;
+use crate::UniplateRef;
+use crate::impl_helpers::{transmute_if_same_type, transmute_if_same_type_mut};
+use crate::try_biplate_mut_to;
+use crate::try_biplate_ref_to;
 use crate::try_biplate_to;
 
 impl<T: Uniplate + Biplate<(T, U)>, U: Uniplate + Biplate<(T, U)>> Uniplate for (T, U) {
@@ -34,11 +42,11 @@ impl<
 > Biplate<To> for (T, U)
 {
     fn biplate(&self) -> (Tree<To>, Box<dyn Fn(Tree<To>) -> Self>) {
-        if std::any::TypeId::of::<To>() == std::any::TypeId::of::<(T, U)>() {
+        if core::any::TypeId::of::<To>() == core::any::TypeId::of::<(T, U)>() {
             unsafe {
                 // Convert self: (T,U) to self: To, and return self.
                 // SAFETY: checked the types above.
-                let self_as_to: &To = std::mem::transmute(self);
+                let self_as_to: &To = core::mem::transmute(self);
                 (
                     Tree::One(self_as_to.clone()),
                     Box::new(move |x| {
@@ -46,7 +54,7 @@ impl<
                             panic!();
                         };
 
-                        let x_as_tuple: &(T, U) = std::mem::transmute(&x);
+                        let x_as_tuple: &(T, U) = core::mem::transmute(&x);
                         x_as_tuple.clone()
                     }),
                 )
@@ -108,11 +116,11 @@ impl<
 > Biplate<To> for (T, U, V)
 {
     fn biplate(&self) -> (Tree<To>, Box<dyn Fn(Tree<To>) -> Self>) {
-        if std::any::TypeId::of::<To>() == std::any::TypeId::of::<(T, U, V)>() {
+        if core::any::TypeId::of::<To>() == core::any::TypeId::of::<(T, U, V)>() {
             unsafe {
                 // Convert self: (T,U,V) to self: To, and return self.
                 // SAFETY: checked the types above.
-                let self_as_to: &To = std::mem::transmute(self);
+                let self_as_to: &To = core::mem::transmute(self);
                 (
                     Tree::One(self_as_to.clone()),
                     Box::new(move |x| {
@@ -120,7 +128,7 @@ impl<
                             panic!();
                         };
 
-                        let x_as_tuple: &(T, U, V) = std::mem::transmute(&x);
+                        let x_as_tuple: &(T, U, V) = core::mem::transmute(&x);
                         x_as_tuple.clone()
                     }),
                 )
@@ -188,11 +196,11 @@ impl<
 > Biplate<To> for (T, U, V, W)
 {
     fn biplate(&self) -> (Tree<To>, Box<dyn Fn(Tree<To>) -> Self>) {
-        if std::any::TypeId::of::<To>() == std::any::TypeId::of::<(T, U, V, W)>() {
+        if core::any::TypeId::of::<To>() == core::any::TypeId::of::<(T, U, V, W)>() {
             unsafe {
                 // Convert self: (T,U,V,W) to self: To, and return self.
                 // SAFETY: checked the types above.
-                let self_as_to: &To = std::mem::transmute(self);
+                let self_as_to: &To = core::mem::transmute(self);
                 (
                     Tree::One(self_as_to.clone()),
                     Box::new(move |x| {
@@ -200,7 +208,7 @@ impl<
                             panic!();
                         };
 
-                        let x_as_tuple: &(T, U, V, W) = std::mem::transmute(&x);
+                        let x_as_tuple: &(T, U, V, W) = core::mem::transmute(&x);
                         x_as_tuple.clone()
                     }),
                 )
@@ -274,11 +282,11 @@ impl<
 > Biplate<To> for (T, U, V, W, X)
 {
     fn biplate(&self) -> (Tree<To>, Box<dyn Fn(Tree<To>) -> Self>) {
-        if std::any::TypeId::of::<To>() == std::any::TypeId::of::<(T, U, V, W, X)>() {
+        if core::any::TypeId::of::<To>() == core::any::TypeId::of::<(T, U, V, W, X)>() {
             unsafe {
                 // Convert self: (T,U,V,W,X) to self: To, and return self.
                 // SAFETY: checked the types above.
-                let self_as_to: &To = std::mem::transmute(self);
+                let self_as_to: &To = core::mem::transmute(self);
                 (
                     Tree::One(self_as_to.clone()),
                     Box::new(move |x| {
@@ -286,7 +294,7 @@ impl<
                             panic!();
                         };
 
-                        let x_as_tuple: &(T, U, V, W, X) = std::mem::transmute(&x);
+                        let x_as_tuple: &(T, U, V, W, X) = core::mem::transmute(&x);
                         x_as_tuple.clone()
                     }),
                 )
@@ -317,3 +325,279 @@ impl<
         }
     }
 }
+
+impl<T: UniplateRef + BiplateRef<(T, U)>, U: UniplateRef + BiplateRef<(T, U)>> UniplateRef
+    for (T, U)
+{
+    fn for_each_child<'a>(&'a self, f: &mut impl FnMut(&'a Self)) {
+        let (t, u) = self;
+        try_biplate_ref_to!(t, (T, U), f);
+        try_biplate_ref_to!(u, (T, U), f);
+    }
+}
+
+impl<
+    T: UniplateRef + BiplateRef<To> + BiplateRef<(T, U)>,
+    U: UniplateRef + BiplateRef<To> + BiplateRef<(T, U)>,
+    To: UniplateRef,
+> BiplateRef<To> for (T, U)
+{
+    fn for_each_child_bi<'a>(&'a self, f: &mut impl FnMut(&'a To)) {
+        if let Some(self_as_to) = transmute_if_same_type::<Self, To>(self) {
+            f(self_as_to);
+        } else {
+            let (t, u) = self;
+            try_biplate_ref_to!(t, To, f);
+            try_biplate_ref_to!(u, To, f);
+        }
+    }
+}
+
+impl<
+    T: UniplateRef + BiplateRef<(T, U, V)>,
+    U: UniplateRef + BiplateRef<(T, U, V)>,
+    V: UniplateRef + BiplateRef<(T, U, V)>,
+> UniplateRef for (T, U, V)
+{
+    fn for_each_child<'a>(&'a self, f: &mut impl FnMut(&'a Self)) {
+        let (t, u, v) = self;
+        try_biplate_ref_to!(t, (T, U, V), f);
+        try_biplate_ref_to!(u, (T, U, V), f);
+        try_biplate_ref_to!(v, (T, U, V), f);
+    }
+}
+
+impl<
+    T: UniplateRef + BiplateRef<To> + BiplateRef<(T, U, V)>,
+    U: UniplateRef + BiplateRef<To> + BiplateRef<(T, U, V)>,
+    V: UniplateRef + BiplateRef<To> + BiplateRef<(T, U, V)>,
+    To: UniplateRef,
+> BiplateRef<To> for (T, U, V)
+{
+    fn for_each_child_bi<'a>(&'a self, f: &mut impl FnMut(&'a To)) {
+        if let Some(self_as_to) = transmute_if_same_type::<Self, To>(self) {
+            f(self_as_to);
+        } else {
+            let (t, u, v) = self;
+            try_biplate_ref_to!(t, To, f);
+            try_biplate_ref_to!(u, To, f);
+            try_biplate_ref_to!(v, To, f);
+        }
+    }
+}
+
+impl<
+    T: UniplateRef + BiplateRef<(T, U, V, W)>,
+    U: UniplateRef + BiplateRef<(T, U, V, W)>,
+    V: UniplateRef + BiplateRef<(T, U, V, W)>,
+    W: UniplateRef + BiplateRef<(T, U, V, W)>,
+> UniplateRef for (T, U, V, W)
+{
+    fn for_each_child<'a>(&'a self, f: &mut impl FnMut(&'a Self)) {
+        let (t, u, v, w) = self;
+        try_biplate_ref_to!(t, (T, U, V, W), f);
+        try_biplate_ref_to!(u, (T, U, V, W), f);
+        try_biplate_ref_to!(v, (T, U, V, W), f);
+        try_biplate_ref_to!(w, (T, U, V, W), f);
+    }
+}
+
+impl<
+    T: UniplateRef + BiplateRef<To> + BiplateRef<(T, U, V, W)>,
+    U: UniplateRef + BiplateRef<To> + BiplateRef<(T, U, V, W)>,
+    V: UniplateRef + BiplateRef<To> + BiplateRef<(T, U, V, W)>,
+    W: UniplateRef + BiplateRef<To> + BiplateRef<(T, U, V, W)>,
+    To: UniplateRef,
+> BiplateRef<To> for (T, U, V, W)
+{
+    fn for_each_child_bi<'a>(&'a self, f: &mut impl FnMut(&'a To)) {
+        if let Some(self_as_to) = transmute_if_same_type::<Self, To>(self) {
+            f(self_as_to);
+        } else {
+            let (t, u, v, w) = self;
+            try_biplate_ref_to!(t, To, f);
+            try_biplate_ref_to!(u, To, f);
+            try_biplate_ref_to!(v, To, f);
+            try_biplate_ref_to!(w, To, f);
+        }
+    }
+}
+
+impl<
+    T: UniplateRef + BiplateRef<(T, U, V, W, X)>,
+    U: UniplateRef + BiplateRef<(T, U, V, W, X)>,
+    V: UniplateRef + BiplateRef<(T, U, V, W, X)>,
+    W: UniplateRef + BiplateRef<(T, U, V, W, X)>,
+    X: UniplateRef + BiplateRef<(T, U, V, W, X)>,
+> UniplateRef for (T, U, V, W, X)
+{
+    fn for_each_child<'a>(&'a self, f: &mut impl FnMut(&'a Self)) {
+        let (t, u, v, w, x) = self;
+        try_biplate_ref_to!(t, (T, U, V, W, X), f);
+        try_biplate_ref_to!(u, (T, U, V, W, X), f);
+        try_biplate_ref_to!(v, (T, U, V, W, X), f);
+        try_biplate_ref_to!(w, (T, U, V, W, X), f);
+        try_biplate_ref_to!(x, (T, U, V, W, X), f);
+    }
+}
+
+impl<
+    T: UniplateRef + BiplateRef<To> + BiplateRef<(T, U, V, W, X)>,
+    U: UniplateRef + BiplateRef<To> + BiplateRef<(T, U, V, W, X)>,
+    V: UniplateRef + BiplateRef<To> + BiplateRef<(T, U, V, W, X)>,
+    W: UniplateRef + BiplateRef<To> + BiplateRef<(T, U, V, W, X)>,
+    X: UniplateRef + BiplateRef<To> + BiplateRef<(T, U, V, W, X)>,
+    To: UniplateRef,
+> BiplateRef<To> for (T, U, V, W, X)
+{
+    fn for_each_child_bi<'a>(&'a self, f: &mut impl FnMut(&'a To)) {
+        if let Some(self_as_to) = transmute_if_same_type::<Self, To>(self) {
+            f(self_as_to);
+        } else {
+            let (t, u, v, w, x) = self;
+            try_biplate_ref_to!(t, To, f);
+            try_biplate_ref_to!(u, To, f);
+            try_biplate_ref_to!(v, To, f);
+            try_biplate_ref_to!(w, To, f);
+            try_biplate_ref_to!(x, To, f);
+        }
+    }
+}
+
+impl<T: UniplateMut + BiplateMut<(T, U)>, U: UniplateMut + BiplateMut<(T, U)>> UniplateMut
+    for (T, U)
+{
+    fn for_each_child_mut(&mut self, f: &mut impl FnMut(&mut Self)) {
+        let (t, u) = self;
+        try_biplate_mut_to!(t, (T, U), f);
+        try_biplate_mut_to!(u, (T, U), f);
+    }
+}
+
+impl<
+    T: UniplateMut + BiplateMut<To> + BiplateMut<(T, U)>,
+    U: UniplateMut + BiplateMut<To> + BiplateMut<(T, U)>,
+    To: UniplateMut,
+> BiplateMut<To> for (T, U)
+{
+    fn for_each_child_bi_mut(&mut self, f: &mut impl FnMut(&mut To)) {
+        if let Some(self_as_to) = transmute_if_same_type_mut::<Self, To>(self) {
+            f(self_as_to);
+        } else {
+            let (t, u) = self;
+            try_biplate_mut_to!(t, To, f);
+            try_biplate_mut_to!(u, To, f);
+        }
+    }
+}
+
+impl<
+    T: UniplateMut + BiplateMut<(T, U, V)>,
+    U: UniplateMut + BiplateMut<(T, U, V)>,
+    V: UniplateMut + BiplateMut<(T, U, V)>,
+> UniplateMut for (T, U, V)
+{
+    fn for_each_child_mut(&mut self, f: &mut impl FnMut(&mut Self)) {
+        let (t, u, v) = self;
+        try_biplate_mut_to!(t, (T, U, V), f);
+        try_biplate_mut_to!(u, (T, U, V), f);
+        try_biplate_mut_to!(v, (T, U, V), f);
+    }
+}
+
+impl<
+    T: UniplateMut + BiplateMut<To> + BiplateMut<(T, U, V)>,
+    U: UniplateMut + BiplateMut<To> + BiplateMut<(T, U, V)>,
+    V: UniplateMut + BiplateMut<To> + BiplateMut<(T, U, V)>,
+    To: UniplateMut,
+> BiplateMut<To> for (T, U, V)
+{
+    fn for_each_child_bi_mut(&mut self, f: &mut impl FnMut(&mut To)) {
+        if let Some(self_as_to) = transmute_if_same_type_mut::<Self, To>(self) {
+            f(self_as_to);
+        } else {
+            let (t, u, v) = self;
+            try_biplate_mut_to!(t, To, f);
+            try_biplate_mut_to!(u, To, f);
+            try_biplate_mut_to!(v, To, f);
+        }
+    }
+}
+
+impl<
+    T: UniplateMut + BiplateMut<(T, U, V, W)>,
+    U: UniplateMut + BiplateMut<(T, U, V, W)>,
+    V: UniplateMut + BiplateMut<(T, U, V, W)>,
+    W: UniplateMut + BiplateMut<(T, U, V, W)>,
+> UniplateMut for (T, U, V, W)
+{
+    fn for_each_child_mut(&mut self, f: &mut impl FnMut(&mut Self)) {
+        let (t, u, v, w) = self;
+        try_biplate_mut_to!(t, (T, U, V, W), f);
+        try_biplate_mut_to!(u, (T, U, V, W), f);
+        try_biplate_mut_to!(v, (T, U, V, W), f);
+        try_biplate_mut_to!(w, (T, U, V, W), f);
+    }
+}
+
+impl<
+    T: UniplateMut + BiplateMut<To> + BiplateMut<(T, U, V, W)>,
+    U: UniplateMut + BiplateMut<To> + BiplateMut<(T, U, V, W)>,
+    V: UniplateMut + BiplateMut<To> + BiplateMut<(T, U, V, W)>,
+    W: UniplateMut + BiplateMut<To> + BiplateMut<(T, U, V, W)>,
+    To: UniplateMut,
+> BiplateMut<To> for (T, U, V, W)
+{
+    fn for_each_child_bi_mut(&mut self, f: &mut impl FnMut(&mut To)) {
+        if let Some(self_as_to) = transmute_if_same_type_mut::<Self, To>(self) {
+            f(self_as_to);
+        } else {
+            let (t, u, v, w) = self;
+            try_biplate_mut_to!(t, To, f);
+            try_biplate_mut_to!(u, To, f);
+            try_biplate_mut_to!(v, To, f);
+            try_biplate_mut_to!(w, To, f);
+        }
+    }
+}
+
+impl<
+    T: UniplateMut + BiplateMut<(T, U, V, W, X)>,
+    U: UniplateMut + BiplateMut<(T, U, V, W, X)>,
+    V: UniplateMut + BiplateMut<(T, U, V, W, X)>,
+    W: UniplateMut + BiplateMut<(T, U, V, W, X)>,
+    X: UniplateMut + BiplateMut<(T, U, V, W, X)>,
+> UniplateMut for (T, U, V, W, X)
+{
+    fn for_each_child_mut(&mut self, f: &mut impl FnMut(&mut Self)) {
+        let (t, u, v, w, x) = self;
+        try_biplate_mut_to!(t, (T, U, V, W, X), f);
+        try_biplate_mut_to!(u, (T, U, V, W, X), f);
+        try_biplate_mut_to!(v, (T, U, V, W, X), f);
+        try_biplate_mut_to!(w, (T, U, V, W, X), f);
+        try_biplate_mut_to!(x, (T, U, V, W, X), f);
+    }
+}
+
+impl<
+    T: UniplateMut + BiplateMut<To> + BiplateMut<(T, U, V, W, X)>,
+    U: UniplateMut + BiplateMut<To> + BiplateMut<(T, U, V, W, X)>,
+    V: UniplateMut + BiplateMut<To> + BiplateMut<(T, U, V, W, X)>,
+    W: UniplateMut + BiplateMut<To> + BiplateMut<(T, U, V, W, X)>,
+    X: UniplateMut + BiplateMut<To> + BiplateMut<(T, U, V, W, X)>,
+    To: UniplateMut,
+> BiplateMut<To> for (T, U, V, W, X)
+{
+    fn for_each_child_bi_mut(&mut self, f: &mut impl FnMut(&mut To)) {
+        if let Some(self_as_to) = transmute_if_same_type_mut::<Self, To>(self) {
+            f(self_as_to);
+        } else {
+            let (t, u, v, w, x) = self;
+            try_biplate_mut_to!(t, To, f);
+            try_biplate_mut_to!(u, To, f);
+            try_biplate_mut_to!(v, To, f);
+            try_biplate_mut_to!(w, To, f);
+            try_biplate_mut_to!(x, To, f);
+        }
+    }
+}
@@ -1,5 +1,8 @@
 #![doc = include_str!("intro.md")]
 #![warn(missing_docs)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 #[doc(hidden)]
 extern crate self as uniplate;
@@ -7,16 +10,25 @@ extern crate self as uniplate;
 #[doc(hidden)]
 pub mod impls;
 
+pub mod context;
+pub mod limits;
+pub mod observer;
+pub mod path;
+pub mod rules;
 pub mod spez;
+pub mod strategy;
 pub mod tagged_zipper;
 pub mod zipper;
 
 pub mod impl_helpers;
 mod traits;
 
-mod tree;
+pub mod tree;
 
-pub use traits::{Biplate, Uniplate};
+pub use traits::{
+    Biplate, BiplateMut, BiplateRef, TransformControl, Transformed, TraversalOrder, Uniplate,
+    UniplateMut, UniplateRef, VisitControl,
+};
 
 pub use tree::Tree;
 
@@ -36,7 +48,8 @@ pub mod test_common;
 /// **See the top level crate documentation for usage details.**
 pub use uniplate_derive::Uniplate;
 
-/// Generates [`Biplate`] and [`Uniplate`] instances for an unplateable type.
+/// Generates [`Biplate`] and [`Uniplate`] instances (and their borrowing and mutable
+/// counterparts) for an unplateable type.
 ///
 /// An unplateable type is one that you don't want Uniplate to traverse inside of.
 ///
@@ -85,10 +98,15 @@ macro_rules! derive_unplateable {
                 &self,
             ) -> (
                 ::uniplate::Tree<Self>,
-                Box<dyn Fn(::uniplate::Tree<Self>) -> Self>,
+                ::uniplate::impl_helpers::Box<dyn Fn(::uniplate::Tree<Self>) -> Self>,
             ) {
-                let val = self.clone();
-                (::uniplate::Tree::Zero, Box::new(move |_| val.clone()))
+                ::uniplate::impl_helpers::uniplate_via_rebuild(self)
+            }
+
+            fn children_into(&self, _: &mut ::uniplate::impl_helpers::Vec<Self>) {}
+
+            fn rebuild(&self, _: &mut impl ::core::iter::Iterator<Item = Self>) -> Self {
+                self.clone()
             }
         }
 
@@ -97,18 +115,19 @@ macro_rules! derive_unplateable {
                 &self,
             ) -> (
                 ::uniplate::Tree<$t>,
-                Box<dyn Fn(::uniplate::Tree<$t>) -> $t>,
+                ::uniplate::impl_helpers::Box<dyn Fn(::uniplate::Tree<$t>) -> Self>,
             ) {
-                let val = self.clone();
-                (
-                    ::uniplate::Tree::One(val.clone()),
-                    Box::new(move |x| {
-                        let ::uniplate::Tree::One(x) = x else {
-                            panic!();
-                        };
-                        x
-                    }),
-                )
+                ::uniplate::impl_helpers::biplate_via_rebuild(self)
+            }
+
+            fn children_bi_into(&self, out: &mut ::uniplate::impl_helpers::Vec<$t>) {
+                out.push(self.clone());
+            }
+
+            fn rebuild_bi(&self, children: &mut impl ::core::iter::Iterator<Item = $t>) -> $t {
+                children
+                    .next()
+                    .expect("rebuild_bi() given too few children")
             }
         }
 
@@ -117,17 +136,50 @@ macro_rules! derive_unplateable {
                 &self,
             ) -> (
                 ::uniplate::Tree<Option<$t>>,
-                Box<dyn Fn(::uniplate::Tree<Option<$t>>) -> $t>,
+                ::uniplate::impl_helpers::Box<dyn Fn(::uniplate::Tree<Option<$t>>) -> Self>,
             ) {
-                let val = self.clone();
-                (::uniplate::Tree::Zero, Box::new(move |_| val.clone()))
+                ::uniplate::impl_helpers::biplate_via_rebuild(self)
+            }
+
+            fn children_bi_into(&self, _: &mut ::uniplate::impl_helpers::Vec<Option<$t>>) {}
+
+            fn rebuild_bi(&self, _: &mut impl ::core::iter::Iterator<Item = Option<$t>>) -> $t {
+                self.clone()
+            }
+        }
+
+        impl ::uniplate::UniplateRef for $t {
+            fn for_each_child<'a>(&'a self, _: &mut impl FnMut(&'a Self)) {}
+        }
+
+        impl ::uniplate::BiplateRef<$t> for $t {
+            fn for_each_child_bi<'a>(&'a self, f: &mut impl FnMut(&'a $t)) {
+                f(self)
             }
         }
+
+        impl ::uniplate::BiplateRef<Option<$t>> for $t {
+            fn for_each_child_bi<'a>(&'a self, _: &mut impl FnMut(&'a Option<$t>)) {}
+        }
+
+        impl ::uniplate::UniplateMut for $t {
+            fn for_each_child_mut(&mut self, _: &mut impl FnMut(&mut Self)) {}
+        }
+
+        impl ::uniplate::BiplateMut<$t> for $t {
+            fn for_each_child_bi_mut(&mut self, f: &mut impl FnMut(&mut $t)) {
+                f(self)
+            }
+        }
+
+        impl ::uniplate::BiplateMut<Option<$t>> for $t {
+            fn for_each_child_bi_mut(&mut self, _: &mut impl FnMut(&mut Option<$t>)) {}
+        }
     };
 }
 
-/// Generates [`Biplate`] and [`Uniplate`] instances for a collection using its [`Iterator`]
-/// implementation.
+/// Generates [`Biplate`] and [`Uniplate`] instances (and their borrowing and mutable
+/// counterparts) for a collection using its [`Iterator`] implementation.
 ///
 /// Children will be visited in the order returned by `.iter()`.
 #[macro_export]
@@ -142,91 +194,38 @@ macro_rules! derive_iter {
                 &self,
             ) -> (
                 ::uniplate::Tree<T>,
-                Box<dyn Fn(::uniplate::Tree<T>) -> $iter_ty<F>>,
+                ::uniplate::impl_helpers::Box<dyn Fn(::uniplate::Tree<T>) -> Self>,
             ) {
-                if (self.is_empty()) {
-                    let val = self.clone();
-                    return (::uniplate::Tree::Zero, Box::new(move |_| val.clone()));
-                }
+                ::uniplate::impl_helpers::biplate_via_rebuild(self)
+            }
 
-                // T == F: return all types F in the iterator.
-                if std::any::TypeId::of::<T>() == std::any::TypeId::of::<F>() {
-                    unsafe {
-                        // need to cast from F to T
-                        let children: ::uniplate::Tree<T> = ::uniplate::Tree::Many(
-                            self.clone()
-                                .into_iter()
-                                .map(|x: F| {
-                                    // possibly unsafe, definitely stupid, but seems to be the only way here?
-                                    let x: T = std::mem::transmute::<&F, &T>(&x).clone();
-                                    ::uniplate::Tree::One(x)
-                                })
-                                .collect(),
-                        );
-
-                        let ctx: Box<dyn Fn(::uniplate::Tree<T>) -> $iter_ty<F>> =
-                            Box::new(move |new_tree: ::uniplate::Tree<T>| {
-                                let ::uniplate::Tree::Many(xs) = new_tree else {
-                                    todo!();
-                                };
-                                xs.into_iter()
-                                    .map(|x| {
-                                        let ::uniplate::Tree::One(x) = x else {
-                                            todo!();
-                                        };
-                                        let x: F = std::mem::transmute::<&T, &F>(&x).clone();
-                                        x
-                                    })
-                                    .collect()
-                            });
-
-                        return (children, ctx);
-                    }
-                }
+            fn children_bi_into(&self, out: &mut ::uniplate::impl_helpers::Vec<T>) {
                 // Identity / same type case: Biplate<Iter<T>> for Iter<T>
-                else if std::any::TypeId::of::<T>() == std::any::TypeId::of::<$iter_ty<F>>() {
-                    unsafe {
-                        // need to cast from Iter<F> to T
-                        let val: T = std::mem::transmute::<&$iter_ty<F>, &T>(&self).clone();
-
-                        let children: ::uniplate::Tree<T> = ::uniplate::Tree::One(val);
-
-                        let ctx: Box<dyn Fn(::uniplate::Tree<T>) -> $iter_ty<F>> =
-                            Box::new(move |new_tree: ::uniplate::Tree<T>| {
-                                let ::uniplate::Tree::One(x) = new_tree else {
-                                    todo!();
-                                };
-                                // need to cast from T to Iter<F>
-                                let val: $iter_ty<F> =
-                                    std::mem::transmute::<&T, &$iter_ty<F>>(&x).clone();
-                                val
-                            });
-
-                        return (children, ctx);
-                    }
+                if let Some(this) =
+                    ::uniplate::impl_helpers::transmute_if_same_type::<Self, T>(self)
+                {
+                    out.push(this.clone());
+                    return;
                 }
 
-                // T != F: return all type T's contained in the type F's in the vector
-                let mut child_trees: VecDeque<::uniplate::Tree<T>> = VecDeque::new();
-                let mut child_ctxs: Vec<Box<dyn Fn(::uniplate::Tree<T>) -> F>> = Vec::new();
-                for item in self {
-                    let (tree, plate) = <F as ::uniplate::Biplate<T>>::biplate(item);
-                    child_trees.push_back(tree);
-                    child_ctxs.push(plate);
+                // Otherwise, return all the type T's contained in each element. If T == F, this
+                // returns the elements themselves.
+                for item in self.iter() {
+                    item.children_bi_into(out);
+                }
+            }
+
+            fn rebuild_bi(&self, children: &mut impl ::core::iter::Iterator<Item = T>) -> Self {
+                // Identity / same type case: Biplate<Iter<T>> for Iter<T>
+                if ::core::any::TypeId::of::<T>() == ::core::any::TypeId::of::<Self>() {
+                    let child = children
+                        .next()
+                        .expect("rebuild_bi() given too few children");
+                    return ::uniplate::impl_helpers::cast_if_same_type::<T, Self>(child)
+                        .unwrap_or_else(|_| ::core::unreachable!());
                 }
 
-                let tree = ::uniplate::Tree::Many(child_trees);
-                let ctx = Box::new(move |new_tree: ::uniplate::Tree<T>| {
-                    let mut out = Vec::<F>::new();
-                    let ::uniplate::Tree::Many(new_trees) = new_tree else {
-                        todo!()
-                    };
-                    for (child_tree, child_ctx) in std::iter::zip(new_trees, &child_ctxs) {
-                        out.push(child_ctx(child_tree));
-                    }
-                    out.into_iter().collect::<$iter_ty<F>>()
-                });
-                (tree, ctx)
+                self.iter().map(|item| item.rebuild_bi(children)).collect()
             }
         }
 
@@ -239,12 +238,75 @@ macro_rules! derive_iter {
                 &self,
             ) -> (
                 ::uniplate::Tree<Self>,
-                Box<dyn Fn(::uniplate::Tree<Self>) -> Self>,
+                ::uniplate::impl_helpers::Box<dyn Fn(::uniplate::Tree<Self>) -> Self>,
             ) {
-                let val = self.clone();
-                (::uniplate::Tree::Zero, Box::new(move |_| val.clone()))
+                ::uniplate::impl_helpers::uniplate_via_rebuild(self)
+            }
+
+            fn children_into(&self, _: &mut ::uniplate::impl_helpers::Vec<Self>) {}
+
+            fn rebuild(&self, _: &mut impl ::core::iter::Iterator<Item = Self>) -> Self {
+                self.clone()
+            }
+        }
+
+        impl<T, F> ::uniplate::BiplateRef<T> for $iter_ty<F>
+        where
+            T: ::uniplate::UniplateRef,
+            F: ::uniplate::BiplateRef<T>,
+        {
+            fn for_each_child_bi<'a>(&'a self, f: &mut impl FnMut(&'a T)) {
+                // Identity / same type case: BiplateRef<Iter<T>> for Iter<T>
+                if let Some(this) =
+                    ::uniplate::impl_helpers::transmute_if_same_type::<Self, T>(self)
+                {
+                    f(this);
+                    return;
+                }
+
+                // Otherwise, visit all the type T's contained in each element. If T == F, this
+                // visits the elements themselves.
+                for item in self.iter() {
+                    item.for_each_child_bi(f);
+                }
             }
         }
+
+        impl<T> ::uniplate::UniplateRef for $iter_ty<T>
+        where
+            T: ::uniplate::UniplateRef,
+        {
+            fn for_each_child<'a>(&'a self, _: &mut impl FnMut(&'a Self)) {}
+        }
+
+        impl<T, F> ::uniplate::BiplateMut<T> for $iter_ty<F>
+        where
+            T: ::uniplate::UniplateMut,
+            F: ::uniplate::BiplateMut<T>,
+        {
+            fn for_each_child_bi_mut(&mut self, f: &mut impl FnMut(&mut T)) {
+                // Identity / same type case: BiplateMut<Iter<T>> for Iter<T>
+                if let Some(this) =
+                    ::uniplate::impl_helpers::transmute_if_same_type_mut::<Self, T>(self)
+                {
+                    f(this);
+                    return;
+                }
+
+                // Otherwise, visit all the type T's contained in each element. If T == F, this
+                // visits the elements themselves.
+                for item in self.iter_mut() {
+                    item.for_each_child_bi_mut(f);
+                }
+            }
+        }
+
+        impl<T> ::uniplate::UniplateMut for $iter_ty<T>
+        where
+            T: ::uniplate::UniplateMut,
+        {
+            fn for_each_child_mut(&mut self, _: &mut impl FnMut(&mut Self)) {}
+        }
     };
 }
 
@@ -257,10 +319,15 @@ macro_rules! unreachable {
                 &self,
             ) -> (
                 ::uniplate::Tree<$to>,
-                Box<dyn Fn(::uniplate::Tree<$to>) -> $from>,
+                ::uniplate::impl_helpers::Box<dyn Fn(::uniplate::Tree<$to>) -> Self>,
             ) {
-                let val = self.clone();
-                (::uniplate::Tree::Zero, Box::new(move |_| val.clone()))
+                ::uniplate::impl_helpers::biplate_via_rebuild(self)
+            }
+
+            fn children_bi_into(&self, _: &mut ::uniplate::impl_helpers::Vec<$to>) {}
+
+            fn rebuild_bi(&self, _: &mut impl ::core::iter::Iterator<Item = $to>) -> $from {
+                self.clone()
             }
         }
     };
@@ -0,0 +1,426 @@
+//! Limits on rewriting.
+//!
+//! If a rule can be applied indefinitely, [`rewrite_fixpoint`](crate::Uniplate::rewrite_fixpoint)
+//! does not terminate. The `_with_limits` variants, such as
+//! [`rewrite_with_limits`](crate::Uniplate::rewrite_with_limits), instead stop once any of the
+//! given [`Limits`] is reached, returning the partially rewritten tree in a
+//! [`RewriteLimitExceeded`] error.
+//!
+//! These variants are implemented using [zippers](crate::zipper), so do not use the call stack to
+//! traverse the tree.
+//!
+//! # Example
+//!
+//! ```
+//! use std::sync::atomic::AtomicBool;
+//!
+//! use uniplate::Uniplate;
+//! use uniplate::limits::{Limit, Limits};
+//!
+//! #[derive(Clone, PartialEq, Eq, Debug, Uniplate)]
+//! enum Expr {
+//!     Val(i32),
+//!     Neg(Box<Expr>),
+//! }
+//!
+//! use Expr::*;
+//!
+//! // applies forever.
+//! let increment = |x: Expr| match x {
+//!     Val(n) => Some(Val(n + 1)),
+//!     _ => None,
+//! };
+//!
+//! let cancel = AtomicBool::new(false);
+//! let limits = Limits {
+//!     max_steps: Some(10),
+//!     cancel: Some(&cancel),
+//!     ..Default::default()
+//! };
+//!
+//! let err = Neg(Box::new(Val(0)))
+//!     .rewrite_with_limits(&increment, &limits)
+//!     .unwrap_err();
+//!
+//! assert_eq!(err.limit, Limit::Steps);
+//! assert_eq!(err.steps, 10);
+//! assert_eq!(err.partial_result, Neg(Box::new(Val(10))));
+//! ```
+
+use core::fmt;
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+/// Limits on a rewrite.
+///
+/// Each limit is disabled if set to `None`. The default value has no limits.
+///
+/// See the module-level documentation.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Limits<'a> {
+    /// The maximum number of rule applications.
+    pub max_steps: Option<usize>,
+
+    /// The maximum depth of the nodes visited, counting the root as depth 0.
+    ///
+    /// For `Biplate` rewrites, depths are those of [paths](crate::path) into the `From` root, so
+    /// the top-most values of type `To` are at depth 1.
+    pub max_depth: Option<usize>,
+
+    /// The time by which the rewrite must finish.
+    ///
+    /// A [`Deadline`] can only be created with the `std` feature, so without it this is always
+    /// `None`.
+    pub deadline: Option<Deadline>,
+
+    /// A flag that stops the rewrite when set, for example by another thread.
+    pub cancel: Option<&'a AtomicBool>,
+}
+
+/// A point in time by which a rewrite must finish, for [`Limits::deadline`].
+///
+/// Creating a deadline requires the `std` feature, which provides the clock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline(Clock);
+
+/// The instants of the clock used by [`Deadline`].
+#[cfg(feature = "std")]
+type Clock = Instant;
+
+/// Without `std` there is no clock, so no [`Deadline`] can exist.
+#[cfg(not(feature = "std"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Clock {}
+
+impl Deadline {
+    /// Returns a deadline at `instant`.
+    #[cfg(feature = "std")]
+    pub fn at(instant: Instant) -> Deadline {
+        Deadline(instant)
+    }
+
+    /// Returns a deadline `duration` from now.
+    #[cfg(feature = "std")]
+    pub fn after(duration: Duration) -> Deadline {
+        Deadline(Instant::now() + duration)
+    }
+
+    /// Returns true if the deadline has passed.
+    fn has_passed(&self) -> bool {
+        #[cfg(feature = "std")]
+        return Instant::now() >= self.0;
+
+        #[cfg(not(feature = "std"))]
+        match self.0 {}
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<Instant> for Deadline {
+    fn from(instant: Instant) -> Deadline {
+        Deadline::at(instant)
+    }
+}
+
+/// A limit in [`Limits`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Limit {
+    /// [`Limits::max_steps`].
+    Steps,
+
+    /// [`Limits::max_depth`].
+    Depth,
+
+    /// [`Limits::deadline`].
+    Deadline,
+
+    /// [`Limits::cancel`].
+    Cancelled,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Limit::Steps => write!(f, "step limit reached"),
+            Limit::Depth => write!(f, "depth limit reached"),
+            Limit::Deadline => write!(f, "deadline passed"),
+            Limit::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+/// The error returned when a rewrite is stopped by one of its [`Limits`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RewriteLimitExceeded<T> {
+    /// The tree, with all rule applications made before the rewrite was stopped.
+    pub partial_result: T,
+
+    /// The number of rule applications made before the rewrite was stopped.
+    pub steps: usize,
+
+    /// The limit that stopped the rewrite.
+    pub limit: Limit,
+}
+
+impl<T> fmt::Display for RewriteLimitExceeded<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rewrite stopped after {} steps: {}",
+            self.steps, self.limit
+        )
+    }
+}
+
+impl<T: fmt::Debug> core::error::Error for RewriteLimitExceeded<T> {}
+
+/// Tracks the progress of a rewrite against its [`Limits`].
+pub(crate) struct Budget<'a> {
+    limits: &'a Limits<'a>,
+    steps: usize,
+}
+
+impl<'a> Budget<'a> {
+    pub(crate) fn new(limits: &'a Limits<'a>) -> Budget<'a> {
+        Budget { limits, steps: 0 }
+    }
+
+    /// Returns the number of steps taken so far.
+    pub(crate) fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Checks the deadline and cancellation flag.
+    pub(crate) fn check(&self) -> Result<(), Limit> {
+        if self
+            .limits
+            .cancel
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+        {
+            return Err(Limit::Cancelled);
+        }
+        if self
+            .limits
+            .deadline
+            .is_some_and(|deadline| deadline.has_passed())
+        {
+            return Err(Limit::Deadline);
+        }
+        Ok(())
+    }
+
+    /// Checks that a node at `depth` may be visited.
+    pub(crate) fn check_depth(&self, depth: usize) -> Result<(), Limit> {
+        match self.limits.max_depth {
+            Some(max_depth) if depth > max_depth => Err(Limit::Depth),
+            _ => Ok(()),
+        }
+    }
+
+    /// Records a rule application, failing if there are no steps left.
+    pub(crate) fn step(&mut self) -> Result<(), Limit> {
+        if self
+            .limits
+            .max_steps
+            .is_some_and(|max_steps| self.steps >= max_steps)
+        {
+            return Err(Limit::Steps);
+        }
+        self.steps += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::test_common::paper::{Expr, proptest_exprs, proptest_stmts};
+    use crate::zipper::{Zipper, ZipperBi};
+    use crate::{Biplate, Uniplate};
+
+    use super::*;
+
+    fn push_negation(expr: Expr) -> Option<Expr> {
+        match expr {
+            Expr::Neg(x) => match *x {
+                Expr::Neg(y) => Some(*y),
+                Expr::Add(a, b) => Some(Expr::Add(Box::new(Expr::Neg(a)), Box::new(Expr::Neg(b)))),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn wrap_in_negation(expr: Expr) -> Option<Expr> {
+        match expr {
+            Expr::Val(_) => Some(Expr::Neg(Box::new(expr))),
+            _ => None,
+        }
+    }
+
+    /// Rewrites every `Val(n)` to `Val(n + 1)` forever.
+    fn increment(expr: Expr) -> Option<Expr> {
+        match expr {
+            Expr::Val(n) => Some(Expr::Val(n.wrapping_add(1))),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn step_limit() {
+        use Expr::*;
+
+        let limits = Limits {
+            max_steps: Some(3),
+            ..Default::default()
+        };
+
+        let expr = Add(Box::new(Val(0)), Box::new(Val(0)));
+        let err = expr.rewrite_with_limits(&increment, &limits).unwrap_err();
+        assert_eq!(
+            err,
+            RewriteLimitExceeded {
+                partial_result: Add(Box::new(Val(3)), Box::new(Val(0))),
+                steps: 3,
+                limit: Limit::Steps,
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "rewrite stopped after 3 steps: step limit reached"
+        );
+
+        // exactly enough steps
+        let expr = Neg(Box::new(Neg(Box::new(Neg(Box::new(Neg(Box::new(Val(
+            1,
+        )))))))));
+        let limits = Limits {
+            max_steps: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            expr.rewrite_with_limits(&push_negation, &limits),
+            Ok(Val(1))
+        );
+    }
+
+    #[test]
+    fn depth_limit() {
+        use Expr::*;
+
+        let limits = Limits {
+            max_depth: Some(3),
+            ..Default::default()
+        };
+
+        // Val(0) ~> Neg(Val(0)) ~> Neg(Neg(Val(0))) ~> ...
+        let err = Val(0)
+            .rewrite_with_limits(&wrap_in_negation, &limits)
+            .unwrap_err();
+        assert_eq!(err.limit, Limit::Depth);
+        assert_eq!(err.steps, 4);
+        assert_eq!(err.partial_result.universe().len(), 5);
+    }
+
+    #[test]
+    fn cancelled() {
+        use Expr::*;
+
+        let cancel = AtomicBool::new(true);
+        let limits = Limits {
+            cancel: Some(&cancel),
+            ..Default::default()
+        };
+        let err = Val(0).rewrite_with_limits(&increment, &limits).unwrap_err();
+        assert_eq!(err.limit, Limit::Cancelled);
+        assert_eq!(err.steps, 0);
+        assert_eq!(err.partial_result, Val(0));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn deadline() {
+        use Expr::*;
+
+        let limits = Limits {
+            deadline: Some(Deadline::after(Duration::from_millis(10))),
+            ..Default::default()
+        };
+        let err = Val(0).rewrite_with_limits(&increment, &limits).unwrap_err();
+        assert_eq!(err.limit, Limit::Deadline);
+        assert_eq!(err.partial_result, Val(err.steps as i32));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn cancelled_from_another_thread() {
+        let cancel = AtomicBool::new(false);
+        let limits = Limits {
+            cancel: Some(&cancel),
+            ..Default::default()
+        };
+
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                std::thread::sleep(Duration::from_millis(10));
+                cancel.store(true, Ordering::Relaxed);
+            });
+            let err = vec![Expr::Val(0)]
+                .rewrite_bi_with_limits(&increment, &limits)
+                .unwrap_err();
+            assert_eq!(err.limit, Limit::Cancelled);
+        });
+    }
+
+    proptest! {
+        #[test]
+        fn unlimited_same_as_rewrite_fixpoint(expr in proptest_exprs()) {
+            let expected = expr.rewrite_fixpoint(&push_negation);
+            prop_assert_eq!(expr.rewrite_with_limits(&push_negation, &Limits::default()), Ok(expected.clone()));
+
+            let mut zipper = Zipper::new(expr);
+            zipper.go_down();
+            prop_assert_eq!(zipper.rewrite_with_limits(&push_negation, &Limits::default()), Ok(expected));
+        }
+
+        #[test]
+        fn unlimited_same_as_rewrite_fixpoint_bi(stmt in proptest_stmts()) {
+            let expected = stmt.rewrite_fixpoint_bi(&push_negation);
+            prop_assert_eq!(stmt.rewrite_bi_with_limits(&push_negation, &Limits::default()), Ok(expected.clone()));
+
+            if let Some(zipper) = ZipperBi::new(stmt) {
+                prop_assert_eq!(zipper.rewrite_with_limits(&push_negation, &Limits::default()), Ok(expected));
+            }
+        }
+
+        #[test]
+        fn partial_result_can_be_resumed(stmt in proptest_stmts(), max_steps in 0..10usize) {
+            let limits = Limits {
+                max_steps: Some(max_steps),
+                ..Default::default()
+            };
+            let expected = stmt.rewrite_fixpoint_bi(&push_negation);
+            match stmt.rewrite_bi_with_limits(&push_negation, &limits) {
+                Ok(result) => prop_assert_eq!(result, expected),
+                Err(err) => {
+                    prop_assert_eq!(err.steps, max_steps);
+                    prop_assert_eq!(err.limit, Limit::Steps);
+                    prop_assert_eq!(err.partial_result.rewrite_fixpoint_bi(&push_negation), expected);
+                }
+            }
+        }
+
+        #[test]
+        fn depth_limit_is_respected(expr in proptest_exprs(), max_depth in 0..5usize) {
+            let limits = Limits {
+                max_depth: Some(max_depth),
+                ..Default::default()
+            };
+            let depth = expr.positions().map(|(path, _)| path.depth()).max().unwrap();
+            let result = expr.rewrite_with_limits(&push_negation, &limits);
+            prop_assert_eq!(result.is_ok(), depth <= max_depth);
+        }
+    }
+}
@@ -0,0 +1,583 @@
+//! Observing rewrites.
+//!
+//! A [`RewriteObserver`] is notified of each change made during a rewrite: which rule was
+//! applied, where, and what it changed. Observers can be passed to the `_observed` variants of
+//! the rewriting methods on [`Uniplate`] and [`Biplate`], to
+//! [`RuleSet::rewrite_observed`](crate::rules::RuleSet::rewrite_observed), and to
+//! [`Strategy::apply_observed`](crate::strategy::Strategy::apply_observed).
+//!
+//! [`Trace`] is an observer that records every change. A trace can be printed as a
+//! human-readable log, or replayed on the original tree to reproduce the rewrite step by step.
+//!
+//! # Rules and paths
+//!
+//! Methods that take a single function, such as
+//! [`rewrite_observed`](Uniplate::rewrite_observed), report it with the name
+//! [`ANONYMOUS_RULE`].
+//!
+//! The path given with each change is the position of the changed node in the tree as it was at
+//! that point in the rewrite, with all earlier changes applied. See [`crate::path`] for how
+//! paths are interpreted for `Biplate` rewrites.
+//!
+//! # Passes
+//!
+//! A [`RuleSet`](crate::rules::RuleSet) reports each of its passes over the tree. All other
+//! rewrites are reported as a single pass.
+//!
+//! # Example
+//!
+//! ```
+//! use uniplate::Uniplate;
+//! use uniplate::observer::Trace;
+//! use uniplate::path::Path;
+//!
+//! #[derive(Clone, PartialEq, Eq, Debug, Uniplate)]
+//! enum Expr {
+//!     Val(i32),
+//!     Neg(Box<Expr>),
+//!     Add(Box<Expr>, Box<Expr>),
+//! }
+//!
+//! use Expr::*;
+//!
+//! let remove_double_negation = |x: Expr| match x {
+//!     Neg(x) => match *x {
+//!         Neg(x) => Some(*x),
+//!         _ => None,
+//!     },
+//!     _ => None,
+//! };
+//!
+//! // 1 + --2
+//! let expr = Add(
+//!     Box::new(Val(1)),
+//!     Box::new(Neg(Box::new(Neg(Box::new(Val(2)))))),
+//! );
+//!
+//! let mut trace = Trace::new();
+//! let result = expr.rewrite_observed(&remove_double_negation, &mut trace);
+//!
+//! assert_eq!(trace.steps().len(), 1);
+//! assert_eq!(trace.steps()[0].path, Path::from([1]));
+//! assert_eq!(trace.replay(&expr), Ok(result));
+//!
+//! assert_eq!(
+//!     trace.to_string(),
+//!     "pass 1:\n  <anonymous> at /1: Neg(Neg(Val(2))) ~> Val(2)\n"
+//! );
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::any::Any;
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::path::Path;
+use crate::{Biplate, Uniplate};
+
+/// The rule name reported for rewrites that use a single, unnamed function.
+pub const ANONYMOUS_RULE: &str = "<anonymous>";
+
+/// Receives notifications about the progress of a rewrite.
+///
+/// All methods do nothing by default.
+///
+/// See the module-level documentation.
+pub trait RewriteObserver<T> {
+    /// Called before each pass over the tree. Passes are numbered from 1.
+    fn on_pass_start(&mut self, pass: usize) {
+        let _ = pass;
+    }
+
+    /// Called after each pass over the tree, with whether the pass changed the tree.
+    fn on_pass_end(&mut self, pass: usize, changed: bool) {
+        let _ = (pass, changed);
+    }
+
+    /// Called each time a rule is applied, with the name of the rule, the path of the node it
+    /// was applied to, and the node before and after.
+    fn on_rule_applied(&mut self, rule: &str, path: &Path, before: &T, after: &T) {
+        let _ = (rule, path, before, after);
+    }
+}
+
+/// The observer that ignores everything.
+impl<T> RewriteObserver<T> for () {}
+
+/// A single change recorded in a [`Trace`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceStep<T> {
+    /// The pass in which the change was made.
+    pub pass: usize,
+
+    /// The name of the rule that was applied.
+    pub rule: String,
+
+    /// The path of the node the rule was applied to.
+    pub path: Path,
+
+    /// The node before the rule was applied.
+    pub before: T,
+
+    /// The node after the rule was applied.
+    pub after: T,
+}
+
+/// A [`RewriteObserver`] that records every change made during a rewrite.
+///
+/// The [`Display`](fmt::Display) implementation prints the trace as a log, with one line per
+/// change, grouped by pass.
+///
+/// See the module-level documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace<T> {
+    steps: Vec<TraceStep<T>>,
+    passes: usize,
+}
+
+impl<T> Trace<T> {
+    /// Creates an empty trace.
+    pub fn new() -> Trace<T> {
+        Trace {
+            steps: Vec::new(),
+            passes: 0,
+        }
+    }
+
+    /// Returns the recorded changes, in the order they were made.
+    pub fn steps(&self) -> &[TraceStep<T>] {
+        &self.steps
+    }
+
+    /// Returns the number of passes recorded.
+    pub fn passes(&self) -> usize {
+        self.passes
+    }
+}
+
+impl<T: Uniplate> Trace<T> {
+    /// Reproduces the recorded rewrite on `root`, returning the result.
+    ///
+    /// Each step replaces the node at its path with its `after` value. Fails if the node at the
+    /// path of a step is not its `before` value, which happens if `root` is not the tree that was
+    /// rewritten.
+    pub fn replay(&self, root: &T) -> Result<T, ReplayError> {
+        self.replay_with(root.clone(), |root, step| {
+            if root.get_at(&step.path).as_ref() != Some(&step.before) {
+                return None;
+            }
+            root.replace_at(&step.path, step.after.clone()).ok()
+        })
+    }
+
+    /// Reproduces the recorded rewrite on the values of type `T` inside `root`, returning the
+    /// result.
+    ///
+    /// Biplate variant of [`replay`](Trace::replay).
+    pub fn replay_bi<From: Biplate<T>>(&self, root: &From) -> Result<From, ReplayError> {
+        self.replay_with(root.clone(), |root, step| {
+            if root.get_at_bi(&step.path).as_ref() != Some(&step.before) {
+                return None;
+            }
+            root.replace_at_bi(&step.path, step.after.clone()).ok()
+        })
+    }
+
+    fn replay_with<R>(
+        &self,
+        root: R,
+        apply: impl Fn(&R, &TraceStep<T>) -> Option<R>,
+    ) -> Result<R, ReplayError> {
+        self.steps
+            .iter()
+            .enumerate()
+            .try_fold(root, |root, (i, step)| {
+                apply(&root, step).ok_or_else(|| ReplayError {
+                    step: i,
+                    path: step.path.clone(),
+                })
+            })
+    }
+}
+
+impl<T> Default for Trace<T> {
+    fn default() -> Self {
+        Trace::new()
+    }
+}
+
+impl<T: Clone> RewriteObserver<T> for Trace<T> {
+    fn on_pass_start(&mut self, pass: usize) {
+        self.passes = pass;
+    }
+
+    fn on_rule_applied(&mut self, rule: &str, path: &Path, before: &T, after: &T) {
+        self.steps.push(TraceStep {
+            pass: self.passes,
+            rule: rule.into(),
+            path: path.clone(),
+            before: before.clone(),
+            after: after.clone(),
+        });
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for Trace<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut steps = self.steps.iter().peekable();
+        for pass in 1..=self.passes {
+            writeln!(f, "pass {pass}:")?;
+            while let Some(step) = steps.next_if(|step| step.pass == pass) {
+                writeln!(
+                    f,
+                    "  {} at {}: {:?} ~> {:?}",
+                    step.rule, step.path, step.before, step.after
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The error returned when a [`Trace`] cannot be replayed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplayError {
+    /// The index of the step that could not be replayed.
+    step: usize,
+
+    /// The path of that step.
+    path: Path,
+}
+
+impl ReplayError {
+    /// Returns the index of the step that could not be replayed.
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Returns the path of the step that could not be replayed.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot replay step {}: the node at {} does not match the trace",
+            self.step, self.path
+        )
+    }
+}
+
+impl core::error::Error for ReplayError {}
+
+/// A [`RewriteObserver`] for an unknown node type.
+///
+/// Used to report changes from code that is generic over the node type, such as strategies
+/// lifted with [`all_bi`](crate::strategy::all_bi). Changes to nodes of any type other than the
+/// observer's are ignored.
+pub(crate) trait ErasedObserver {
+    fn on_rule_applied(&mut self, rule: &str, path: &Path, before: &dyn Any, after: &dyn Any);
+}
+
+/// Wraps a [`RewriteObserver<T>`] as an [`ErasedObserver`].
+pub(crate) struct Erased<'a, O, T>(pub &'a mut O, pub PhantomData<T>);
+
+impl<O: RewriteObserver<T>, T: 'static> ErasedObserver for Erased<'_, O, T> {
+    fn on_rule_applied(&mut self, rule: &str, path: &Path, before: &dyn Any, after: &dyn Any) {
+        if let (Some(before), Some(after)) = (before.downcast_ref(), after.downcast_ref()) {
+            self.0.on_rule_applied(rule, path, before, after);
+        }
+    }
+}
+
+/// Reports a rewrite of `root` by `f` to `observer` as a single pass.
+pub(crate) fn single_pass<R: Eq + Clone, T, O: RewriteObserver<T>>(
+    root: &R,
+    observer: &mut O,
+    f: impl FnOnce(R, &mut O) -> R,
+) -> R {
+    observer.on_pass_start(1);
+    let result = f(root.clone(), observer);
+    let changed = result != *root;
+    observer.on_pass_end(1, changed);
+    result
+}
+
+/// Applies `f` to each child of type `To` of `root`, giving it the path of the child.
+pub(crate) fn descend_bi<From: Biplate<To>, To: Uniplate, O>(
+    root: From,
+    observer: &mut O,
+    f: impl Fn(To, &mut Path, &mut O) -> To,
+) -> From {
+    let children = root
+        .children_bi()
+        .into_iter()
+        .enumerate()
+        .map(|(i, child)| f(child, &mut Path::from([i]), observer))
+        .collect();
+    root.with_children_bi(children)
+}
+
+/// Applies `f` to each child of `node`, giving it the path of the child.
+fn descend<T: Uniplate, O>(
+    node: T,
+    path: &mut Path,
+    observer: &mut O,
+    f: impl Fn(T, &mut Path, &mut O) -> T,
+) -> T {
+    let mut children = node.children().into_iter().enumerate().map(|(i, child)| {
+        path.push(i);
+        let child = f(child, path, observer);
+        path.pop();
+        child
+    });
+    node.rebuild(&mut children)
+}
+
+/// Observed implementation of [`Uniplate::transform`].
+pub(crate) fn transform<T: Uniplate>(
+    node: T,
+    path: &mut Path,
+    f: &impl Fn(T) -> T,
+    observer: &mut impl RewriteObserver<T>,
+) -> T {
+    let node = descend(node, path, observer, |child, path, observer| {
+        transform(child, path, f, observer)
+    });
+    let new_node = f(node.clone());
+    if new_node != node {
+        observer.on_rule_applied(ANONYMOUS_RULE, path, &node, &new_node);
+    }
+    new_node
+}
+
+/// Observed implementation of [`Uniplate::rewrite`], or of [`Uniplate::rewrite_fixpoint`] if
+/// `fixpoint` is true.
+pub(crate) fn rewrite<T: Uniplate>(
+    node: T,
+    path: &mut Path,
+    f: &impl Fn(T) -> Option<T>,
+    fixpoint: bool,
+    observer: &mut impl RewriteObserver<T>,
+) -> T {
+    let node = descend(node, path, observer, |child, path, observer| {
+        rewrite(child, path, f, fixpoint, observer)
+    });
+    let Some(new_node) = f(node.clone()) else {
+        return node;
+    };
+    observer.on_rule_applied(ANONYMOUS_RULE, path, &node, &new_node);
+    if fixpoint {
+        rewrite(new_node, path, f, fixpoint, observer)
+    } else {
+        new_node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::rules::{Rule, RuleSet};
+    use crate::strategy::{self, Strategy};
+    use crate::test_common::paper::{Expr, Stmt, proptest_exprs, proptest_stmts};
+
+    use super::*;
+
+    fn remove_double_negation(expr: Expr) -> Option<Expr> {
+        match expr {
+            Expr::Neg(x) => match *x {
+                Expr::Neg(y) => Some(*y),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn push_negation(expr: Expr) -> Option<Expr> {
+        match expr {
+            Expr::Neg(x) => match *x {
+                Expr::Neg(y) => Some(*y),
+                Expr::Add(a, b) => Some(Expr::Add(Box::new(Expr::Neg(a)), Box::new(Expr::Neg(b)))),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn double(expr: Expr) -> Expr {
+        match expr {
+            Expr::Val(x) => Expr::Val(x.wrapping_mul(2)),
+            x => x,
+        }
+    }
+
+    /// Counts passes and checks that they are properly nested.
+    #[derive(Default)]
+    struct PassCounter {
+        started: usize,
+        ended: usize,
+    }
+
+    impl<T> RewriteObserver<T> for PassCounter {
+        fn on_pass_start(&mut self, pass: usize) {
+            assert_eq!(self.started, self.ended);
+            self.started += 1;
+            assert_eq!(pass, self.started);
+        }
+
+        fn on_pass_end(&mut self, pass: usize, _changed: bool) {
+            self.ended += 1;
+            assert_eq!(pass, self.started);
+        }
+    }
+
+    #[test]
+    fn trace_log() {
+        use Expr::*;
+
+        let rules = RuleSet::from_iter([
+            Rule::new("remove_double_negation", 1, |x: &Expr| {
+                remove_double_negation(x.clone())
+            }),
+            Rule::new("zero_to_double_negation", 0, |x: &Expr| match x {
+                Val(0) => Some(Neg(Box::new(Neg(Box::new(Val(1)))))),
+                _ => None,
+            }),
+        ]);
+
+        let mut trace = Trace::new();
+        let expr = Add(Box::new(Val(0)), Box::new(Var("x".into())));
+        let (result, _) = rules.rewrite_observed(&expr, &mut trace);
+        assert_eq!(result, Add(Box::new(Val(1)), Box::new(Var("x".into()))));
+
+        assert_eq!(
+            trace.to_string(),
+            "pass 1:\n  \
+             zero_to_double_negation at /0: Val(0) ~> Neg(Neg(Val(1)))\n\
+             pass 2:\n  \
+             remove_double_negation at /0: Neg(Neg(Val(1))) ~> Val(1)\n\
+             pass 3:\n"
+        );
+    }
+
+    #[test]
+    fn replay_wrong_root() {
+        use Expr::*;
+
+        let mut trace = Trace::new();
+        let expr = Neg(Box::new(Neg(Box::new(Val(1)))));
+        expr.rewrite_observed(&remove_double_negation, &mut trace);
+
+        let err = trace.replay(&Val(1)).unwrap_err();
+        assert_eq!(err.step(), 0);
+        assert_eq!(err.path(), &Path::root());
+        assert_eq!(
+            err.to_string(),
+            "cannot replay step 0: the node at / does not match the trace"
+        );
+    }
+
+    #[test]
+    fn strategy_reports_rule_names() {
+        use Expr::*;
+
+        let s = strategy::bottom_up(strategy::try_(strategy::choice(
+            Strategy::named("remove_double_negation", remove_double_negation),
+            Strategy::new(|x| match x {
+                Val(0) => Some(Val(1)),
+                _ => None,
+            }),
+        )));
+
+        let mut trace = Trace::new();
+        let expr = Add(
+            Box::new(Val(0)),
+            Box::new(Neg(Box::new(Neg(Box::new(Var("x".into())))))),
+        );
+        let result = s.apply_observed(expr.clone(), &mut trace).unwrap();
+
+        let steps: Vec<_> = trace
+            .steps()
+            .iter()
+            .map(|step| (step.rule.as_str(), step.path.to_string()))
+            .collect();
+        assert_eq!(
+            steps,
+            [
+                (ANONYMOUS_RULE, "/0".into()),
+                ("remove_double_negation", "/1".into())
+            ]
+        );
+        assert_eq!(trace.replay(&expr), Ok(result));
+    }
+
+    proptest! {
+        #[test]
+        fn observed_rewrites_replay(expr in proptest_exprs()) {
+            let mut trace = Trace::new();
+            let result = expr.rewrite_fixpoint_observed(&push_negation, &mut trace);
+            prop_assert_eq!(&result, &expr.rewrite_fixpoint(&push_negation));
+            prop_assert_eq!(trace.replay(&expr), Ok(result));
+
+            let mut trace = Trace::new();
+            let result = expr.rewrite_observed(&push_negation, &mut trace);
+            prop_assert_eq!(&result, &expr.rewrite(&push_negation));
+            prop_assert_eq!(trace.replay(&expr), Ok(result));
+
+            let mut trace = Trace::new();
+            let result = expr.transform_observed(&double, &mut trace);
+            prop_assert_eq!(&result, &expr.transform(&double));
+            prop_assert_eq!(trace.replay(&expr), Ok(result));
+            prop_assert_eq!(trace.passes(), 1);
+        }
+
+        #[test]
+        fn observed_biplate_rewrites_replay(stmt in proptest_stmts()) {
+            let mut trace = Trace::new();
+            let result = stmt.rewrite_fixpoint_bi_observed(&push_negation, &mut trace);
+            prop_assert_eq!(&result, &stmt.rewrite_fixpoint_bi(&push_negation));
+            prop_assert_eq!(trace.replay_bi(&stmt), Ok(result));
+
+            let mut trace = Trace::new();
+            let result = stmt.transform_bi_observed(&double, &mut trace);
+            prop_assert_eq!(&result, &stmt.transform_bi(&double));
+            prop_assert_eq!(trace.replay_bi(&stmt), Ok(result));
+        }
+
+        #[test]
+        fn observed_rule_set_replays(stmt in proptest_stmts()) {
+            let rules = RuleSet::from_iter([
+                Rule::new("push_negation", 0, |x: &Expr| push_negation(x.clone())),
+            ]);
+
+            let mut trace = Trace::new();
+            let (result, stats) = rules.rewrite_bi_observed(&stmt, &mut trace);
+            prop_assert_eq!(trace.steps().len(), stats.applications());
+            prop_assert_eq!(trace.passes(), stats.passes());
+            prop_assert_eq!(trace.replay_bi(&stmt), Ok(result));
+
+            let mut counter = PassCounter::default();
+            rules.rewrite_bi_observed(&stmt, &mut counter);
+            prop_assert_eq!(counter.ended, stats.passes());
+        }
+
+        #[test]
+        fn observed_strategies_replay(stmt in proptest_stmts()) {
+            let s: Strategy<Stmt> = strategy::all_bi(strategy::innermost(Strategy::new(push_negation)));
+
+            let mut trace: Trace<Expr> = Trace::new();
+            let result = s.apply_observed(stmt.clone(), &mut trace).unwrap();
+            prop_assert_eq!(&Some(result.clone()), &s.apply(stmt.clone()));
+            prop_assert_eq!(trace.replay_bi(&stmt), Ok(result));
+
+            // observers of a different type see nothing.
+            let mut trace: Trace<Stmt> = Trace::new();
+            s.apply_observed(stmt, &mut trace);
+            prop_assert!(trace.steps().is_empty());
+        }
+    }
+}
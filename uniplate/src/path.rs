@@ -0,0 +1,226 @@
+//! Paths to subterms.
+//!
+//! A [`Path`] names a position in a tree by the sequence of child indices that lead to it from
+//! the root. For example, the path `/1/0` refers to the left-most child of the second child of
+//! the root.
+//!
+//! For `Uniplate` operations, child indices are those of [`children`](crate::Uniplate::children).
+//! For `Biplate` operations, the first index is into
+//! [`children_bi`](crate::Biplate::children_bi) of the `From` root, and the remaining indices are
+//! into `children` as before.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::Uniplate;
+
+/// A position in a tree, given as a sequence of child indices from the root.
+///
+/// The empty path refers to the root itself.
+///
+/// See the module-level documentation.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Path(Vec<usize>);
+
+impl Path {
+    /// Returns the path to the root node.
+    pub fn root() -> Path {
+        Path(Vec::new())
+    }
+
+    /// Returns true if this is the path to the root node.
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of steps in this path; that is, the depth of the node it refers to.
+    pub fn depth(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the child indices that make up this path, root first.
+    pub fn indices(&self) -> &[usize] {
+        &self.0
+    }
+
+    /// Returns the path to the `index`th child of the node at this path.
+    pub fn child(&self, index: usize) -> Path {
+        let mut path = self.clone();
+        path.push(index);
+        path
+    }
+
+    /// Returns the path to the parent of the node at this path.
+    ///
+    /// Returns `None` if this is the root path.
+    pub fn parent(&self) -> Option<Path> {
+        let mut path = self.clone();
+        path.pop()?;
+        Some(path)
+    }
+
+    /// Extends this path by one step, to the `index`th child.
+    pub fn push(&mut self, index: usize) {
+        self.0.push(index);
+    }
+
+    /// Removes the last step from this path, returning its index.
+    ///
+    /// Returns `None` if this is the root path.
+    pub fn pop(&mut self) -> Option<usize> {
+        self.0.pop()
+    }
+
+    /// Returns true if the node at this path is the node at `other`, or one of its ancestors.
+    pub fn is_prefix_of(&self, other: &Path) -> bool {
+        other.0.starts_with(&self.0)
+    }
+}
+
+impl From<Vec<usize>> for Path {
+    fn from(indices: Vec<usize>) -> Self {
+        Path(indices)
+    }
+}
+
+impl<const N: usize> From<[usize; N]> for Path {
+    fn from(indices: [usize; N]) -> Self {
+        Path(indices.into())
+    }
+}
+
+impl FromIterator<usize> for Path {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        Path(iter.into_iter().collect())
+    }
+}
+
+impl fmt::Display for Path {
+    /// Formats the path as `/i/j/k`, or `/` for the root.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_root() {
+            return write!(f, "/");
+        }
+        for index in &self.0 {
+            write!(f, "/{index}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The error returned when a [`Path`] does not refer to a node in a tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathError {
+    /// The path that was requested.
+    path: Path,
+
+    /// The number of steps of the path that could be followed.
+    depth: usize,
+
+    /// The number of children of the node at which the path could no longer be followed.
+    children: usize,
+}
+
+impl PathError {
+    pub(crate) fn new(path: &Path, depth: usize, children: usize) -> PathError {
+        PathError {
+            path: path.clone(),
+            depth,
+            children,
+        }
+    }
+
+    /// Returns the path that was requested.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the longest prefix of the requested path that exists in the tree.
+    pub fn valid_prefix(&self) -> Path {
+        self.path.0[..self.depth].iter().copied().collect()
+    }
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.depth == self.path.depth() {
+            // Only possible for Biplate paths, whose root is not of the target type.
+            return write!(
+                f,
+                "no subterm at path {}: the root is not of the target type",
+                self.path
+            );
+        }
+        write!(
+            f,
+            "no subterm at path {}: the node at {} has {} children, but child {} was requested",
+            self.path,
+            self.valid_prefix(),
+            self.children,
+            self.path.0[self.depth]
+        )
+    }
+}
+
+impl core::error::Error for PathError {}
+
+/// Follows `indices` down from `node`, returning the node at the end.
+pub(crate) fn get_at<T: Uniplate>(node: T, indices: &[usize]) -> Option<T> {
+    indices
+        .iter()
+        .try_fold(node, |node, &index| node.children().remove(index))
+}
+
+/// Replaces the node at `path` inside `node` with the result of `f`, returning the new node.
+///
+/// The first `depth` steps of the path have already been followed to reach `node`.
+pub(crate) fn modify_at<T: Uniplate>(
+    node: &T,
+    path: &Path,
+    depth: usize,
+    f: impl FnOnce(T) -> T,
+) -> Result<T, PathError> {
+    let Some(&index) = path.indices().get(depth) else {
+        return Ok(f(node.clone()));
+    };
+
+    let mut children = node.children();
+    let n_children = children.len();
+    let Some(child) = children.get_mut(index) else {
+        return Err(PathError::new(path, depth, n_children));
+    };
+    *child = modify_at(child, path, depth + 1, f)?;
+    Ok(node.rebuild(&mut children.into_iter()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_display() {
+        assert_eq!(Path::root().to_string(), "/");
+        assert_eq!(Path::from([1, 0, 2]).to_string(), "/1/0/2");
+    }
+
+    #[test]
+    fn path_parent_and_child() {
+        let path = Path::from([1, 0]);
+        assert_eq!(path.child(2), Path::from([1, 0, 2]));
+        assert_eq!(path.parent(), Some(Path::from([1])));
+        assert_eq!(Path::root().parent(), None);
+        assert!(path.is_prefix_of(&path.child(2)));
+        assert!(Path::root().is_prefix_of(&path));
+        assert!(!path.child(2).is_prefix_of(&path));
+    }
+
+    #[test]
+    fn path_error_display() {
+        let err = PathError::new(&Path::from([1, 3, 0]), 1, 2);
+        assert_eq!(err.valid_prefix(), Path::from([1]));
+        assert_eq!(
+            err.to_string(),
+            "no subterm at path /1/3/0: the node at /1 has 2 children, but child 3 was requested"
+        );
+    }
+}
@@ -0,0 +1,536 @@
+//! Named rewrite rules with priorities.
+//!
+//! A [`RuleSet`] rewrites a tree using a collection of named [`Rule`]s, until none of them apply
+//! anywhere. It also records which rules were applied, how often, and how long they took.
+//!
+//! Rewriting proceeds in passes. Each pass visits every node bottom up, and at each node tries
+//! the rules in priority order, highest first. The first rule that applies replaces the node, and
+//! the pass moves on to the next node. Passes are repeated until one makes no changes.
+//!
+//! # Example
+//!
+//! ```
+//! use uniplate::Uniplate;
+//! use uniplate::rules::{Rule, RuleSet};
+//!
+//! #[derive(Clone, PartialEq, Eq, Debug, Uniplate)]
+//! enum Expr {
+//!     Val(i32),
+//!     Neg(Box<Expr>),
+//!     Add(Box<Expr>, Box<Expr>),
+//! }
+//!
+//! use Expr::*;
+//!
+//! let rules = RuleSet::from_iter([
+//!     Rule::new("remove_double_negation", 10, |x: &Expr| match x {
+//!         Neg(x) => match &**x {
+//!             Neg(x) => Some((**x).clone()),
+//!             _ => None,
+//!         },
+//!         _ => None,
+//!     }),
+//!     Rule::new("fold_constants", 1, |x: &Expr| match x {
+//!         Add(a, b) => match (&**a, &**b) {
+//!             (Val(a), Val(b)) => Some(Val(a + b)),
+//!             _ => None,
+//!         },
+//!         _ => None,
+//!     }),
+//! ]);
+//!
+//! // --(1 + --2)
+//! let expr = Neg(Box::new(Neg(Box::new(Add(
+//!     Box::new(Val(1)),
+//!     Box::new(Neg(Box::new(Neg(Box::new(Val(2)))))),
+//! )))));
+//!
+//! let (result, stats) = rules.rewrite(&expr);
+//! assert_eq!(result, Val(3));
+//! assert_eq!(stats.get("remove_double_negation").unwrap().applications, 2);
+//! assert_eq!(stats.get("fold_constants").unwrap().applications, 1);
+//! ```
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::fmt;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+use crate::observer::RewriteObserver;
+use crate::path::Path;
+use crate::{Biplate, Uniplate};
+
+/// A named rewrite rule.
+///
+/// A rule applies to a node if `apply` returns `Some`, in which case the node is replaced by the
+/// returned value.
+pub struct Rule<T> {
+    /// The name of the rule, used to report statistics.
+    pub name: String,
+
+    /// The priority of the rule. Rules with higher priorities are tried first.
+    pub priority: u16,
+
+    /// The rule itself.
+    pub apply: Box<RuleFn<T>>,
+}
+
+/// The function type of a [`Rule`].
+pub type RuleFn<T> = dyn Fn(&T) -> Option<T>;
+
+impl<T> Rule<T> {
+    /// Creates a new rule.
+    pub fn new(
+        name: impl Into<String>,
+        priority: u16,
+        apply: impl Fn(&T) -> Option<T> + 'static,
+    ) -> Rule<T> {
+        Rule {
+            name: name.into(),
+            priority,
+            apply: Box::new(apply),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Rule<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rule")
+            .field("name", &self.name)
+            .field("priority", &self.priority)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A collection of rules, applied in priority order.
+///
+/// Rules with equal priorities are tried in the order they were added.
+///
+/// See the module-level documentation.
+pub struct RuleSet<T> {
+    /// Sorted by descending priority.
+    rules: Vec<Rule<T>>,
+}
+
+impl<T> RuleSet<T> {
+    /// Creates an empty rule set.
+    pub fn new() -> RuleSet<T> {
+        RuleSet { rules: Vec::new() }
+    }
+
+    /// Adds a rule to this rule set.
+    pub fn add(&mut self, rule: Rule<T>) {
+        // insert after all rules of the same or higher priority, so that ties are kept in
+        // insertion order.
+        let index = self
+            .rules
+            .partition_point(|other| other.priority >= rule.priority);
+        self.rules.insert(index, rule);
+    }
+
+    /// Returns the rules in this rule set, in the order they are tried.
+    pub fn rules(&self) -> &[Rule<T>] {
+        &self.rules
+    }
+
+    /// Returns the number of rules in this rule set.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Returns true if this rule set has no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+impl<T: Uniplate> RuleSet<T> {
+    /// Rewrites `root` until no rule applies anywhere, returning the result and statistics about
+    /// the rules applied.
+    ///
+    /// If the rules can be applied indefinitely, this function will not terminate.
+    pub fn rewrite(&self, root: &T) -> (T, RewriteStats) {
+        self.rewrite_observed(root, &mut ())
+    }
+
+    /// Rewrites all values of type `T` inside `root` until no rule applies anywhere, returning
+    /// the result and statistics about the rules applied.
+    ///
+    /// Biplate variant of [`rewrite`](RuleSet::rewrite).
+    pub fn rewrite_bi<From: Biplate<T>>(&self, root: &From) -> (From, RewriteStats) {
+        self.rewrite_bi_observed(root, &mut ())
+    }
+
+    /// Rewrites `root` until no rule applies anywhere, reporting each pass and rule application
+    /// to `observer`.
+    ///
+    /// See [`crate::observer`].
+    ///
+    /// Observed variant of [`rewrite`](RuleSet::rewrite).
+    pub fn rewrite_observed(
+        &self,
+        root: &T,
+        observer: &mut impl RewriteObserver<T>,
+    ) -> (T, RewriteStats) {
+        let mut engine = Engine::new(&self.rules, observer);
+        let result = engine.fixpoint(root.clone(), |engine, root| {
+            engine.pass(root, &mut Path::root())
+        });
+        (result, engine.stats)
+    }
+
+    /// Rewrites all values of type `T` inside `root` until no rule applies anywhere, reporting
+    /// each pass and rule application to `observer`.
+    ///
+    /// Biplate variant of [`rewrite_observed`](RuleSet::rewrite_observed).
+    pub fn rewrite_bi_observed<From: Biplate<T>>(
+        &self,
+        root: &From,
+        observer: &mut impl RewriteObserver<T>,
+    ) -> (From, RewriteStats) {
+        let mut engine = Engine::new(&self.rules, observer);
+        let result = engine.fixpoint(root.clone(), |engine, root: From| {
+            let mut changed = false;
+            let children = root
+                .children_bi()
+                .into_iter()
+                .enumerate()
+                .map(|(i, child)| {
+                    let (child, child_changed) = engine.pass(child, &mut Path::from([i]));
+                    changed |= child_changed;
+                    child
+                })
+                .collect();
+            (root.with_children_bi(children), changed)
+        });
+        (result, engine.stats)
+    }
+}
+
+impl<T> Default for RuleSet<T> {
+    fn default() -> Self {
+        RuleSet::new()
+    }
+}
+
+impl<T> FromIterator<Rule<T>> for RuleSet<T> {
+    fn from_iter<I: IntoIterator<Item = Rule<T>>>(iter: I) -> Self {
+        let mut rule_set = RuleSet::new();
+        rule_set.extend(iter);
+        rule_set
+    }
+}
+
+impl<T> Extend<Rule<T>> for RuleSet<T> {
+    fn extend<I: IntoIterator<Item = Rule<T>>>(&mut self, iter: I) {
+        for rule in iter {
+            self.add(rule);
+        }
+    }
+}
+
+impl<T> fmt::Debug for RuleSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.rules).finish()
+    }
+}
+
+/// Statistics about a single rule, collected during a rewrite.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RuleStats {
+    /// The name of the rule.
+    pub name: String,
+
+    /// The number of times the rule was tried.
+    pub attempts: usize,
+
+    /// The number of times the rule applied.
+    pub applications: usize,
+
+    /// The total time spent in the rule.
+    ///
+    /// Always zero without the `std` feature.
+    pub time: Duration,
+}
+
+/// Statistics collected during a rewrite by a [`RuleSet`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RewriteStats {
+    /// One entry per rule, in the order the rules are tried.
+    rules: Vec<RuleStats>,
+
+    passes: usize,
+}
+
+impl RewriteStats {
+    /// Returns the statistics for each rule, in the order the rules are tried.
+    pub fn rules(&self) -> &[RuleStats] {
+        &self.rules
+    }
+
+    /// Returns the statistics for the rule called `name`.
+    ///
+    /// If several rules have this name, the first one tried is returned.
+    pub fn get(&self, name: &str) -> Option<&RuleStats> {
+        self.rules.iter().find(|stats| stats.name == name)
+    }
+
+    /// Returns the statistics for the rules that applied at least once.
+    pub fn fired(&self) -> impl Iterator<Item = &RuleStats> {
+        self.rules.iter().filter(|stats| stats.applications > 0)
+    }
+
+    /// Returns the total number of rule applications.
+    pub fn applications(&self) -> usize {
+        self.rules.iter().map(|stats| stats.applications).sum()
+    }
+
+    /// Returns the total time spent in rules.
+    pub fn time(&self) -> Duration {
+        self.rules.iter().map(|stats| stats.time).sum()
+    }
+
+    /// Returns the number of passes made over the tree.
+    ///
+    /// This includes the final pass, in which no rules applied.
+    pub fn passes(&self) -> usize {
+        self.passes
+    }
+}
+
+/// The state of a single rewrite.
+struct Engine<'a, T, O> {
+    rules: &'a [Rule<T>],
+    observer: &'a mut O,
+    stats: RewriteStats,
+}
+
+impl<'a, T: Uniplate, O: RewriteObserver<T>> Engine<'a, T, O> {
+    fn new(rules: &'a [Rule<T>], observer: &'a mut O) -> Engine<'a, T, O> {
+        let stats = RewriteStats {
+            rules: rules
+                .iter()
+                .map(|rule| RuleStats {
+                    name: rule.name.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+            passes: 0,
+        };
+        Engine {
+            rules,
+            observer,
+            stats,
+        }
+    }
+
+    /// Repeats `pass` on `root` until it makes no changes.
+    fn fixpoint<R>(&mut self, mut root: R, mut pass: impl FnMut(&mut Self, R) -> (R, bool)) -> R {
+        loop {
+            self.stats.passes += 1;
+            self.observer.on_pass_start(self.stats.passes);
+            let (new_root, changed) = pass(self, root);
+            self.observer.on_pass_end(self.stats.passes, changed);
+            root = new_root;
+            if !changed {
+                return root;
+            }
+        }
+    }
+
+    /// Makes a single bottom-up pass over `node`, at `path`, returning the new node and whether
+    /// any rules applied.
+    fn pass(&mut self, node: T, path: &mut Path) -> (T, bool) {
+        let mut changed = false;
+        let children: Vec<T> = node
+            .children()
+            .into_iter()
+            .enumerate()
+            .map(|(i, child)| {
+                path.push(i);
+                let (child, child_changed) = self.pass(child, path);
+                path.pop();
+                changed |= child_changed;
+                child
+            })
+            .collect();
+        // only rebuild the node if one of its children changed.
+        let node = if changed {
+            node.rebuild(&mut children.into_iter())
+        } else {
+            node
+        };
+
+        match self.apply_rules(&node, path) {
+            Some(new_node) => (new_node, true),
+            None => (node, changed),
+        }
+    }
+
+    /// Tries each rule on `node` in priority order, returning the result of the first that
+    /// applies.
+    fn apply_rules(&mut self, node: &T, path: &Path) -> Option<T> {
+        for (rule, stats) in self.rules.iter().zip(self.stats.rules.iter_mut()) {
+            #[cfg(feature = "std")]
+            let start = Instant::now();
+            let result = (rule.apply)(node);
+            #[cfg(feature = "std")]
+            {
+                stats.time += start.elapsed();
+            }
+            stats.attempts += 1;
+
+            if let Some(new_node) = &result {
+                stats.applications += 1;
+                self.observer
+                    .on_rule_applied(&rule.name, path, node, new_node);
+                return result;
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::test_common::paper::{Expr, proptest_exprs, proptest_stmts};
+
+    use super::*;
+
+    fn remove_double_negation(expr: &Expr) -> Option<Expr> {
+        match expr {
+            Expr::Neg(x) => match &**x {
+                Expr::Neg(y) => Some((**y).clone()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn fold_add(expr: &Expr) -> Option<Expr> {
+        match expr {
+            Expr::Add(a, b) => match (&**a, &**b) {
+                (Expr::Val(a), Expr::Val(b)) => Some(Expr::Val(a.wrapping_add(*b))),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn zero_to_var(expr: &Expr) -> Option<Expr> {
+        match expr {
+            Expr::Val(0) => Some(Expr::Var("zero".into())),
+            _ => None,
+        }
+    }
+
+    fn zero_to_one(expr: &Expr) -> Option<Expr> {
+        match expr {
+            Expr::Val(0) => Some(Expr::Val(1)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn rules_are_tried_in_priority_order() {
+        let rules = RuleSet::from_iter([
+            Rule::new("zero_to_var", 1, zero_to_var),
+            Rule::new("zero_to_one", 2, zero_to_one),
+        ]);
+        let names: Vec<_> = rules
+            .rules()
+            .iter()
+            .map(|rule| rule.name.as_str())
+            .collect();
+        assert_eq!(names, ["zero_to_one", "zero_to_var"]);
+
+        let (result, stats) = rules.rewrite(&Expr::Val(0));
+        assert_eq!(result, Expr::Val(1));
+        assert_eq!(stats.get("zero_to_one").unwrap().applications, 1);
+        assert_eq!(stats.get("zero_to_var").unwrap().applications, 0);
+        assert_eq!(stats.fired().count(), 1);
+    }
+
+    #[test]
+    fn equal_priorities_keep_insertion_order() {
+        let rules = RuleSet::from_iter([
+            Rule::new("zero_to_var", 1, zero_to_var),
+            Rule::new("zero_to_one", 1, zero_to_one),
+        ]);
+        assert_eq!(rules.rewrite(&Expr::Val(0)).0, Expr::Var("zero".into()));
+    }
+
+    #[test]
+    fn stats_count_attempts_applications_and_passes() {
+        use Expr::*;
+
+        let rules = RuleSet::from_iter([Rule::new("fold_add", 0, fold_add)]);
+
+        // (1 + 2) + 3
+        let expr = Add(
+            Box::new(Add(Box::new(Val(1)), Box::new(Val(2)))),
+            Box::new(Val(3)),
+        );
+        let (result, stats) = rules.rewrite(&expr);
+        assert_eq!(result, Val(6));
+
+        let fold_stats = stats.get("fold_add").unwrap();
+        assert_eq!(fold_stats.applications, 2);
+        assert_eq!(stats.applications(), 2);
+
+        // both additions are folded in the first pass; the second finds nothing.
+        assert_eq!(stats.passes(), 2);
+        assert_eq!(fold_stats.attempts, 5 + 1);
+    }
+
+    #[test]
+    fn rewrite_bi_vec() {
+        use Expr::*;
+
+        let rules = RuleSet::from_iter([
+            Rule::new("remove_double_negation", 1, remove_double_negation),
+            Rule::new("fold_add", 0, fold_add),
+        ]);
+
+        let model = vec![
+            Neg(Box::new(Neg(Box::new(Val(1))))),
+            Add(
+                Box::new(Val(1)),
+                Box::new(Neg(Box::new(Neg(Box::new(Val(2)))))),
+            ),
+            Var("x".into()),
+        ];
+        let (result, stats) = rules.rewrite_bi(&model);
+        assert_eq!(result, vec![Val(1), Val(3), Var("x".into())]);
+        assert_eq!(stats.get("remove_double_negation").unwrap().applications, 2);
+        assert_eq!(stats.get("fold_add").unwrap().applications, 1);
+    }
+
+    proptest! {
+        #[test]
+        fn single_rule_same_as_rewrite_fixpoint(expr in proptest_exprs()) {
+            let rules = RuleSet::from_iter([Rule::new("remove_double_negation", 0, remove_double_negation)]);
+            let (result, stats) = rules.rewrite(&expr);
+            prop_assert_eq!(result, expr.rewrite_fixpoint(&|x| remove_double_negation(&x)));
+
+            let negations = expr.count_node(&|x| matches!(x, Expr::Neg(_)));
+            let remaining = expr.rewrite_fixpoint(&|x| remove_double_negation(&x)).count_node(&|x| matches!(x, Expr::Neg(_)));
+            prop_assert_eq!(stats.applications() * 2, negations - remaining);
+        }
+
+        #[test]
+        fn rewrite_bi_same_as_rewrite_on_each_child(stmt in proptest_stmts()) {
+            let rules = RuleSet::from_iter([
+                Rule::new("remove_double_negation", 1, remove_double_negation),
+                Rule::new("fold_add", 0, fold_add),
+            ]);
+            let (result, stats) = rules.rewrite_bi(&stmt);
+            let expected = stmt.descend_bi(&|x: Expr| rules.rewrite(&x).0);
+            prop_assert_eq!(result, expected);
+            prop_assert!(stats.passes() >= 1);
+        }
+    }
+}
@@ -4,10 +4,19 @@
 mod biplate;
 pub use biplate::*;
 
+mod biplate_mut;
+pub use biplate_mut::*;
+
+mod biplate_ref;
+pub use biplate_ref::*;
+
+mod biplate_rebuild;
+pub use biplate_rebuild::*;
+
 mod uniplate;
 pub use uniplate::*;
 
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 /// A wrapper type used for auto-deref specialisation of `Biplate`.
 ///
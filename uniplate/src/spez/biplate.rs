@@ -1,5 +1,6 @@
 //! Specialisation helpers for biplate.
-use std::any::TypeId;
+use alloc::boxed::Box;
+use core::any::TypeId;
 
 /// Specialization proxy for [`uniplate::Biplate`].
 pub trait BiplateYes {
@@ -63,7 +64,7 @@ where
         // Biplate<T> for T returns self, not immediate childreen
         if TypeId::of::<Src>() == TypeId::of::<Dest>() {
             unsafe {
-                let this_as_dest: Dest = (std::mem::transmute::<&Src, &Dest>(&self.0)).clone();
+                let this_as_dest: Dest = (core::mem::transmute::<&Src, &Dest>(&self.0)).clone();
 
                 let tree = Tree::One(this_as_dest);
                 let ctx = Box::new(move |x| {
@@ -71,7 +72,7 @@ where
                         panic!();
                     };
 
-                    std::mem::transmute::<&Dest, &Src>(&x).clone()
+                    core::mem::transmute::<&Dest, &Src>(&x).clone()
                 });
 
                 (tree, ctx)
@@ -100,7 +101,7 @@ macro_rules! try_biplate_to {
         #[allow(unused_imports)]
         use ::uniplate::spez::{BiplateNo, BiplateYes, SpezBiplate};
         #[allow(clippy::needless_borrow)]
-        (&&SpezBiplate($x, std::marker::PhantomData::<$t>)).spez_try_biplate()
+        (&&SpezBiplate($x, ::core::marker::PhantomData::<$t>)).spez_try_biplate()
     }};
 }
 
@@ -134,6 +135,6 @@ macro_rules! impls_biplate_to {
         #[allow(unused_imports)]
         use ::uniplate::spez::{BiplateNo as _, BiplateYes as _, SpezBiplate};
         #[allow(clippy::needless_borrow)]
-        (&&SpezBiplate($x, std::marker::PhantomData::<$t>)).spez_impls_biplate()
+        (&&SpezBiplate($x, ::core::marker::PhantomData::<$t>)).spez_impls_biplate()
     }};
 }
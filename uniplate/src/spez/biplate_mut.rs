@@ -0,0 +1,135 @@
+//! Specialisation helpers for biplate_mut.
+
+use core::cell::Cell;
+
+use crate::BiplateMut;
+use crate::UniplateMut;
+use crate::impl_helpers::transmute_if_same_type_mut;
+use crate::{Biplate, Uniplate};
+
+use super::SpezBiplate;
+
+/// Specialization proxy for [`uniplate::BiplateMut`].
+///
+/// Specialisation dispatches through a shared reference, so the wrapped `&mut` is stored in a
+/// [`Cell`] and taken out when the method is called.
+pub trait BiplateMutYes {
+    /// The destination type of the biplate operation.
+    type Dest;
+
+    /// Calls `BiplateMut<Dest>` on the inner value.
+    ///
+    /// This method is called when the wrapped type implements `BiplateMut<Dest>`.
+    fn spez_try_for_each_child_bi_mut(&self, f: &mut impl FnMut(&mut Self::Dest));
+}
+
+/// Specialization proxy for [`uniplate::BiplateMut`].
+///
+/// This is chosen when the wrapped type implements `Biplate<Dest>` but not `BiplateMut<Dest>`,
+/// and fails to compile: skipping the value would make in-place traversals silently miss
+/// children that [`Biplate`] finds.
+pub trait BiplateMutMissing {
+    /// The type of the wrapped value.
+    type Src;
+
+    /// The destination type of the biplate operation.
+    type Dest;
+
+    /// Never callable, as the wrapped type does not implement `BiplateMut<Dest>`.
+    fn spez_try_for_each_child_bi_mut(&self, f: &mut impl FnMut(&mut Self::Dest))
+    where
+        Self::Src: HasBiplateMut<Self::Dest>;
+}
+
+/// Implemented by types that implement `BiplateMut<To>`.
+///
+/// This only exists to give a helpful error when a derived mutable instance finds a field that
+/// has a `Biplate` instance but no `BiplateMut` instance.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` implements `Biplate<{To}>` but not `BiplateMut<{To}>`",
+    label = "in-place traversals would skip the children of this field",
+    note = "derive `BiplateMut` with `#[uniplate(by_mut)]`, or implement it by hand"
+)]
+pub trait HasBiplateMut<To> {}
+
+impl<T: BiplateMut<To>, To: UniplateMut> HasBiplateMut<To> for T {}
+
+/// Specialization proxy for [`uniplate::BiplateMut`].
+pub trait BiplateMutNo {
+    /// The destination type of the biplate operation.
+    type Dest;
+
+    /// Fallback implementation used when the inner value doesn't implement `BiplateMut<Dest>`.
+    fn spez_try_for_each_child_bi_mut(&self, f: &mut impl FnMut(&mut Self::Dest));
+}
+
+impl<Src, Dest> BiplateMutYes for &&SpezBiplate<Cell<Option<&mut Src>>, Dest>
+where
+    Src: BiplateMut<Dest>,
+    Dest: UniplateMut,
+{
+    type Dest = Dest;
+
+    fn spez_try_for_each_child_bi_mut(&self, f: &mut impl FnMut(&mut Self::Dest)) {
+        if let Some(x) = self.0.take() {
+            x.for_each_child_bi_mut(f)
+        }
+    }
+}
+
+impl<Src, Dest> BiplateMutMissing for &SpezBiplate<Cell<Option<&mut Src>>, Dest>
+where
+    Src: Biplate<Dest>,
+    Dest: Uniplate,
+{
+    type Src = Src;
+    type Dest = Dest;
+
+    fn spez_try_for_each_child_bi_mut(&self, _: &mut impl FnMut(&mut Self::Dest))
+    where
+        Src: HasBiplateMut<Dest>,
+    {
+        unreachable!("BiplateMutYes is chosen for types that implement BiplateMut");
+    }
+}
+
+impl<Src, Dest> BiplateMutNo for SpezBiplate<Cell<Option<&mut Src>>, Dest>
+where
+    Src: 'static,
+    Dest: 'static,
+{
+    type Dest = Dest;
+
+    fn spez_try_for_each_child_bi_mut(&self, f: &mut impl FnMut(&mut Self::Dest)) {
+        // BiplateMut<T> for T visits self, not immediate children
+        if let Some(this) = self
+            .0
+            .take()
+            .and_then(transmute_if_same_type_mut::<Src, Dest>)
+        {
+            f(this)
+        }
+    }
+}
+
+#[doc(inline)]
+/// Tries to call `BiplateMut<$t>::for_each_child_bi_mut` on `$x` with the callback `$f`, doing
+/// nothing if `$x` implements neither `BiplateMut<$t>` nor `Biplate<$t>`.
+///
+/// If `$x` implements `Biplate<$t>` but not `BiplateMut<$t>`, this fails to compile.
+pub use crate::try_biplate_mut_to;
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! try_biplate_mut_to {
+    ($x:expr,$t:ty,$f:expr) => {{
+        #[allow(unused_imports)]
+        use ::uniplate::spez::{BiplateMutMissing, BiplateMutNo, BiplateMutYes, SpezBiplate};
+        #[allow(clippy::needless_borrow)]
+        (&&&SpezBiplate(
+            ::core::cell::Cell::new(Some($x)),
+            ::core::marker::PhantomData::<$t>,
+        ))
+            .spez_try_for_each_child_bi_mut($f)
+    }};
+}
@@ -0,0 +1,123 @@
+//! Specialisation helpers for the closure-free biplate methods.
+
+use alloc::vec::Vec;
+
+use crate::Biplate;
+use crate::Uniplate;
+use crate::impl_helpers::{cast_if_same_type, transmute_if_same_type};
+
+use super::SpezBiplate;
+
+/// Specialization proxy for [`Biplate::children_bi_into`] and [`Biplate::rebuild_bi`].
+pub trait BiplateRebuildYes<'a> {
+    /// The source type.
+    type Src: 'a;
+    /// The destination type of the biplate operation.
+    type Dest;
+
+    /// Calls `Biplate<Dest>::children_bi_into` on the inner value.
+    ///
+    /// This method is called when the wrapped type implements `Biplate<Dest>`.
+    fn spez_try_children_bi_into(&self, out: &mut Vec<Self::Dest>);
+
+    /// Calls `Biplate<Dest>::rebuild_bi` on the inner value.
+    ///
+    /// This method is called when the wrapped type implements `Biplate<Dest>`.
+    fn spez_try_rebuild_bi(&self, children: &mut impl Iterator<Item = Self::Dest>) -> Self::Src;
+}
+
+/// Specialization proxy for [`Biplate::children_bi_into`] and [`Biplate::rebuild_bi`].
+pub trait BiplateRebuildNo<'a> {
+    /// The source type.
+    type Src: 'a;
+    /// The destination type of the biplate operation.
+    type Dest;
+
+    /// Fallback implementation used when the inner value doesn't implement `Biplate<Dest>`.
+    fn spez_try_children_bi_into(&self, out: &mut Vec<Self::Dest>);
+
+    /// Fallback implementation used when the inner value doesn't implement `Biplate<Dest>`.
+    fn spez_try_rebuild_bi(&self, children: &mut impl Iterator<Item = Self::Dest>) -> Self::Src;
+}
+
+impl<'a, Src, Dest> BiplateRebuildYes<'a> for &SpezBiplate<&'a Src, Dest>
+where
+    Src: Biplate<Dest>,
+    Dest: Uniplate,
+{
+    type Src = Src;
+    type Dest = Dest;
+
+    #[inline(always)]
+    fn spez_try_children_bi_into(&self, out: &mut Vec<Self::Dest>) {
+        self.0.children_bi_into(out)
+    }
+
+    #[inline(always)]
+    fn spez_try_rebuild_bi(&self, children: &mut impl Iterator<Item = Self::Dest>) -> Self::Src {
+        self.0.rebuild_bi(children)
+    }
+}
+
+impl<'a, Src, Dest> BiplateRebuildNo<'a> for SpezBiplate<&'a Src, Dest>
+where
+    Src: Clone + 'static,
+    Dest: Clone + 'static,
+{
+    type Src = Src;
+    type Dest = Dest;
+
+    fn spez_try_children_bi_into(&self, out: &mut Vec<Self::Dest>) {
+        // Biplate<T> for T returns self, not immediate children
+        if let Some(this) = transmute_if_same_type::<Src, Dest>(self.0) {
+            out.push(this.clone());
+        }
+    }
+
+    fn spez_try_rebuild_bi(&self, children: &mut impl Iterator<Item = Self::Dest>) -> Self::Src {
+        if transmute_if_same_type::<Src, Dest>(self.0).is_some() {
+            let child = children
+                .next()
+                .expect("rebuild_bi() given too few children");
+            cast_if_same_type::<Dest, Src>(child).unwrap_or_else(|_| unreachable!())
+        } else {
+            self.0.clone()
+        }
+    }
+}
+
+#[doc(inline)]
+/// Tries to call `Biplate<$t>::children_bi_into` on `$x` with the output vector `$out`, doing
+/// nothing if `$x` does not implement `Biplate<$t>`.
+///
+/// `$x` must be a reference.
+pub use crate::try_children_bi_into;
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! try_children_bi_into {
+    ($x:expr,$t:ty,$out:expr) => {{
+        #[allow(unused_imports)]
+        use ::uniplate::spez::{BiplateRebuildNo, BiplateRebuildYes, SpezBiplate};
+        #[allow(clippy::needless_borrow)]
+        (&&SpezBiplate($x, ::core::marker::PhantomData::<$t>)).spez_try_children_bi_into($out)
+    }};
+}
+
+#[doc(inline)]
+/// Tries to call `Biplate<$t>::rebuild_bi` on `$x` with the children `$children`, returning a
+/// clone of `$x` if it does not implement `Biplate<$t>`.
+///
+/// `$x` must be a reference.
+pub use crate::try_rebuild_bi;
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! try_rebuild_bi {
+    ($x:expr,$t:ty,$children:expr) => {{
+        #[allow(unused_imports)]
+        use ::uniplate::spez::{BiplateRebuildNo, BiplateRebuildYes, SpezBiplate};
+        #[allow(clippy::needless_borrow)]
+        (&&SpezBiplate($x, ::core::marker::PhantomData::<$t>)).spez_try_rebuild_bi($children)
+    }};
+}
@@ -0,0 +1,120 @@
+//! Specialisation helpers for biplate_ref.
+
+use crate::BiplateRef;
+use crate::UniplateRef;
+use crate::impl_helpers::transmute_if_same_type;
+use crate::{Biplate, Uniplate};
+
+use super::SpezBiplate;
+
+/// Specialization proxy for [`uniplate::BiplateRef`].
+pub trait BiplateRefYes<'a> {
+    /// The destination type of the biplate operation.
+    type Dest: 'a;
+
+    /// Calls `BiplateRef<Dest>` on the inner value.
+    ///
+    /// This method is called when the wrapped type implements `BiplateRef<Dest>`.
+    fn spez_try_for_each_child_bi(&self, f: &mut impl FnMut(&'a Self::Dest));
+}
+
+/// Specialization proxy for [`uniplate::BiplateRef`].
+///
+/// This is chosen when the wrapped type implements `Biplate<Dest>` but not `BiplateRef<Dest>`,
+/// and fails to compile: skipping the value would make borrowing traversals silently miss
+/// children that [`Biplate`] finds.
+pub trait BiplateRefMissing<'a> {
+    /// The type of the wrapped value.
+    type Src: 'a;
+
+    /// The destination type of the biplate operation.
+    type Dest: 'a;
+
+    /// Never callable, as the wrapped type does not implement `BiplateRef<Dest>`.
+    fn spez_try_for_each_child_bi(&self, f: &mut impl FnMut(&'a Self::Dest))
+    where
+        Self::Src: HasBiplateRef<Self::Dest>;
+}
+
+/// Implemented by types that implement `BiplateRef<To>`.
+///
+/// This only exists to give a helpful error when a derived borrowing instance finds a field that
+/// has a `Biplate` instance but no `BiplateRef` instance.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` implements `Biplate<{To}>` but not `BiplateRef<{To}>`",
+    label = "borrowing traversals would skip the children of this field",
+    note = "derive `BiplateRef` with `#[uniplate(by_ref)]`, or implement it by hand"
+)]
+pub trait HasBiplateRef<To> {}
+
+impl<T: BiplateRef<To>, To: UniplateRef> HasBiplateRef<To> for T {}
+
+/// Specialization proxy for [`uniplate::BiplateRef`].
+pub trait BiplateRefNo<'a> {
+    /// The destination type of the biplate operation.
+    type Dest: 'a;
+
+    /// Fallback implementation used when the inner value doesn't implement `BiplateRef<Dest>`.
+    fn spez_try_for_each_child_bi(&self, f: &mut impl FnMut(&'a Self::Dest));
+}
+
+impl<'a, Src, Dest> BiplateRefYes<'a> for &&SpezBiplate<&'a Src, Dest>
+where
+    Src: BiplateRef<Dest>,
+    Dest: UniplateRef,
+{
+    type Dest = Dest;
+
+    fn spez_try_for_each_child_bi(&self, f: &mut impl FnMut(&'a Self::Dest)) {
+        self.0.for_each_child_bi(f)
+    }
+}
+
+impl<'a, Src, Dest> BiplateRefMissing<'a> for &SpezBiplate<&'a Src, Dest>
+where
+    Src: Biplate<Dest>,
+    Dest: Uniplate,
+{
+    type Src = Src;
+    type Dest = Dest;
+
+    fn spez_try_for_each_child_bi(&self, _: &mut impl FnMut(&'a Self::Dest))
+    where
+        Src: HasBiplateRef<Dest>,
+    {
+        unreachable!("BiplateRefYes is chosen for types that implement BiplateRef");
+    }
+}
+
+impl<'a, Src, Dest> BiplateRefNo<'a> for SpezBiplate<&'a Src, Dest>
+where
+    Src: 'static,
+    Dest: 'static,
+{
+    type Dest = Dest;
+
+    fn spez_try_for_each_child_bi(&self, f: &mut impl FnMut(&'a Self::Dest)) {
+        // BiplateRef<T> for T visits self, not immediate children
+        if let Some(this) = transmute_if_same_type::<Src, Dest>(self.0) {
+            f(this)
+        }
+    }
+}
+
+#[doc(inline)]
+/// Tries to call `BiplateRef<$t>::for_each_child_bi` on `$x` with the callback `$f`, doing
+/// nothing if `$x` implements neither `BiplateRef<$t>` nor `Biplate<$t>`.
+///
+/// If `$x` implements `Biplate<$t>` but not `BiplateRef<$t>`, this fails to compile.
+pub use crate::try_biplate_ref_to;
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! try_biplate_ref_to {
+    ($x:expr,$t:ty,$f:expr) => {{
+        #[allow(unused_imports)]
+        use ::uniplate::spez::{BiplateRefMissing, BiplateRefNo, BiplateRefYes, SpezBiplate};
+        #[allow(clippy::needless_borrow)]
+        (&&&SpezBiplate($x, ::core::marker::PhantomData::<$t>)).spez_try_for_each_child_bi($f)
+    }};
+}
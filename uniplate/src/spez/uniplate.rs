@@ -73,6 +73,8 @@ macro_rules! try_uniplate {
         (&&SpezUniplate($x)).spez_try_biplate()
     }};
 }
+use alloc::boxed::Box;
+
 use crate::Tree;
 use crate::Uniplate;
 #[doc(inline)]
@@ -0,0 +1,626 @@
+//! Composable rewrite strategies.
+//!
+//! A [`Strategy<T>`] is a transformation that may fail: applied to a value, it either returns a
+//! new value or `None`. Strategies are built from rules (functions `T -> Option<T>`), and
+//! combined using the functions in this module, in the style of
+//! [Stratego](https://spoofax.dev/references/stratego/) and ELAN.
+//!
+//! The primitive combinators are:
+//!
+//!   - [`id`] and [`fail`], which always succeed and always fail respectively;
+//!   - [`seq`] and [`choice`], for sequential composition and left-biased choice;
+//!   - [`try_`] and [`repeat`];
+//!   - [`all`], [`one`] and [`some`], which apply a strategy to the children of a node.
+//!
+//! From these, the usual traversals are derived: [`bottom_up`], [`top_down`], [`innermost`],
+//! [`outermost`], [`once_bu`] and [`once_td`].
+//!
+//! Strategies operate on [`Uniplate`] types. To apply a strategy for `To` to the values of type
+//! `To` inside a `From`, lift it with [`all_bi`], [`one_bi`] or [`some_bi`].
+//!
+//! Rule applications can be observed using [`Strategy::apply_observed`]; give rules names with
+//! [`Strategy::named`] to tell them apart. See [`crate::observer`].
+//!
+//! # Example
+//!
+//! ```
+//! use uniplate::Uniplate;
+//! use uniplate::strategy::{Strategy, innermost};
+//!
+//! #[derive(Clone, PartialEq, Eq, Debug, Uniplate)]
+//! enum Expr {
+//!     Val(i32),
+//!     Neg(Box<Expr>),
+//!     Add(Box<Expr>, Box<Expr>),
+//! }
+//!
+//! use Expr::*;
+//!
+//! let remove_double_negation = Strategy::new(|x| match x {
+//!     Neg(x) => match *x {
+//!         Neg(x) => Some(*x),
+//!         _ => None,
+//!     },
+//!     _ => None,
+//! });
+//!
+//! let fold_constants = Strategy::new(|x| match x {
+//!     Add(a, b) => match (*a, *b) {
+//!         (Val(a), Val(b)) => Some(Val(a + b)),
+//!         _ => None,
+//!     },
+//!     _ => None,
+//! });
+//!
+//! let normalise = innermost(uniplate::strategy::choice(remove_double_negation, fold_constants));
+//!
+//! // --(1 + --2)
+//! let expr = Neg(Box::new(Neg(Box::new(Add(
+//!     Box::new(Val(1)),
+//!     Box::new(Neg(Box::new(Neg(Box::new(Val(2)))))),
+//! )))));
+//!
+//! assert_eq!(normalise.apply(expr), Some(Val(3)));
+//! ```
+
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::marker::PhantomData;
+
+use crate::observer::{ANONYMOUS_RULE, Erased, ErasedObserver, RewriteObserver};
+use crate::path::Path;
+use crate::{Biplate, Uniplate};
+
+/// A transformation on values of type `T` that may fail.
+///
+/// See the module-level documentation.
+pub struct Strategy<T: Uniplate> {
+    f: Arc<StrategyFn<T>>,
+}
+
+type StrategyFn<T> = dyn Fn(T, &mut Cx<'_>) -> Option<T>;
+
+/// The context a strategy is applied in.
+struct Cx<'a> {
+    /// The path of the current node.
+    path: Path,
+
+    observer: Option<&'a mut dyn ErasedObserver>,
+}
+
+impl Cx<'_> {
+    /// Runs `f` on the `index`th child of the current node.
+    fn at<R>(&mut self, index: usize, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.path.push(index);
+        let result = f(self);
+        self.path.pop();
+        result
+    }
+}
+
+impl<T: Uniplate> Clone for Strategy<T> {
+    fn clone(&self) -> Self {
+        Strategy {
+            f: Arc::clone(&self.f),
+        }
+    }
+}
+
+impl<T: Uniplate> Strategy<T> {
+    /// Creates a strategy from a rule.
+    ///
+    /// The rule succeeds if it returns `Some`, and fails if it returns `None`.
+    ///
+    /// When observed, applications of the rule are reported with the name
+    /// [`ANONYMOUS_RULE`]. To give the rule a name, use [`named`](Strategy::named).
+    pub fn new(f: impl Fn(T) -> Option<T> + 'static) -> Strategy<T> {
+        Strategy::named(ANONYMOUS_RULE, f)
+    }
+
+    /// Creates a strategy from a named rule.
+    ///
+    /// The name is used when reporting applications of the rule to an observer.
+    pub fn named(name: impl Into<String>, f: impl Fn(T) -> Option<T> + 'static) -> Strategy<T> {
+        let name: String = name.into();
+        Strategy::from_fn(move |x, cx| match cx.observer.as_deref_mut() {
+            None => f(x),
+            Some(observer) => {
+                let y = f(x.clone())?;
+                observer.on_rule_applied(&name, &cx.path, &x, &y);
+                Some(y)
+            }
+        })
+    }
+
+    /// Applies this strategy to `x`, returning `None` if it fails.
+    pub fn apply(&self, x: T) -> Option<T> {
+        self.run(
+            x,
+            &mut Cx {
+                path: Path::root(),
+                observer: None,
+            },
+        )
+    }
+
+    /// Applies this strategy to `x`, reporting each successful rule application to `observer`.
+    ///
+    /// Only applications of rules to values of type `U` are reported. The application of the
+    /// strategy is reported as a single pass. See [`crate::observer`].
+    ///
+    /// Observed variant of [`apply`](Strategy::apply).
+    pub fn apply_observed<U: Uniplate>(
+        &self,
+        x: T,
+        observer: &mut impl RewriteObserver<U>,
+    ) -> Option<T> {
+        observer.on_pass_start(1);
+        let result = self.run(
+            x.clone(),
+            &mut Cx {
+                path: Path::root(),
+                observer: Some(&mut Erased(&mut *observer, PhantomData)),
+            },
+        );
+        observer.on_pass_end(1, result.as_ref().is_some_and(|y| *y != x));
+        result
+    }
+
+    fn from_fn(f: impl Fn(T, &mut Cx<'_>) -> Option<T> + 'static) -> Strategy<T> {
+        Strategy { f: Arc::new(f) }
+    }
+
+    fn run(&self, x: T, cx: &mut Cx<'_>) -> Option<T> {
+        (self.f)(x, cx)
+    }
+}
+
+/// The strategy that always succeeds, leaving its input unchanged.
+pub fn id<T: Uniplate>() -> Strategy<T> {
+    Strategy::from_fn(|x, _| Some(x))
+}
+
+/// The strategy that always fails.
+pub fn fail<T: Uniplate>() -> Strategy<T> {
+    Strategy::from_fn(|_, _| None)
+}
+
+/// Applies `s1`, then `s2` to its result.
+///
+/// Fails if either strategy fails.
+pub fn seq<T: Uniplate>(s1: Strategy<T>, s2: Strategy<T>) -> Strategy<T> {
+    Strategy::from_fn(move |x, cx| s2.run(s1.run(x, cx)?, cx))
+}
+
+/// Applies `s1`, or `s2` to the original input if `s1` fails.
+///
+/// Fails if both strategies fail.
+pub fn choice<T: Uniplate>(s1: Strategy<T>, s2: Strategy<T>) -> Strategy<T> {
+    Strategy::from_fn(move |x: T, cx| s1.run(x.clone(), cx).or_else(|| s2.run(x, cx)))
+}
+
+/// Applies `s`, leaving the input unchanged if it fails.
+///
+/// This strategy never fails.
+pub fn try_<T: Uniplate>(s: Strategy<T>) -> Strategy<T> {
+    choice(s, id())
+}
+
+/// Applies `s` repeatedly until it fails, returning the last successful result.
+///
+/// This strategy never fails. If `s` always succeeds, it does not terminate.
+pub fn repeat<T: Uniplate>(s: Strategy<T>) -> Strategy<T> {
+    Strategy::from_fn(move |mut x: T, cx| {
+        while let Some(y) = s.run(x.clone(), cx) {
+            x = y;
+        }
+        Some(x)
+    })
+}
+
+/// Applies `s` to all children of the input.
+///
+/// Fails if `s` fails on any child. Succeeds on nodes with no children.
+pub fn all<T: Uniplate>(s: Strategy<T>) -> Strategy<T> {
+    Strategy::from_fn(move |x: T, cx| all_children(&x, cx, &s))
+}
+
+/// Applies `s` to the left-most child of the input for which it succeeds.
+///
+/// Fails if `s` fails on every child, including if there are no children.
+pub fn one<T: Uniplate>(s: Strategy<T>) -> Strategy<T> {
+    Strategy::from_fn(move |x: T, cx| one_child(&x, cx, &s))
+}
+
+/// Applies `s` to all children of the input for which it succeeds.
+///
+/// Fails if `s` fails on every child, including if there are no children.
+pub fn some<T: Uniplate>(s: Strategy<T>) -> Strategy<T> {
+    Strategy::from_fn(move |x: T, cx| {
+        let new_children = apply_some(x.children(), cx, &s)?;
+        Some(x.with_children(new_children))
+    })
+}
+
+/// Applies `s` to every node, bottom up.
+///
+/// Fails if `s` fails on any node. `bottom_up(try_(s))` is equivalent to
+/// [`transform`](Uniplate::transform).
+pub fn bottom_up<T: Uniplate>(s: Strategy<T>) -> Strategy<T> {
+    fn go<T: Uniplate>(s: &Strategy<T>, x: T, cx: &mut Cx<'_>) -> Option<T> {
+        let x = all_children_with(&x, cx, &|child, cx| go(s, child, cx))?;
+        s.run(x, cx)
+    }
+    Strategy::from_fn(move |x, cx| go(&s, x, cx))
+}
+
+/// Applies `s` to every node, top down.
+///
+/// The traversal continues into the children of the node returned by `s`. Fails if `s` fails on
+/// any node.
+pub fn top_down<T: Uniplate>(s: Strategy<T>) -> Strategy<T> {
+    fn go<T: Uniplate>(s: &Strategy<T>, x: T, cx: &mut Cx<'_>) -> Option<T> {
+        let x = s.run(x, cx)?;
+        all_children_with(&x, cx, &|child, cx| go(s, child, cx))
+    }
+    Strategy::from_fn(move |x, cx| go(&s, x, cx))
+}
+
+/// Applies `s` bottom up, normalising the result of each successful application, until it can
+/// no longer be applied anywhere.
+///
+/// This strategy never fails. `innermost(s)` is equivalent to
+/// [`rewrite_fixpoint`](Uniplate::rewrite_fixpoint).
+pub fn innermost<T: Uniplate>(s: Strategy<T>) -> Strategy<T> {
+    fn go<T: Uniplate>(s: &Strategy<T>, x: T, cx: &mut Cx<'_>) -> T {
+        let x =
+            all_children_with(&x, cx, &|child, cx| Some(go(s, child, cx))).expect("never fails");
+        match s.run(x.clone(), cx) {
+            Some(y) => go(s, y, cx),
+            None => x,
+        }
+    }
+    Strategy::from_fn(move |x, cx| Some(go(&s, x, cx)))
+}
+
+/// Applies `s` to the outermost node where it succeeds, repeatedly, until it can no longer be
+/// applied anywhere.
+///
+/// This strategy never fails.
+pub fn outermost<T: Uniplate>(s: Strategy<T>) -> Strategy<T> {
+    repeat(once_td(s))
+}
+
+/// Applies `s` once, to the first node bottom up (the left-most innermost node) where it
+/// succeeds.
+///
+/// Fails if `s` fails on every node.
+pub fn once_bu<T: Uniplate>(s: Strategy<T>) -> Strategy<T> {
+    fn go<T: Uniplate>(s: &Strategy<T>, x: T, cx: &mut Cx<'_>) -> Option<T> {
+        one_child_with(&x, cx, &|child, cx| go(s, child, cx)).or_else(|| s.run(x, cx))
+    }
+    Strategy::from_fn(move |x, cx| go(&s, x, cx))
+}
+
+/// Applies `s` once, to the first node top down (in preorder) where it succeeds.
+///
+/// Fails if `s` fails on every node.
+pub fn once_td<T: Uniplate>(s: Strategy<T>) -> Strategy<T> {
+    fn go<T: Uniplate>(s: &Strategy<T>, x: T, cx: &mut Cx<'_>) -> Option<T> {
+        s.run(x.clone(), cx)
+            .or_else(|| one_child_with(&x, cx, &|child, cx| go(s, child, cx)))
+    }
+    Strategy::from_fn(move |x, cx| go(&s, x, cx))
+}
+
+/// Lifts `s` to apply to all values of type `To` inside a `From`.
+///
+/// Biplate variant of [`all`]: fails if `s` fails on any of the values. To apply a traversal to
+/// the values of type `To`, lift the traversal, e.g. `all_bi(bottom_up(s))`.
+pub fn all_bi<From, To>(s: Strategy<To>) -> Strategy<From>
+where
+    From: Biplate<To>,
+    To: Uniplate,
+{
+    Strategy::from_fn(move |x: From, cx| {
+        let new_children = apply_all(x.children_bi(), cx, &|child, cx| s.run(child, cx))?;
+        Some(x.with_children_bi(new_children))
+    })
+}
+
+/// Lifts `s` to apply to the left-most value of type `To` inside a `From` for which it succeeds.
+///
+/// Biplate variant of [`one`].
+pub fn one_bi<From, To>(s: Strategy<To>) -> Strategy<From>
+where
+    From: Biplate<To>,
+    To: Uniplate,
+{
+    Strategy::from_fn(move |x: From, cx| {
+        let new_children = apply_one(x.children_bi(), cx, &|child, cx| s.run(child, cx))?;
+        Some(x.with_children_bi(new_children))
+    })
+}
+
+/// Lifts `s` to apply to all values of type `To` inside a `From` for which it succeeds.
+///
+/// Biplate variant of [`some`].
+pub fn some_bi<From, To>(s: Strategy<To>) -> Strategy<From>
+where
+    From: Biplate<To>,
+    To: Uniplate,
+{
+    Strategy::from_fn(move |x: From, cx| {
+        let new_children = apply_some(x.children_bi(), cx, &s)?;
+        Some(x.with_children_bi(new_children))
+    })
+}
+
+/// Applies `s` to all children of `x`, failing if it fails on any of them.
+fn all_children<T: Uniplate>(x: &T, cx: &mut Cx<'_>, s: &Strategy<T>) -> Option<T> {
+    all_children_with(x, cx, &|child, cx| s.run(child, cx))
+}
+
+/// Applies `f` to all children of `x`, failing if it fails on any of them.
+fn all_children_with<T: Uniplate>(
+    x: &T,
+    cx: &mut Cx<'_>,
+    f: &impl Fn(T, &mut Cx<'_>) -> Option<T>,
+) -> Option<T> {
+    Some(x.with_children(apply_all(x.children(), cx, f)?))
+}
+
+/// Applies `s` to the left-most child of `x` for which it succeeds.
+fn one_child<T: Uniplate>(x: &T, cx: &mut Cx<'_>, s: &Strategy<T>) -> Option<T> {
+    one_child_with(x, cx, &|child, cx| s.run(child, cx))
+}
+
+/// Applies `f` to the left-most child of `x` for which it succeeds.
+fn one_child_with<T: Uniplate>(
+    x: &T,
+    cx: &mut Cx<'_>,
+    f: &impl Fn(T, &mut Cx<'_>) -> Option<T>,
+) -> Option<T> {
+    Some(x.with_children(apply_one(x.children(), cx, f)?))
+}
+
+/// Applies `f` to each element of `xs`, failing if it fails on any of them.
+fn apply_all<T>(
+    xs: VecDeque<T>,
+    cx: &mut Cx<'_>,
+    f: &impl Fn(T, &mut Cx<'_>) -> Option<T>,
+) -> Option<VecDeque<T>> {
+    xs.into_iter()
+        .enumerate()
+        .map(|(i, x)| cx.at(i, |cx| f(x, cx)))
+        .collect()
+}
+
+/// Applies `f` to the first element of `xs` for which it succeeds, failing if it fails on all of
+/// them.
+fn apply_one<T: Clone>(
+    mut xs: VecDeque<T>,
+    cx: &mut Cx<'_>,
+    f: &impl Fn(T, &mut Cx<'_>) -> Option<T>,
+) -> Option<VecDeque<T>> {
+    for i in 0..xs.len() {
+        if let Some(y) = cx.at(i, |cx| f(xs[i].clone(), cx)) {
+            xs[i] = y;
+            return Some(xs);
+        }
+    }
+    None
+}
+
+/// Applies `s` to each element of `xs`, keeping the original where it fails.
+///
+/// Fails if `s` fails on all elements.
+fn apply_some<T: Uniplate>(
+    xs: VecDeque<T>,
+    cx: &mut Cx<'_>,
+    s: &Strategy<T>,
+) -> Option<VecDeque<T>> {
+    let mut any_succeeded = false;
+    let new_xs = xs
+        .into_iter()
+        .enumerate()
+        .map(|(i, x)| match cx.at(i, |cx| s.run(x.clone(), cx)) {
+            Some(y) => {
+                any_succeeded = true;
+                y
+            }
+            None => x,
+        })
+        .collect();
+    any_succeeded.then_some(new_xs)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::test_common::paper::{Expr, Stmt, proptest_exprs, proptest_stmts};
+
+    use super::Strategy;
+    use super::*;
+
+    fn negate(expr: Expr) -> Option<Expr> {
+        match expr {
+            Expr::Val(x) => Some(Expr::Val(x.wrapping_neg())),
+            _ => None,
+        }
+    }
+
+    fn remove_double_negation(expr: Expr) -> Option<Expr> {
+        match expr {
+            Expr::Neg(x) => match *x {
+                Expr::Neg(y) => Some(*y),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn push_negation(expr: Expr) -> Option<Expr> {
+        match expr {
+            Expr::Neg(x) => match *x {
+                Expr::Neg(y) => Some(*y),
+                Expr::Add(a, b) => Some(Expr::Add(Box::new(Expr::Neg(a)), Box::new(Expr::Neg(b)))),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_primitives() {
+        use Expr::*;
+
+        let expr = Add(Box::new(Val(1)), Box::new(Var("x".into())));
+        let negate = Strategy::new(negate);
+
+        assert_eq!(id().apply(expr.clone()), Some(expr.clone()));
+        assert_eq!(fail().apply(expr.clone()), None);
+
+        assert_eq!(negate.apply(expr.clone()), None);
+        assert_eq!(try_(negate.clone()).apply(expr.clone()), Some(expr.clone()));
+        assert_eq!(
+            seq(negate.clone(), negate.clone()).apply(Val(1)),
+            Some(Val(1))
+        );
+        assert_eq!(choice(fail(), negate.clone()).apply(Val(1)), Some(Val(-1)));
+
+        // Var fails, so all fails.
+        assert_eq!(all(negate.clone()).apply(expr.clone()), None);
+        let expected = Add(Box::new(Val(-1)), Box::new(Var("x".into())));
+        assert_eq!(
+            one(negate.clone()).apply(expr.clone()),
+            Some(expected.clone())
+        );
+        assert_eq!(some(negate.clone()).apply(expr.clone()), Some(expected));
+
+        // No children.
+        assert_eq!(all(negate.clone()).apply(Val(1)), Some(Val(1)));
+        assert_eq!(one(negate.clone()).apply(Val(1)), None);
+        assert_eq!(some(negate.clone()).apply(Val(1)), None);
+    }
+
+    #[test]
+    fn test_repeat() {
+        let decrement = Strategy::new(|x| match x {
+            Expr::Val(n) if n > 0 => Some(Expr::Val(n - 1)),
+            _ => None,
+        });
+        assert_eq!(repeat(decrement).apply(Expr::Val(5)), Some(Expr::Val(0)));
+    }
+
+    #[test]
+    fn test_once() {
+        use Expr::*;
+
+        // (1 + 2) + 3
+        let expr = Add(
+            Box::new(Add(Box::new(Val(1)), Box::new(Val(2)))),
+            Box::new(Val(3)),
+        );
+        let fold_add = Strategy::new(|x| match x {
+            Add(a, b) => match (*a, *b) {
+                (Val(a), Val(b)) => Some(Val(a + b)),
+                _ => None,
+            },
+            _ => None,
+        });
+        let wrap_add = Strategy::new(|x| match x {
+            Add(_, _) => Some(Neg(Box::new(x))),
+            _ => None,
+        });
+
+        assert_eq!(
+            once_bu(fold_add.clone()).apply(expr.clone()),
+            Some(Add(Box::new(Val(3)), Box::new(Val(3))))
+        );
+        assert_eq!(once_td(fold_add.clone()).apply(Val(1)), None);
+
+        // once_td applies to the outermost node only.
+        assert_eq!(
+            once_td(wrap_add.clone()).apply(expr.clone()),
+            Some(Neg(Box::new(expr.clone())))
+        );
+        assert_eq!(
+            once_bu(wrap_add).apply(expr.clone()),
+            Some(Add(
+                Box::new(Neg(Box::new(Add(Box::new(Val(1)), Box::new(Val(2)))))),
+                Box::new(Val(3)),
+            ))
+        );
+
+        assert_eq!(outermost(fold_add).apply(expr), Some(Val(6)));
+    }
+
+    proptest! {
+        #[test]
+        fn test_bottom_up_try_same_as_transform(expr in proptest_exprs()) {
+            let s = bottom_up(try_(Strategy::new(negate)));
+            let expected = expr.transform(&|x| negate(x.clone()).unwrap_or(x));
+            prop_assert_eq!(s.apply(expr), Some(expected));
+        }
+
+        #[test]
+        fn test_top_down_try_same_as_transform_top_down(expr in proptest_exprs()) {
+            let s = top_down(try_(Strategy::new(push_negation)));
+            let expected = expr.transform_top_down(&|x| push_negation(x.clone()).unwrap_or(x));
+            prop_assert_eq!(s.apply(expr), Some(expected));
+        }
+
+        #[test]
+        fn test_innermost_same_as_rewrite_fixpoint(expr in proptest_exprs()) {
+            let s = innermost(Strategy::new(push_negation));
+            prop_assert_eq!(s.apply(expr.clone()), Some(expr.rewrite_fixpoint(&push_negation)));
+        }
+
+        #[test]
+        fn test_outermost_is_normal_form(expr in proptest_exprs()) {
+            let result = outermost(Strategy::new(remove_double_negation)).apply(expr).unwrap();
+            prop_assert!(!result.any_node(&|x| remove_double_negation(x.clone()).is_some()));
+        }
+
+        #[test]
+        fn test_bottom_up_fails_if_rule_fails_anywhere(expr in proptest_exprs()) {
+            let s = bottom_up(Strategy::new(negate));
+            let all_vals = expr.all_node(&|x| matches!(x, Expr::Val(_)));
+            prop_assert_eq!(s.apply(expr).is_some(), all_vals);
+        }
+
+        #[test]
+        fn test_all_bi_bottom_up_same_as_transform_bi(stmt in proptest_stmts()) {
+            let s: Strategy<Stmt> = all_bi(bottom_up(try_(Strategy::new(negate))));
+            let expected = stmt.transform_bi(&|x: Expr| negate(x.clone()).unwrap_or(x));
+            prop_assert_eq!(s.apply(stmt), Some(expected));
+        }
+
+        #[test]
+        fn test_one_bi_and_some_bi(stmt in proptest_stmts()) {
+            let is_val = |x: &Expr| matches!(x, Expr::Val(_));
+            let children: VecDeque<Expr> = stmt.children_bi();
+
+            let one_result = one_bi(Strategy::new(negate)).apply(stmt.clone());
+            match children.iter().position(is_val) {
+                Some(i) => {
+                    let mut expected = children.clone();
+                    expected[i] = negate(expected[i].clone()).unwrap();
+                    prop_assert_eq!(one_result, Some(stmt.with_children_bi(expected)));
+                }
+                None => prop_assert_eq!(one_result, None),
+            }
+
+            let some_result = some_bi(Strategy::new(negate)).apply(stmt.clone());
+            if children.iter().any(is_val) {
+                let expected = children.into_iter().map(|x| negate(x.clone()).unwrap_or(x)).collect();
+                prop_assert_eq!(some_result, Some(stmt.with_children_bi(expected)));
+            } else {
+                prop_assert_eq!(some_result, None);
+            }
+        }
+    }
+}
@@ -2,10 +2,9 @@
 //!
 //! See the [`Zipper`] type for more information on how to use this type.
 
-use std::{
-    cell::{Ref, RefCell, RefMut},
-    rc::Rc,
-};
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::{Ref, RefCell, RefMut};
 
 use crate::{Uniplate, zipper::Zipper};
 
@@ -167,7 +166,7 @@ where
 
     /// Replaces the tag of the current focus, returning the old tag.
     pub fn replace_tag(&mut self, new_tag: D) -> D {
-        std::mem::replace(&mut self.tag_node.borrow_mut().data, new_tag)
+        core::mem::replace(&mut self.tag_node.borrow_mut().data, new_tag)
     }
 
     /// Resets the tag of the current focus to the value returned by the constructor,
@@ -188,7 +187,7 @@ where
             parent: parent_node,
             children: Vec::new(),
         }));
-        let _ = std::mem::replace(&mut self.tag_node, new_tag);
+        let _ = core::mem::replace(&mut self.tag_node, new_tag);
     }
 
     /// Sets the focus to the parent of the focus (if it exists).
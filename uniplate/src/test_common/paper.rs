@@ -1,4 +1,5 @@
 use crate::Uniplate;
+use alloc::{boxed::Box, string::String, vec::Vec};
 
 // Examples found in the Uniplate paper.
 
@@ -6,6 +7,7 @@ use crate::Uniplate;
 #[derive(Eq, PartialEq, Clone, Debug, Uniplate)]
 #[biplate(to=Expr)]
 #[biplate(to=String,)]
+#[uniplate(by_ref, by_mut)]
 pub enum Stmt {
     Assign(String, Expr),
     Sequence(Vec<Stmt>),
@@ -16,6 +18,7 @@ pub enum Stmt {
 #[derive(Eq, PartialEq, Clone, Debug, Uniplate)]
 #[biplate(to=String)]
 #[biplate(to=Stmt)]
+#[uniplate(by_ref, by_mut)]
 pub enum Expr {
     Add(Box<Expr>, Box<Expr>),
     Sub(Box<Expr>, Box<Expr>),
@@ -80,7 +83,7 @@ mod proptest {
 
     #[test]
     fn uniplate_children(ast in proptest_stmts(), new_children in proptest::collection::vec(proptest_stmts(),1..=10)) {
-        use std::collections::VecDeque;
+        use alloc::collections::VecDeque;
         let original_children = ast.children();
         prop_assume!(original_children.len() == new_children.len());
 
@@ -3,8 +3,26 @@
 #![allow(clippy::type_complexity)]
 
 mod biplate;
-mod context;
-mod holes;
+mod biplate_mut;
+mod biplate_ref;
+mod changed;
+mod control;
+mod order;
+#[cfg(feature = "std")]
+mod par;
 mod uniplate;
+mod uniplate_mut;
+mod uniplate_ref;
+mod universe;
 
-pub use {biplate::Biplate, uniplate::Uniplate};
+pub use {
+    biplate::Biplate,
+    biplate_mut::BiplateMut,
+    biplate_ref::BiplateRef,
+    changed::Transformed,
+    control::{TransformControl, VisitControl},
+    order::TraversalOrder,
+    uniplate::Uniplate,
+    uniplate_mut::UniplateMut,
+    uniplate_ref::UniplateRef,
+};
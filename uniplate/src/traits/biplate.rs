@@ -1,7 +1,19 @@
-use super::holes::HolesIterBi;
-use super::{Uniplate, context::ContextIterBi};
+use super::changed::{self, Transformed};
+#[cfg(feature = "std")]
+use super::par;
+use super::uniplate::too_few_children;
+use super::universe::{LevelOrderIter, OrderedUniverseIter, PositionsIter, UniverseIter, visit};
+use super::{TransformControl, TraversalOrder, Uniplate, VisitControl};
+use crate::context::{self, ContextBi};
 
-use std::collections::VecDeque;
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use crate::limits::{Limits, RewriteLimitExceeded};
+use crate::observer::{self, RewriteObserver};
+use crate::path::{self, Path, PathError};
+use crate::zipper::ZipperBi;
 
 pub use crate::Tree;
 /// `Biplate<U>` for type `T` operates over all values of type `U` within `T`.
@@ -20,8 +32,38 @@ where
     /// Returns all the top most children of type `To` within `From`.
     ///
     /// If from == to then this function should return the root as the single child.
+    ///
+    /// As with [`Uniplate::uniplate`], [`children_bi_into`](Biplate::children_bi_into) and
+    /// [`rebuild_bi`](Biplate::rebuild_bi) are provided in terms of this method, and instances that
+    /// override them can implement it with
+    /// [`biplate_via_rebuild`](crate::impl_helpers::biplate_via_rebuild).
     fn biplate(&self) -> (Tree<To>, Box<dyn Fn(Tree<To>) -> Self>);
 
+    /// Appends the top most children of type `To` within `From` to `out`, from left to right.
+    ///
+    /// If from == to then this function should push the root.
+    ///
+    /// Biplate variant of [`Uniplate::children_into`]
+    fn children_bi_into(&self, out: &mut Vec<To>) {
+        let (children, _) = self.biplate();
+        out.extend(children);
+    }
+
+    /// Reconstructs the node, taking its children of type `To` from `children`.
+    ///
+    /// Biplate variant of [`Uniplate::rebuild`]
+    ///
+    /// # Panics
+    ///
+    /// If `children` runs out.
+    fn rebuild_bi(&self, children: &mut impl Iterator<Item = To>) -> Self {
+        let (old_children, ctx) = self.biplate();
+        let new_children = old_children
+            .try_map_children(&mut |_| children.next().ok_or(()))
+            .unwrap_or_else(|()| panic!("rebuild_bi() given too few children"));
+        ctx(new_children)
+    }
+
     /// Reconstructs the node with the given children.
     ///
     /// Biplate variant of [`Uniplate::children`]
@@ -31,18 +73,12 @@ where
     /// If there are a different number of children given as there were originally returned by
     /// children().
     fn with_children_bi(&self, children: VecDeque<To>) -> Self {
-        // 1. Turn old tree into list.
-        // 2. Check lists are same size.
-        // 3. Use the reconstruction function given by old_children.list() to
-        //   create a tree with the same structure but the new lists' elements .
-
-        let (old_children, ctx) = self.biplate();
-        let (old_children_lst, rebuild) = old_children.list();
-        if old_children_lst.len() != children.len() {
+        let mut children = children.into_iter();
+        let node = self.rebuild_bi(&mut children.by_ref().chain(too_few_children()));
+        if children.next().is_some() {
             panic!("with_children() given an unexpected amount of children");
-        } else {
-            ctx(rebuild(children))
         }
+        node
     }
 
     /// Biplate variant of [`Uniplate::descend`]
@@ -52,8 +88,34 @@ where
     /// pattern is to first match the types using descend_bi, then continue the recursion with
     /// descend.
     fn descend_bi(&self, op: &impl Fn(To) -> To) -> Self {
-        let (children, ctx) = self.biplate();
-        ctx(children.map(op))
+        self.rebuild_bi(&mut self.children_bi().into_iter().map(op))
+    }
+
+    /// Biplate variant of [`Uniplate::try_descend`]
+    ///
+    /// As with [`descend_bi`](Biplate::descend_bi), if from == to then this function does not
+    /// descend.
+    fn try_descend_bi<E>(&self, op: &impl Fn(To) -> Result<To, E>) -> Result<Self, E> {
+        let children: Vec<To> = self
+            .children_bi()
+            .into_iter()
+            .map(op)
+            .collect::<Result<_, _>>()?;
+        Ok(self.rebuild_bi(&mut children.into_iter()))
+    }
+
+    /// Biplate variant of [`Uniplate::descend_accum`]
+    ///
+    /// As with [`descend_bi`](Biplate::descend_bi), if from == to then this function does not
+    /// descend.
+    fn descend_accum_bi<S>(&self, state: S, f: &mut impl FnMut(S, To) -> (S, To)) -> (S, Self) {
+        let mut new_children = Vec::new();
+        let state = self.children_bi().into_iter().fold(state, |state, child| {
+            let (state, child) = f(state, child);
+            new_children.push(child);
+            state
+        });
+        (state, self.rebuild_bi(&mut new_children.into_iter()))
     }
 
     // NOTE (niklasdewally): Uniplate does something different here, and  I don't know why. In
@@ -72,17 +134,138 @@ where
     ///
     /// If to == from then it returns the original element.
     fn universe_bi(&self) -> VecDeque<To> {
-        self.children_bi()
-            .into_iter()
-            .flat_map(|child| child.universe())
-            .collect()
+        self.universe_bi_iter().collect()
+    }
+
+    /// Returns an iterator over all children of type `To`, and all of their descendants.
+    ///
+    /// Biplate variant of [`Uniplate::universe_iter`]
+    ///
+    /// Nodes are produced lazily in the same order as [`universe_bi`](Biplate::universe_bi).
+    fn universe_bi_iter(&self) -> impl Iterator<Item = To> {
+        UniverseIter::new(self.children_bi())
+    }
+
+    /// Returns an iterator over all children of type `To`, and all of their descendants, in the
+    /// given order.
+    ///
+    /// Biplate variant of [`Uniplate::universe_in`]
+    ///
+    /// The top most children of type `To` are treated as siblings: for example, in
+    /// [`TraversalOrder::BreadthFirst`] all of them are produced before any of their children.
+    fn universe_bi_in(&self, order: TraversalOrder) -> impl Iterator<Item = To> {
+        OrderedUniverseIter::new(self.children_bi(), order)
+    }
+
+    /// Returns an iterator over all children of type `To`, and all of their descendants, in level
+    /// order, paired with their depth.
+    ///
+    /// Biplate variant of [`Uniplate::level_order`]
+    ///
+    /// The top most children of type `To` have depth 0.
+    fn level_order_bi(&self) -> impl Iterator<Item = (usize, To)> {
+        LevelOrderIter::new(self.children_bi())
+    }
+
+    /// Returns an iterator over all values of type `To` and their descendants, paired with their
+    /// paths from this node.
+    ///
+    /// Biplate variant of [`Uniplate::positions`]
+    ///
+    /// The first index of each path is into [`children_bi`](Biplate::children_bi).
+    fn positions_bi(&self) -> impl Iterator<Item = (Path, To)> {
+        let roots = self.children_bi().into_iter().enumerate();
+        PositionsIter::new(roots.map(|(i, x)| (Path::from([i]), x)).collect())
+    }
+
+    /// Gets the value of type `To` at the given path from this node.
+    ///
+    /// Biplate variant of [`Uniplate::get_at`]
+    ///
+    /// The first index of the path is into [`children_bi`](Biplate::children_bi). Returns `None`
+    /// if there is no such node, including for the empty path.
+    fn get_at_bi(&self, path: &Path) -> Option<To> {
+        let (&first, rest) = path.indices().split_first()?;
+        path::get_at(self.children_bi().remove(first)?, rest)
+    }
+
+    /// Replaces the value of type `To` at the given path with `new`, returning the new root.
+    ///
+    /// Biplate variant of [`Uniplate::replace_at`]
+    fn replace_at_bi(&self, path: &Path, new: To) -> Result<Self, PathError> {
+        self.modify_at_bi(path, |_| new)
+    }
+
+    /// Applies `f` to the value of type `To` at the given path, returning the new root.
+    ///
+    /// Biplate variant of [`Uniplate::modify_at`]
+    fn modify_at_bi(&self, path: &Path, f: impl FnOnce(To) -> To) -> Result<Self, PathError> {
+        let mut children = self.children_bi();
+        let n_children = children.len();
+        let Some(child) = path.indices().first().and_then(|&i| children.get_mut(i)) else {
+            return Err(PathError::new(path, 0, n_children));
+        };
+        *child = path::modify_at(child, path, 1, f)?;
+        Ok(self.rebuild_bi(&mut children.into_iter()))
+    }
+
+    /// Visits all values of type `To` and their descendants in preorder, letting the callback
+    /// prune or stop the traversal.
+    ///
+    /// Biplate variant of [`Uniplate::visit`]
+    fn visit_bi<B>(&self, f: &mut impl FnMut(&To) -> VisitControl<B>) -> Option<B> {
+        visit(self.children_bi(), f)
+    }
+
+    /// Returns the first value of type `To` that satisfies the predicate.
+    ///
+    /// Biplate variant of [`Uniplate::find_node`]
+    fn find_bi(&self, pred: &impl Fn(&To) -> bool) -> Option<To> {
+        self.universe_bi_iter().find(|x| pred(x))
+    }
+
+    /// Returns the first non-`None` result of applying `op` to the values of type `To`.
+    ///
+    /// Biplate variant of [`Uniplate::find_map_node`]
+    fn find_map_bi<T>(&self, op: &impl Fn(To) -> Option<T>) -> Option<T> {
+        self.universe_bi_iter().find_map(op)
+    }
+
+    /// Returns true if any value of type `To` satisfies the predicate.
+    ///
+    /// Biplate variant of [`Uniplate::any_node`]
+    fn any_bi(&self, pred: &impl Fn(&To) -> bool) -> bool {
+        self.universe_bi_iter().any(|x| pred(&x))
+    }
+
+    /// Returns true if every value of type `To` satisfies the predicate.
+    ///
+    /// Biplate variant of [`Uniplate::all_node`]
+    fn all_bi(&self, pred: &impl Fn(&To) -> bool) -> bool {
+        self.universe_bi_iter().all(|x| pred(&x))
+    }
+
+    /// Returns the number of values of type `To` that satisfy the predicate.
+    ///
+    /// Biplate variant of [`Uniplate::count_node`]
+    fn count_bi(&self, pred: &impl Fn(&To) -> bool) -> usize {
+        self.universe_bi_iter().filter(|x| pred(x)).count()
+    }
+
+    /// Returns true if `x` is equal to any value of type `To` inside this one.
+    ///
+    /// Biplate variant of [`Uniplate::contains_node`]
+    fn contains_bi(&self, x: &To) -> bool {
+        self.any_bi(&|y| y == x)
     }
 
     /// Returns the children of a type. If to == from then it returns the original element (in contrast to children).
     ///
     /// Biplate variant of [`Uniplate::children`]
     fn children_bi(&self) -> VecDeque<To> {
-        self.biplate().0.list().0
+        let mut children = Vec::new();
+        self.children_bi_into(&mut children);
+        children.into()
     }
 
     /// Applies the given function to all nodes bottom up.
@@ -92,14 +275,228 @@ where
         self.descend_bi(&|x| x.transform(op))
     }
 
+    /// Applies the given function to all nodes bottom up, reporting each change to `observer`.
+    ///
+    /// Biplate variant of [`Uniplate::transform_observed`]
+    fn transform_bi_observed(
+        &self,
+        op: &impl Fn(To) -> To,
+        observer: &mut impl RewriteObserver<To>,
+    ) -> Self {
+        observer::single_pass(self, observer, |root, observer| {
+            observer::descend_bi(root, observer, |x, path, observer| {
+                observer::transform(x, path, op, observer)
+            })
+        })
+    }
+
+    /// Applies the given function to all nodes top down.
+    ///
+    /// Biplate variant of [`Uniplate::transform_top_down`]
+    fn transform_bi_top_down(&self, op: &impl Fn(To) -> To) -> Self {
+        self.descend_bi(&|x| x.transform_top_down(op))
+    }
+
+    /// Applies the given function to all nodes top down, letting it decide whether to continue
+    /// into the children of each node.
+    ///
+    /// Biplate variant of [`Uniplate::transform_with_control`]
+    fn transform_bi_with_control(&self, op: &impl Fn(To) -> TransformControl<To>) -> Self {
+        self.descend_bi(&|x| x.transform_with_control(op))
+    }
+
+    /// Rewrites by applying a rule everywhere it can, in a single bottom-up pass.
+    ///
+    /// Biplate variant of [`Uniplate::rewrite`]
+    fn rewrite_bi(&self, f: &impl Fn(To) -> Option<To>) -> Self {
+        self.descend_bi(&|x| x.rewrite(f))
+    }
+
+    /// Rewrites by applying a rule everywhere it can, until it no longer applies anywhere.
+    ///
+    /// Biplate variant of [`Uniplate::rewrite_fixpoint`]
+    fn rewrite_fixpoint_bi(&self, f: &impl Fn(To) -> Option<To>) -> Self {
+        self.descend_bi(&|x| x.rewrite_fixpoint(f))
+    }
+
+    /// Applies a function to all children of type `To`, tracking whether anything changed.
+    ///
+    /// Biplate variant of [`Uniplate::descend_changed`]
+    fn descend_bi_changed(&self, f: &impl Fn(&To) -> Option<To>) -> Transformed<Self> {
+        Transformed::from_option(self, changed::descend_bi(self, f))
+    }
+
+    /// Applies the given function to all nodes bottom up, tracking whether anything changed.
+    ///
+    /// Biplate variant of [`Uniplate::transform_changed`]
+    fn transform_bi_changed(&self, f: &impl Fn(&To) -> Option<To>) -> Transformed<Self> {
+        Transformed::from_option(
+            self,
+            changed::descend_bi(self, &|x| changed::transform(x, f)),
+        )
+    }
+
+    /// Rewrites by applying a rule everywhere it can, until it no longer applies anywhere,
+    /// tracking whether anything changed.
+    ///
+    /// Biplate variant of [`Uniplate::rewrite_fixpoint_changed`]
+    fn rewrite_fixpoint_bi_changed(&self, f: &impl Fn(&To) -> Option<To>) -> Transformed<Self> {
+        Transformed::from_option(
+            self,
+            changed::descend_bi(self, &|x| changed::rewrite_fixpoint(x, f)),
+        )
+    }
+
+    /// Returns all children of type `To`, and all of their descendants, splitting the work across
+    /// threads.
+    ///
+    /// Biplate variant of [`Uniplate::par_universe`]
+    #[cfg(feature = "std")]
+    fn par_universe_bi(&self, threshold: usize) -> VecDeque<To>
+    where
+        To: Send,
+    {
+        let mut out = VecDeque::new();
+        par::universes(self.children_bi(), threshold, &mut out);
+        out
+    }
+
+    /// Applies the given function to all nodes bottom up, splitting the work across threads.
+    ///
+    /// Biplate variant of [`Uniplate::par_transform`]
+    #[cfg(feature = "std")]
+    fn par_transform_bi(&self, op: &(impl Fn(To) -> To + Sync), threshold: usize) -> Self
+    where
+        To: Send,
+    {
+        par::descend_bi(self, threshold, &|x| x.transform(op), &|x| {
+            x.par_transform(op, threshold)
+        })
+    }
+
+    /// Rewrites by applying a rule everywhere it can, in a single bottom-up pass, splitting the
+    /// work across threads.
+    ///
+    /// Biplate variant of [`Uniplate::par_rewrite`]
+    #[cfg(feature = "std")]
+    fn par_rewrite_bi(&self, f: &(impl Fn(To) -> Option<To> + Sync), threshold: usize) -> Self
+    where
+        To: Send,
+    {
+        par::descend_bi(self, threshold, &|x| x.rewrite(f), &|x| {
+            x.par_rewrite(f, threshold)
+        })
+    }
+
+    /// Rewrites by applying a rule everywhere it can, until it no longer applies anywhere or one
+    /// of `limits` is reached.
+    ///
+    /// Biplate variant of [`Uniplate::rewrite_with_limits`]
+    fn rewrite_bi_with_limits(
+        &self,
+        f: &impl Fn(To) -> Option<To>,
+        limits: &Limits,
+    ) -> Result<Self, RewriteLimitExceeded<Self>> {
+        match ZipperBi::new(self.clone()) {
+            Some(zipper) => zipper.rewrite_with_limits(f, limits),
+            None => Ok(self.clone()),
+        }
+    }
+
+    /// Rewrites by applying a rule everywhere it can, in a single bottom-up pass, reporting each
+    /// application to `observer`.
+    ///
+    /// Biplate variant of [`Uniplate::rewrite_observed`]
+    fn rewrite_bi_observed(
+        &self,
+        f: &impl Fn(To) -> Option<To>,
+        observer: &mut impl RewriteObserver<To>,
+    ) -> Self {
+        observer::single_pass(self, observer, |root, observer| {
+            observer::descend_bi(root, observer, |x, path, observer| {
+                observer::rewrite(x, path, f, false, observer)
+            })
+        })
+    }
+
+    /// Rewrites by applying a rule everywhere it can, until it no longer applies anywhere,
+    /// reporting each application to `observer`.
+    ///
+    /// Biplate variant of [`Uniplate::rewrite_fixpoint_observed`]
+    fn rewrite_fixpoint_bi_observed(
+        &self,
+        f: &impl Fn(To) -> Option<To>,
+        observer: &mut impl RewriteObserver<To>,
+    ) -> Self {
+        observer::single_pass(self, observer, |root, observer| {
+            observer::descend_bi(root, observer, |x, path, observer| {
+                observer::rewrite(x, path, f, true, observer)
+            })
+        })
+    }
+
+    /// Applies the given function to all nodes bottom up, giving it mutable access to some state.
+    ///
+    /// Biplate variant of [`Uniplate::transform_with_state`]
+    ///
+    /// The state is threaded through each child of type `To` from left to right, and through each
+    /// of their descendants in postorder.
+    fn transform_bi_with_state<S>(
+        &self,
+        state: &mut S,
+        f: &mut impl FnMut(&mut S, To) -> To,
+    ) -> Self {
+        self.rebuild_bi(
+            &mut self
+                .children_bi()
+                .into_iter()
+                .map(|child| child.transform_with_state(state, f)),
+        )
+    }
+
+    /// Applies the given fallible function to all nodes bottom up.
+    ///
+    /// Biplate variant of [`Uniplate::try_transform`]
+    fn try_transform_bi<E>(&self, op: &impl Fn(To) -> Result<To, E>) -> Result<Self, E> {
+        self.try_descend_bi(&|x| x.try_transform(op))
+    }
+
+    /// Performs a fold-like computation on each child of type `To`, returning the results from
+    /// left to right.
+    ///
+    /// Biplate variant of [`Uniplate::cata`]
+    fn cata_bi<T>(&self, op: &impl Fn(To, VecDeque<T>) -> T) -> VecDeque<T> {
+        self.children_bi().into_iter().map(|c| c.cata(op)).collect()
+    }
+
+    /// Performs a paramorphism on each child of type `To`, returning the results from left to
+    /// right.
+    ///
+    /// Biplate variant of [`Uniplate::para`]
+    fn para_bi<T>(&self, op: &impl Fn(To, VecDeque<(To, T)>) -> T) -> VecDeque<T> {
+        self.children_bi().into_iter().map(|c| c.para(op)).collect()
+    }
+
+    /// Replaces the children of type `To` with values built from the given seeds.
+    ///
+    /// Biplate variant of [`Uniplate::ana`]
+    ///
+    /// # Panics
+    ///
+    /// If there are a different number of seeds than there are children returned by
+    /// [`children_bi`](Biplate::children_bi), or if the callback returns the wrong number of
+    /// seeds for a node.
+    fn ana_bi<S>(&self, seeds: VecDeque<S>, op: &impl Fn(S) -> (To, VecDeque<S>)) -> Self {
+        self.with_children_bi(seeds.into_iter().map(|s| To::ana(s, op)).collect())
+    }
+
     /// Returns an iterator over all direct children of the input, paired with a function that
     /// "fills the hole" where the child was with a new value.
     ///
     /// `Biplate` variant of [`Uniplate::holes`]
     fn holes_bi(&self) -> impl Iterator<Item = (To, impl Fn(To) -> Self)> {
-        // must be an iterator as we cannot clone Box<dyn Fn()>'s, so cannot stick them in
-        // vectors, etc
-        HolesIterBi::new(self.clone())
+        self.hole_contexts_bi()
+            .map(|(node, context)| (node, move |x| context.fill(x)))
     }
 
     /// Returns an iterator over the universe of the input, paired with a function that "fills the
@@ -110,7 +507,23 @@ where
     /// To efficiently update multiple values in a single traversal, use
     /// [`ZipperBi`](crate::zipper::ZipperBi) instead.
     fn contexts_bi(&self) -> impl Iterator<Item = (To, impl Fn(To) -> Self)> {
-        ContextIterBi::new(self.clone())
+        self.universe_contexts_bi()
+            .map(|(node, context)| (node, move |x| context.fill(x)))
+    }
+
+    /// Returns an iterator over all direct children of the input, paired with their
+    /// [`ContextBi`].
+    ///
+    /// `Biplate` variant of [`Uniplate::hole_contexts`]
+    fn hole_contexts_bi(&self) -> impl Iterator<Item = (To, ContextBi<To, Self>)> {
+        context::hole_contexts_bi(self.clone())
+    }
+
+    /// Returns an iterator over the universe of the input, paired with their [`ContextBi`].
+    ///
+    /// `Biplate` variant of [`Uniplate::universe_contexts`]
+    fn universe_contexts_bi(&self) -> impl Iterator<Item = (To, ContextBi<To, Self>)> {
+        context::universe_contexts_bi(self.clone())
     }
 }
 
@@ -121,6 +534,24 @@ mod tests {
     use crate::test_common::paper::{Expr, Stmt, proptest_stmts};
 
     use super::*;
+
+    fn negate(expr: Expr) -> Expr {
+        match expr {
+            Expr::Val(x) => Expr::Val(x.wrapping_neg()),
+            x => x,
+        }
+    }
+
+    fn remove_double_negation(expr: Expr) -> Option<Expr> {
+        match expr {
+            Expr::Neg(x) => match *x {
+                Expr::Neg(y) => Some(*y),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     proptest! {
         #[test]
         fn test_context_bi_same_as_universe_bi(ast in proptest_stmts()) {
@@ -128,6 +559,12 @@ mod tests {
             prop_assert_eq!(Biplate::<Stmt>::universe_bi(&ast),Biplate::<Stmt>::contexts_bi(&ast).map(|(elem,_)| elem).collect::<VecDeque<_>>());
         }
 
+        #[test]
+        fn test_universe_bi_iter_same_as_universe_of_children_bi(ast in proptest_stmts()) {
+            let expected: VecDeque<Expr> = Biplate::<Expr>::children_bi(&ast).into_iter().flat_map(|x| x.universe()).collect();
+            prop_assert_eq!(Biplate::<Expr>::universe_bi_iter(&ast).collect::<VecDeque<_>>(), expected);
+        }
+
         #[test]
         fn test_context_bi_isomorphic(ast in proptest_stmts()) {
             for (e,c) in Biplate::<Expr>::contexts_bi(&ast) {
@@ -135,6 +572,184 @@ mod tests {
             }
         }
 
+        #[test]
+        fn test_universe_contexts_bi_stored_same_as_replace_at_bi(ast in proptest_stmts()) {
+            let new = Expr::Val(0);
+            let contexts: Vec<_> = Biplate::<Expr>::universe_contexts_bi(&ast).collect();
+            for ((path, _), (_, c)) in Biplate::<Expr>::positions_bi(&ast).zip(contexts.iter().cloned()) {
+                prop_assert_eq!(c.path(), path.clone());
+                prop_assert_eq!(c.depth(), path.depth());
+                prop_assert_eq!(c.fill(new.clone()), ast.replace_at_bi(&path, new.clone()).unwrap());
+            }
+            prop_assert!(contexts.into_iter().eq(Biplate::<Expr>::universe_contexts_bi(&ast)));
+        }
+
+        #[test]
+        fn test_rewrite_fixpoint_bi_is_normal_form(ast in proptest_stmts()) {
+            let result = ast.rewrite_fixpoint_bi(&remove_double_negation);
+            for x in Biplate::<Expr>::universe_bi(&result) {
+                prop_assert_eq!(remove_double_negation(x), None);
+            }
+        }
+
+        #[test]
+        fn test_transform_bi_top_down_leaf_map_same_as_transform_bi(ast in proptest_stmts()) {
+            prop_assert_eq!(ast.transform_bi_top_down(&negate), ast.transform_bi(&negate));
+        }
+
+        #[test]
+        fn test_cata_bi_sizes_sum_to_universe_bi(ast in proptest_stmts()) {
+            let sizes = Biplate::<Expr>::cata_bi(&ast, &|_, xs: VecDeque<usize>| 1 + xs.iter().sum::<usize>());
+            prop_assert_eq!(sizes.iter().sum::<usize>(), Biplate::<Expr>::universe_bi(&ast).len());
+        }
+
+        #[test]
+        fn test_ana_bi_children_is_identity(ast in proptest_stmts()) {
+            let seeds: VecDeque<Expr> = ast.children_bi();
+            let rebuilt = ast.ana_bi(seeds, &|x: Expr| {
+                let children = x.children();
+                (x, children)
+            });
+            prop_assert_eq!(rebuilt, ast);
+        }
+
+        #[test]
+        fn test_try_descend_bi_ok_same_as_descend_bi(ast in proptest_stmts()) {
+            let actual: Result<Stmt, ()> = ast.try_descend_bi(&|x: Expr| Ok(negate(x)));
+            prop_assert_eq!(actual, Ok(ast.descend_bi(&negate)));
+        }
+
+        #[test]
+        fn test_try_transform_bi_ok_same_as_transform_bi(ast in proptest_stmts()) {
+            let actual: Result<Stmt, ()> = ast.try_transform_bi(&|x: Expr| Ok(negate(x)));
+            prop_assert_eq!(actual, Ok(ast.transform_bi(&negate)));
+        }
+
+        #[test]
+        fn test_try_transform_bi_returns_first_error(ast in proptest_stmts()) {
+            // the first node to be transformed is the left-most leaf of the first child.
+            let expected = Biplate::<Expr>::universe_bi(&ast).into_iter().find(|x| x.children().is_empty());
+            let actual = ast.try_transform_bi(&|x: Expr| Err::<Expr, _>(x));
+            match expected {
+                Some(x) => prop_assert_eq!(actual, Err(x)),
+                None => prop_assert_eq!(actual, Ok(ast.clone())),
+            }
+        }
+
+        #[test]
+        fn test_universe_bi_in_preorder_same_as_universe_bi(ast in proptest_stmts()) {
+            let actual: VecDeque<Expr> = Biplate::<Expr>::universe_bi_in(&ast, TraversalOrder::PreOrder).collect();
+            prop_assert_eq!(actual, Biplate::<Expr>::universe_bi(&ast));
+        }
+
+        #[test]
+        fn test_universe_bi_in_postorder_same_as_children_postorder(ast in proptest_stmts()) {
+            let expected: Vec<Expr> = Biplate::<Expr>::children_bi(&ast)
+                .iter()
+                .flat_map(|x| x.universe_in(TraversalOrder::PostOrder))
+                .collect();
+            let actual: Vec<Expr> = Biplate::<Expr>::universe_bi_in(&ast, TraversalOrder::PostOrder).collect();
+            prop_assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn test_universe_bi_in_breadth_first_same_as_level_order_bi(ast in proptest_stmts()) {
+            let (depths, nodes): (Vec<usize>, Vec<Expr>) = Biplate::<Expr>::level_order_bi(&ast).unzip();
+            prop_assert!(depths.is_sorted());
+            prop_assert_eq!(nodes.len(), Biplate::<Expr>::universe_bi(&ast).len());
+            let actual: Vec<Expr> = Biplate::<Expr>::universe_bi_in(&ast, TraversalOrder::BreadthFirst).collect();
+            prop_assert_eq!(actual, nodes);
+        }
+
+        #[test]
+        fn test_queries_bi_same_as_universe_bi(ast in proptest_stmts()) {
+            let universe: VecDeque<Expr> = ast.universe_bi();
+            let is_val = |x: &Expr| matches!(x, Expr::Val(_));
+
+            prop_assert_eq!(ast.find_bi(&is_val), universe.iter().find(|x| is_val(x)).cloned());
+            prop_assert_eq!(ast.any_bi(&is_val), universe.iter().any(is_val));
+            prop_assert_eq!(ast.all_bi(&is_val), universe.iter().all(is_val));
+            prop_assert_eq!(ast.count_bi(&is_val), universe.iter().filter(|x| is_val(x)).count());
+
+            let val = |x: Expr| match x {
+                Expr::Val(n) => Some(n),
+                _ => None,
+            };
+            prop_assert_eq!(ast.find_map_bi(&val), universe.iter().cloned().find_map(val));
+
+            for x in universe {
+                prop_assert!(ast.contains_bi(&x));
+                prop_assert!(x.is_subterm_of_bi(&ast));
+            }
+        }
+
+        #[test]
+        fn test_visit_bi_continue_same_as_universe_bi(ast in proptest_stmts()) {
+            let mut visited = VecDeque::new();
+            let result: Option<()> = ast.visit_bi(&mut |x: &Expr| {
+                visited.push_back(x.clone());
+                VisitControl::Continue
+            });
+            prop_assert_eq!(result, None);
+            prop_assert_eq!(visited, Biplate::<Expr>::universe_bi(&ast));
+        }
+
+        #[test]
+        fn test_visit_bi_skip_children_same_as_children_bi(ast in proptest_stmts()) {
+            let mut visited = VecDeque::new();
+            let result: Option<()> = ast.visit_bi(&mut |x: &Expr| {
+                visited.push_back(x.clone());
+                VisitControl::SkipChildren
+            });
+            prop_assert_eq!(result, None);
+            prop_assert_eq!(visited, Biplate::<Expr>::children_bi(&ast));
+        }
+
+        #[test]
+        fn test_transform_bi_with_state_visits_in_postorder(ast in proptest_stmts()) {
+            let mut visited: Vec<Expr> = Vec::new();
+            let result = ast.transform_bi_with_state(&mut visited, &mut |visited, x: Expr| {
+                visited.push(x.clone());
+                x
+            });
+            prop_assert_eq!(&result, &ast);
+            prop_assert_eq!(visited, Biplate::<Expr>::universe_bi_in(&ast, TraversalOrder::PostOrder).collect::<Vec<_>>());
+
+            let mut n = 0;
+            let result = ast.transform_bi_with_state(&mut n, &mut |n, x: Expr| {
+                *n += 1;
+                negate(x)
+            });
+            prop_assert_eq!(result, ast.transform_bi(&negate));
+            prop_assert_eq!(n, Biplate::<Expr>::universe_bi(&ast).len());
+        }
+
+        #[test]
+        fn test_descend_accum_bi_threads_state_through_children(ast in proptest_stmts()) {
+            let (n, result) = ast.descend_accum_bi(0, &mut |n, x: Expr| (n + 1, negate(x)));
+            prop_assert_eq!(n, Biplate::<Expr>::children_bi(&ast).len());
+            prop_assert_eq!(result, ast.descend_bi(&negate));
+        }
+
+        #[test]
+        fn test_positions_bi_same_as_universe_bi(ast in proptest_stmts()) {
+            let nodes: VecDeque<Expr> = Biplate::<Expr>::positions_bi(&ast).map(|(_, x)| x).collect();
+            prop_assert_eq!(nodes, Biplate::<Expr>::universe_bi(&ast));
+            for (path, node) in Biplate::<Expr>::positions_bi(&ast) {
+                prop_assert_eq!(ast.get_at_bi(&path), Some(node.clone()));
+                prop_assert_eq!(ast.replace_at_bi(&path, node), Ok(ast.clone()));
+
+                let replaced = ast.modify_at_bi(&path, negate).unwrap();
+                prop_assert_eq!(replaced.get_at_bi(&path), ast.get_at_bi(&path).map(negate));
+            }
+        }
+
+        #[test]
+        fn test_get_at_bi_root_is_invalid(ast in proptest_stmts()) {
+            prop_assert_eq!(Biplate::<Expr>::get_at_bi(&ast, &Path::root()), None);
+            prop_assert!(Biplate::<Expr>::replace_at_bi(&ast, &Path::root(), Expr::Val(0)).is_err());
+        }
+
         #[test]
         fn test_holes_bi_same_as_children_bi(ast in proptest_stmts()) {
             prop_assert_eq!(Biplate::<Expr>::children_bi(&ast),Biplate::<Expr>::holes_bi(&ast).map(|(elem,_)| elem).collect::<VecDeque<_>>());
@@ -0,0 +1,91 @@
+use super::UniplateMut;
+
+/// `BiplateMut<U>` for type `T` operates in place over all values of type `U` within `T`.
+///
+/// This is a mutable companion to [`Biplate`](crate::Biplate), in the same way that
+/// [`UniplateMut`] is for [`Uniplate`](crate::Uniplate).
+///
+/// **Note: `BiplateMut<T>` for `T` visits the input expression, not its children of type `T`.
+/// Use [`UniplateMut`] instead.**
+pub trait BiplateMut<To>
+where
+    Self: Sized + 'static,
+    To: UniplateMut,
+{
+    /// Definition of a `BiplateMut`.
+    ///
+    /// Calls `f` on each of the top most children of type `To` within `From`, from left to right.
+    ///
+    /// If from == to then this function should call `f` on the root.
+    fn for_each_child_bi_mut(&mut self, f: &mut impl FnMut(&mut To));
+
+    /// Applies a function to all top most children of type `To`, in place.
+    ///
+    /// In-place variant of [`Biplate::descend_bi`](crate::Biplate::descend_bi).
+    fn descend_bi_mut(&mut self, f: &mut impl FnMut(&mut To)) {
+        self.for_each_child_bi_mut(f);
+    }
+
+    /// Applies the given function to all values of type `To` bottom up, in place.
+    ///
+    /// In-place variant of [`Biplate::transform_bi`](crate::Biplate::transform_bi).
+    fn transform_bi_mut(&mut self, f: &mut impl FnMut(&mut To)) {
+        self.for_each_child_bi_mut(&mut |child| child.transform_mut(f));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::Biplate;
+    use crate::test_common::paper::{Expr, Stmt, proptest_stmts};
+
+    use super::*;
+
+    fn negate(expr: Expr) -> Expr {
+        match expr {
+            Expr::Val(x) => Expr::Val(x.wrapping_neg()),
+            x => x,
+        }
+    }
+
+    fn negate_mut(expr: &mut Expr) {
+        if let Expr::Val(x) = expr {
+            *x = x.wrapping_neg();
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn descend_bi_mut_same_as_descend_bi(ast in proptest_stmts()) {
+            let expected = ast.descend_bi(&|x: Expr| Expr::Neg(Box::new(x)));
+            let mut actual = ast.clone();
+            actual.descend_bi_mut(&mut |x: &mut Expr| *x = Expr::Neg(Box::new(x.clone())));
+            prop_assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn transform_bi_mut_same_as_transform_bi(ast in proptest_stmts()) {
+            let expected = ast.transform_bi(&negate);
+            let mut actual = ast.clone();
+            actual.transform_bi_mut(&mut negate_mut);
+            prop_assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn transform_bi_mut_strings(ast in proptest_stmts()) {
+            let expected = ast.transform_bi(&|x: String| x.to_uppercase());
+            let mut actual = ast.clone();
+            actual.transform_bi_mut(&mut |x: &mut String| *x = x.to_uppercase());
+            prop_assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn for_each_child_bi_mut_identity(ast in proptest_stmts()) {
+            let mut actual = ast.clone();
+            actual.for_each_child_bi_mut(&mut |x: &mut Stmt| *x = Stmt::Sequence(vec![]));
+            prop_assert_eq!(actual, Stmt::Sequence(vec![]));
+        }
+    }
+}
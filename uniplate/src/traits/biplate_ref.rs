@@ -0,0 +1,77 @@
+use super::UniplateRef;
+use super::universe::UniverseRefIter;
+use alloc::vec::Vec;
+
+/// `BiplateRef<U>` for type `T` operates over references to all values of type `U` within `T`.
+///
+/// This is a borrowing companion to [`Biplate`](crate::Biplate), in the same way that
+/// [`UniplateRef`] is for [`Uniplate`](crate::Uniplate).
+///
+/// **Note: `BiplateRef<T>` for `T` visits the input expression, not its children of type `T`.
+/// Use [`UniplateRef`] instead.**
+pub trait BiplateRef<To>
+where
+    Self: Sized + 'static,
+    To: UniplateRef,
+{
+    /// Definition of a `BiplateRef`.
+    ///
+    /// Calls `f` on each of the top most children of type `To` within `From`, from left to right.
+    ///
+    /// If from == to then this function should call `f` on the root.
+    fn for_each_child_bi<'a>(&'a self, f: &mut impl FnMut(&'a To));
+
+    /// Borrows the children of a type. If to == from then it returns the original element (in
+    /// contrast to children).
+    ///
+    /// Borrowing variant of [`Biplate::children_bi`](crate::Biplate::children_bi).
+    fn children_bi_ref(&self) -> Vec<&To> {
+        let mut children = Vec::new();
+        self.for_each_child_bi(&mut |child| children.push(child));
+        children
+    }
+
+    /// Returns an iterator over references to all children of type `To`, and all of their
+    /// descendants.
+    ///
+    /// Borrowing variant of [`Biplate::universe_bi_iter`](crate::Biplate::universe_bi_iter).
+    fn universe_bi_ref(&self) -> impl Iterator<Item = &To> {
+        UniverseRefIter::new(self.children_bi_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::Biplate;
+    use crate::test_common::paper::{Expr, Stmt, proptest_stmts};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn children_bi_ref_same_as_children_bi(ast in proptest_stmts()) {
+            let expected: Vec<Expr> = ast.children_bi().into_iter().collect();
+            let actual: Vec<Expr> = ast.children_bi_ref().into_iter().cloned().collect();
+            prop_assert_eq!(actual, expected);
+
+            let expected: Vec<String> = ast.children_bi().into_iter().collect();
+            let actual: Vec<String> = ast.children_bi_ref().into_iter().cloned().collect();
+            prop_assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn universe_bi_ref_same_as_universe_bi(ast in proptest_stmts()) {
+            let expected: Vec<Expr> = ast.universe_bi().into_iter().collect();
+            let actual: Vec<Expr> = BiplateRef::<Expr>::universe_bi_ref(&ast).cloned().collect();
+            prop_assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn children_bi_ref_identity(ast in proptest_stmts()) {
+            let children: Vec<&Stmt> = ast.children_bi_ref();
+            prop_assert_eq!(children, vec![&ast]);
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Change-aware traversals.
+//!
+//! The callbacks of these traversals return `None` to leave a node unchanged. Unchanged nodes are
+//! passed through as they are: a parent is only rebuilt if one of its children changed.
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use super::{Biplate, Uniplate};
+
+/// The result of a change-aware traversal, such as
+/// [`transform_changed`](crate::Uniplate::transform_changed).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Transformed<T> {
+    /// The result of the traversal.
+    pub value: T,
+
+    /// Whether the traversal changed anything.
+    pub changed: bool,
+}
+
+impl<T> Transformed<T> {
+    /// Returns the result of the traversal, or `None` if it changed nothing.
+    pub fn into_changed(self) -> Option<T> {
+        self.changed.then_some(self.value)
+    }
+
+    /// Wraps the result of a traversal of `original` that returns `None` when nothing changed.
+    pub(super) fn from_option(original: &T, value: Option<T>) -> Transformed<T>
+    where
+        T: Clone,
+    {
+        match value {
+            Some(value) => Transformed {
+                value,
+                changed: true,
+            },
+            None => Transformed {
+                value: original.clone(),
+                changed: false,
+            },
+        }
+    }
+}
+
+/// Applies `f` to each of `children`, returning the new children if any of them changed.
+fn map_changed<T>(children: VecDeque<T>, f: &impl Fn(&T) -> Option<T>) -> Option<Vec<T>> {
+    let mut changed = false;
+    let new_children = children
+        .into_iter()
+        .map(|child| match f(&child) {
+            Some(new_child) => {
+                changed = true;
+                new_child
+            }
+            None => child,
+        })
+        .collect();
+    changed.then_some(new_children)
+}
+
+/// Applies `f` to the children of `node`, returning the new node if any of them changed.
+pub(super) fn descend<T: Uniplate>(node: &T, f: &impl Fn(&T) -> Option<T>) -> Option<T> {
+    let new_children = map_changed(node.children(), f)?;
+    Some(node.rebuild(&mut new_children.into_iter()))
+}
+
+/// Applies `f` to the children of type `To` of `node`, returning the new node if any of them
+/// changed.
+pub(super) fn descend_bi<From: Biplate<To>, To: Uniplate>(
+    node: &From,
+    f: &impl Fn(&To) -> Option<To>,
+) -> Option<From> {
+    let new_children = map_changed(node.children_bi(), f)?;
+    Some(node.rebuild_bi(&mut new_children.into_iter()))
+}
+
+/// Applies `f` to every node bottom up, returning the new node if anything changed.
+pub(super) fn transform<T: Uniplate>(node: &T, f: &impl Fn(&T) -> Option<T>) -> Option<T> {
+    match descend(node, &|child| transform(child, f)) {
+        Some(new_node) => Some(f(&new_node).unwrap_or(new_node)),
+        None => f(node),
+    }
+}
+
+/// Rewrites `node` until `f` no longer applies anywhere, returning the new node if anything
+/// changed.
+pub(super) fn rewrite_fixpoint<T: Uniplate>(node: &T, f: &impl Fn(&T) -> Option<T>) -> Option<T> {
+    let new_node = descend(node, &|child| rewrite_fixpoint(child, f));
+    match f(new_node.as_ref().unwrap_or(node)) {
+        Some(rewritten) => Some(rewrite_fixpoint(&rewritten, f).unwrap_or(rewritten)),
+        None => new_node,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use proptest::prelude::*;
+
+    use crate::Tree;
+    use crate::test_common::paper::{Expr, Stmt, proptest_exprs, proptest_stmts};
+
+    use super::*;
+
+    fn push_negation(expr: &Expr) -> Option<Expr> {
+        match expr {
+            Expr::Neg(x) => match &**x {
+                Expr::Neg(y) => Some((**y).clone()),
+                Expr::Add(a, b) => Some(Expr::Add(
+                    Box::new(Expr::Neg(a.clone())),
+                    Box::new(Expr::Neg(b.clone())),
+                )),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// The number of times a `Counted` node has been rebuilt. Only one test uses this.
+    static REBUILDS: AtomicUsize = AtomicUsize::new(0);
+
+    /// A binary tree that counts how many times it is rebuilt.
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    enum Counted {
+        Leaf(i32),
+        Node(Box<Counted>, Box<Counted>),
+    }
+
+    impl Uniplate for Counted {
+        fn uniplate(&self) -> (Tree<Self>, Box<dyn Fn(Tree<Self>) -> Self>) {
+            match self.clone() {
+                leaf @ Counted::Leaf(_) => (Tree::Zero, Box::new(move |_| leaf.clone())),
+                Counted::Node(l, r) => (
+                    Tree::Many([Tree::One(*l), Tree::One(*r)].into()),
+                    Box::new(|tree| {
+                        REBUILDS.fetch_add(1, Ordering::Relaxed);
+                        let mut children = tree.list().0;
+                        Counted::Node(
+                            Box::new(children.pop_front().unwrap()),
+                            Box::new(children.pop_front().unwrap()),
+                        )
+                    }),
+                ),
+            }
+        }
+    }
+
+    impl Biplate<Counted> for Counted {
+        fn biplate(&self) -> (Tree<Counted>, Box<dyn Fn(Tree<Counted>) -> Counted>) {
+            (
+                Tree::One(self.clone()),
+                Box::new(|tree| {
+                    let Tree::One(x) = tree else { panic!() };
+                    x
+                }),
+            )
+        }
+    }
+
+    #[test]
+    fn unchanged_subtrees_are_not_rebuilt() {
+        use Counted::*;
+
+        let node = |l, r| Node(Box::new(l), Box::new(r));
+
+        // ((0, 1), (2, 3))
+        let tree = node(node(Leaf(0), Leaf(1)), node(Leaf(2), Leaf(3)));
+        let replace_two = |x: &Counted| match x {
+            Leaf(2) => Some(Leaf(20)),
+            _ => None,
+        };
+
+        REBUILDS.store(0, Ordering::Relaxed);
+        let result = tree.transform_changed(&|x| match x {
+            Leaf(100) => Some(Leaf(0)),
+            _ => None,
+        });
+        assert!(!result.changed);
+        assert_eq!(result.value, tree);
+        assert_eq!(REBUILDS.load(Ordering::Relaxed), 0);
+
+        // only the path to the changed leaf is rebuilt.
+        let result = tree.transform_changed(&replace_two);
+        assert!(result.changed);
+        assert_eq!(
+            result.value,
+            node(node(Leaf(0), Leaf(1)), node(Leaf(20), Leaf(3)))
+        );
+        assert_eq!(REBUILDS.load(Ordering::Relaxed), 2);
+
+        // transform rebuilds everything.
+        REBUILDS.store(0, Ordering::Relaxed);
+        tree.transform(&|x| replace_two(&x).unwrap_or(x));
+        assert_eq!(REBUILDS.load(Ordering::Relaxed), 3);
+    }
+
+    proptest! {
+        #[test]
+        fn transform_changed_same_as_rewrite(expr in proptest_exprs()) {
+            let result = expr.transform_changed(&push_negation);
+            let expected = expr.rewrite(&|x| push_negation(&x));
+            prop_assert_eq!(result.changed, expr != expected);
+            prop_assert_eq!(result.value, expected);
+        }
+
+        #[test]
+        fn rewrite_fixpoint_changed_same_as_rewrite_fixpoint(expr in proptest_exprs()) {
+            let result = expr.rewrite_fixpoint_changed(&push_negation);
+            let expected = expr.rewrite_fixpoint(&|x| push_negation(&x));
+            prop_assert_eq!(result.changed, expr != expected);
+            prop_assert_eq!(&result.value, &expected);
+
+            // a second pass changes nothing.
+            let result = expected.rewrite_fixpoint_changed(&push_negation);
+            prop_assert_eq!(result.into_changed(), None);
+        }
+
+        #[test]
+        fn descend_changed_same_as_descend(expr in proptest_exprs()) {
+            let result = expr.descend_changed(&push_negation);
+            let expected = expr.descend(&|x| push_negation(&x).unwrap_or(x));
+            prop_assert_eq!(result.changed, expr != expected);
+            prop_assert_eq!(result.value, expected);
+        }
+
+        #[test]
+        fn biplate_changed_same_as_biplate(stmt in proptest_stmts()) {
+            let result = stmt.transform_bi_changed(&push_negation);
+            let expected: Stmt = stmt.rewrite_bi(&|x| push_negation(&x));
+            prop_assert_eq!(result.changed, stmt != expected);
+            prop_assert_eq!(result.value, expected);
+
+            let result = stmt.rewrite_fixpoint_bi_changed(&push_negation);
+            let expected: Stmt = stmt.rewrite_fixpoint_bi(&|x| push_negation(&x));
+            prop_assert_eq!(result.changed, stmt != expected);
+            prop_assert_eq!(result.value, expected);
+
+            let result = stmt.descend_bi_changed(&push_negation);
+            let expected: Stmt = stmt.descend_bi(&|x| push_negation(&x).unwrap_or(x));
+            prop_assert_eq!(result.changed, stmt != expected);
+            prop_assert_eq!(result.value, expected);
+        }
+    }
+}
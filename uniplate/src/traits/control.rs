@@ -0,0 +1,31 @@
+//! Control flow types for traversals.
+
+/// The result of the callback given to
+/// [`transform_with_control`](crate::Uniplate::transform_with_control).
+///
+/// Both variants replace the current node with the given value; they differ in whether the
+/// traversal continues into the children of the replacement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransformControl<T> {
+    /// Replace the node with this value, then transform its children.
+    Continue(T),
+
+    /// Replace the node with this value, and leave its children untouched.
+    Stop(T),
+}
+
+/// The result of the callback given to [`visit`](crate::Uniplate::visit).
+///
+/// Controls whether the traversal continues into the children of the current node, and allows
+/// it to stop early with a value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VisitControl<B> {
+    /// Visit the children of this node, then carry on.
+    Continue,
+
+    /// Do not visit the children of this node, but carry on with the rest of the tree.
+    SkipChildren,
+
+    /// Stop the traversal, returning this value.
+    Break(B),
+}
@@ -0,0 +1,23 @@
+//! Traversal orders.
+
+/// The order in which a traversal visits the nodes of a tree.
+///
+/// Used by [`universe_in`](crate::Uniplate::universe_in) and
+/// [`universe_bi_in`](crate::Biplate::universe_bi_in).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TraversalOrder {
+    /// Visit each node before its children, from left to right.
+    ///
+    /// This is the order used by [`universe`](crate::Uniplate::universe).
+    #[default]
+    PreOrder,
+
+    /// Visit each node after its children, from left to right.
+    ///
+    /// This is the order in which [`transform`](crate::Uniplate::transform) and
+    /// [`cata`](crate::Uniplate::cata) reach each node.
+    PostOrder,
+
+    /// Visit nodes level by level, from left to right within each level.
+    BreadthFirst,
+}
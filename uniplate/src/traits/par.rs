@@ -0,0 +1,206 @@
+//! Parallel traversals.
+//!
+//! Work is split across sibling subtrees using [`std::thread::scope`]. When a node has at least
+//! `threshold` children, its children are divided into contiguous chunks, one per available
+//! thread, and each chunk is processed sequentially on its own thread. Otherwise, the children
+//! are processed one at a time, looking for a wide enough node further down.
+//!
+//! Results are reassembled in their original order, so are identical to those of the sequential
+//! traversals.
+
+use alloc::collections::VecDeque;
+use std::thread;
+
+use super::{Biplate, Uniplate};
+
+/// Applies `f` to each item, splitting the items across threads.
+///
+/// The results are returned in the same order as the items.
+fn par_map<T: Send, U: Send>(items: VecDeque<T>, f: &(impl Fn(T) -> U + Sync)) -> VecDeque<U> {
+    let n_threads = thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(items.len());
+    if n_threads <= 1 {
+        return items.into_iter().map(f).collect();
+    }
+
+    let chunk_size = items.len().div_ceil(n_threads);
+    let mut items = Vec::from(items);
+    let mut chunks = Vec::with_capacity(n_threads);
+    while items.len() > chunk_size {
+        let rest = items.split_off(chunk_size);
+        chunks.push(items);
+        items = rest;
+    }
+    chunks.push(items);
+
+    thread::scope(|s| {
+        let handles: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| s.spawn(move || chunk.into_iter().map(f).collect::<Vec<U>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|err| std::panic::resume_unwind(err))
+            })
+            .collect()
+    })
+}
+
+/// Applies `seq` to all children of `node` in parallel if there are at least `threshold` of
+/// them, or `par` to each child in turn otherwise.
+fn descend<T: Uniplate + Send>(
+    node: &T,
+    threshold: usize,
+    seq: &(impl Fn(T) -> T + Sync),
+    par: &impl Fn(T) -> T,
+) -> T {
+    let children = node.children();
+    let new_children = if children.len() >= threshold {
+        par_map(children, seq)
+    } else {
+        children.into_iter().map(par).collect()
+    };
+    node.rebuild(&mut new_children.into_iter())
+}
+
+/// Biplate variant of [`descend`].
+pub(super) fn descend_bi<From: Biplate<To>, To: Uniplate + Send>(
+    node: &From,
+    threshold: usize,
+    seq: &(impl Fn(To) -> To + Sync),
+    par: &impl Fn(To) -> To,
+) -> From {
+    let children = node.children_bi();
+    let new_children = if children.len() >= threshold {
+        par_map(children, seq)
+    } else {
+        children.into_iter().map(par).collect()
+    };
+    node.rebuild_bi(&mut new_children.into_iter())
+}
+
+/// Parallel implementation of [`Uniplate::transform`].
+pub(super) fn transform<T: Uniplate + Send>(
+    node: &T,
+    f: &(impl Fn(T) -> T + Sync),
+    threshold: usize,
+) -> T {
+    f(descend(
+        node,
+        threshold,
+        &|child| child.transform(f),
+        &|child| transform(&child, f, threshold),
+    ))
+}
+
+/// Parallel implementation of [`Uniplate::rewrite`].
+pub(super) fn rewrite<T: Uniplate + Send>(
+    node: &T,
+    f: &(impl Fn(T) -> Option<T> + Sync),
+    threshold: usize,
+) -> T {
+    let node = descend(node, threshold, &|child| child.rewrite(f), &|child| {
+        rewrite(&child, f, threshold)
+    });
+    match f(node.clone()) {
+        Some(new_node) => new_node,
+        None => node,
+    }
+}
+
+/// Parallel implementation of [`Uniplate::universe`], appending the universe of `node` to `out`.
+pub(super) fn universe<T: Uniplate + Send>(node: T, threshold: usize, out: &mut VecDeque<T>) {
+    let children = node.children();
+    out.push_back(node);
+    universes(children, threshold, out);
+}
+
+/// Appends the universes of each node in `nodes` to `out`, in parallel if there are at least
+/// `threshold` nodes.
+pub(super) fn universes<T: Uniplate + Send>(
+    nodes: VecDeque<T>,
+    threshold: usize,
+    out: &mut VecDeque<T>,
+) {
+    if nodes.len() >= threshold {
+        for universe in par_map(nodes, &|node: T| node.universe()) {
+            out.extend(universe);
+        }
+    } else {
+        for node in nodes {
+            universe(node, threshold, out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::test_common::paper::{Expr, Stmt, proptest_exprs, proptest_stmts};
+
+    use super::*;
+
+    fn push_negation(expr: Expr) -> Option<Expr> {
+        match expr {
+            Expr::Neg(x) => match *x {
+                Expr::Neg(y) => Some(*y),
+                Expr::Add(a, b) => Some(Expr::Add(Box::new(Expr::Neg(a)), Box::new(Expr::Neg(b)))),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn double(expr: Expr) -> Expr {
+        match expr {
+            Expr::Val(x) => Expr::Val(x.wrapping_mul(2)),
+            x => x,
+        }
+    }
+
+    #[test]
+    fn par_map_keeps_order() {
+        let items: VecDeque<usize> = (0..1000).collect();
+        let expected: VecDeque<usize> = (0..1000).map(|x| x * 2).collect();
+        assert_eq!(par_map(items, &|x| x * 2), expected);
+        assert_eq!(par_map(VecDeque::new(), &|x: usize| x), VecDeque::new());
+    }
+
+    #[test]
+    fn par_rewrite_bi_many_top_level_constraints() {
+        let model: Vec<Expr> = (0..1000)
+            .map(|i| Expr::Neg(Box::new(Expr::Neg(Box::new(Expr::Val(i))))))
+            .collect();
+        let expected: Vec<Expr> = (0..1000).map(Expr::Val).collect();
+
+        assert_eq!(model.par_rewrite_bi(&push_negation, 16), expected);
+        assert_eq!(
+            model.par_universe_bi(16),
+            Biplate::<Expr>::universe_bi(&model)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn par_same_as_sequential(expr in proptest_exprs(), threshold in 0..4usize) {
+            prop_assert_eq!(expr.par_transform(&double, threshold), expr.transform(&double));
+            prop_assert_eq!(expr.par_rewrite(&push_negation, threshold), expr.rewrite(&push_negation));
+            prop_assert_eq!(expr.par_universe(threshold), expr.universe());
+        }
+
+        #[test]
+        fn par_bi_same_as_sequential(stmt in proptest_stmts(), threshold in 0..4usize) {
+            prop_assert_eq!(stmt.par_transform_bi(&double, threshold), stmt.transform_bi(&double));
+            prop_assert_eq!(stmt.par_rewrite_bi(&push_negation, threshold), stmt.rewrite_bi(&push_negation));
+            prop_assert_eq!(stmt.par_universe_bi(threshold), Biplate::<Expr>::universe_bi(&stmt));
+
+            // Stmt is its own child under Biplate<Stmt>.
+            prop_assert_eq!(Biplate::<Stmt>::par_universe_bi(&stmt, threshold), stmt.universe());
+        }
+    }
+}
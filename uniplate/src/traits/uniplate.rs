@@ -1,9 +1,27 @@
-use super::context::ContextIter;
-use super::holes::HolesIter;
+use super::changed::{self, Transformed};
+use super::control::{TransformControl, VisitControl};
+use super::order::TraversalOrder;
+#[cfg(feature = "std")]
+use super::par;
+use super::universe::{
+    LeavesIter, LevelOrderIter, OrderedUniverseIter, PositionsIter, UniverseIter, visit,
+};
+use crate::context::{self, Context};
 
-use std::collections::VecDeque;
+use alloc::collections::VecDeque;
+use alloc::{boxed::Box, vec, vec::Vec};
 
-use crate::Tree;
+use crate::limits::{Limits, RewriteLimitExceeded};
+use crate::observer::{self, RewriteObserver};
+use crate::path::{self, Path, PathError};
+use crate::zipper::Zipper;
+use crate::{Biplate, Tree};
+
+/// An infinite iterator that panics when polled, for rebuilding a node from a list of children
+/// that may be too short.
+pub(super) fn too_few_children<T>() -> impl Iterator<Item = T> {
+    core::iter::repeat_with(|| panic!("with_children() given an unexpected amount of children"))
+}
 
 /// `Uniplate` for type `T` operates over all values of type `T` within `T`.
 pub trait Uniplate
@@ -13,33 +31,239 @@ where
     /// Definition of a `Uniplate`.
     ///
     /// This method is only useful for defining a Uniplate.
+    ///
+    /// [`children_into`](Uniplate::children_into) and [`rebuild`](Uniplate::rebuild) are provided
+    /// in terms of this method. Overriding them as well avoids allocating a context, so is faster;
+    /// this is what the derive macro does. Such instances can implement this method with
+    /// [`uniplate_via_rebuild`](crate::impl_helpers::uniplate_via_rebuild).
     fn uniplate(&self) -> (Tree<Self>, Box<dyn Fn(Tree<Self>) -> Self>);
 
+    /// Appends the direct children of this node to `out`, from left to right.
+    ///
+    /// This is only useful for defining a Uniplate; see [`uniplate`](Uniplate::uniplate).
+    fn children_into(&self, out: &mut Vec<Self>) {
+        let (children, _) = self.uniplate();
+        out.extend(children);
+    }
+
+    /// Reconstructs the node, taking its children from `children`.
+    ///
+    /// This takes as many children as [`children_into`](Uniplate::children_into) gives, in the
+    /// same order, leaving the rest of the iterator untouched.
+    ///
+    /// This is only useful for defining a Uniplate; see [`uniplate`](Uniplate::uniplate). Use
+    /// [`with_children`](Uniplate::with_children) instead.
+    ///
+    /// # Panics
+    ///
+    /// If `children` runs out.
+    fn rebuild(&self, children: &mut impl Iterator<Item = Self>) -> Self {
+        let (old_children, ctx) = self.uniplate();
+        let new_children = old_children
+            .try_map_children(&mut |_| children.next().ok_or(()))
+            .unwrap_or_else(|()| panic!("rebuild() given too few children"));
+        ctx(new_children)
+    }
+
     /// Applies a function to all direct children of this
     ///
     /// Consider using [`transform`](Uniplate::transform) instead, as it does bottom-up
     /// transformation of the entire tree.
     fn descend(&self, op: &impl Fn(Self) -> Self) -> Self {
-        let (children, ctx) = self.uniplate();
-        ctx(children.map(op))
+        self.rebuild(&mut self.children().into_iter().map(op))
+    }
+
+    /// Applies a fallible function to all direct children of this.
+    ///
+    /// Children are visited from left to right, stopping at the first error.
+    ///
+    /// Fallible variant of [`descend`](Uniplate::descend).
+    fn try_descend<E>(&self, op: &impl Fn(Self) -> Result<Self, E>) -> Result<Self, E> {
+        let children: Vec<Self> = self
+            .children()
+            .into_iter()
+            .map(op)
+            .collect::<Result<_, _>>()?;
+        Ok(self.rebuild(&mut children.into_iter()))
+    }
+
+    /// Applies a function to all direct children of this, threading an accumulator through each
+    /// call.
+    ///
+    /// Children are visited from left to right. Returns the final accumulator and the new node.
+    /// This is `mapAccumL` over the children of the node.
+    ///
+    /// Stateful variant of [`descend`](Uniplate::descend).
+    fn descend_accum<S>(&self, state: S, f: &mut impl FnMut(S, Self) -> (S, Self)) -> (S, Self) {
+        let mut new_children = Vec::new();
+        let state = self.children().into_iter().fold(state, |state, child| {
+            let (state, child) = f(state, child);
+            new_children.push(child);
+            state
+        });
+        (state, self.rebuild(&mut new_children.into_iter()))
     }
 
     /// Gets all children of a node, including itself and all children.
     ///
     /// Universe does a preorder traversal: it returns a given node first, followed by its
     /// children from left to right.
+    ///
+    /// To stop early without visiting the whole tree, use
+    /// [`universe_iter`](Uniplate::universe_iter) instead.
     fn universe(&self) -> VecDeque<Self> {
-        let mut results = VecDeque::from([self.clone()]);
-        for child in self.children() {
-            results.append(&mut child.universe());
-        }
-        results
+        self.universe_iter().collect()
+    }
+
+    /// Returns an iterator over a node and all of its descendants.
+    ///
+    /// Nodes are produced lazily in the same order as [`universe`](Uniplate::universe): a
+    /// preorder traversal from left to right.
+    ///
+    /// Each step still clones the children of the node it produces, so this allocates as it goes.
+    /// To traverse without cloning, use [`universe_ref`](crate::UniplateRef::universe_ref).
+    fn universe_iter(&self) -> impl Iterator<Item = Self> {
+        UniverseIter::new(VecDeque::from([self.clone()]))
+    }
+
+    /// Returns an iterator over a node and all of its descendants, in the given order.
+    ///
+    /// `universe_in(TraversalOrder::PreOrder)` produces the same nodes in the same order as
+    /// [`universe_iter`](Uniplate::universe_iter).
+    fn universe_in(&self, order: TraversalOrder) -> impl Iterator<Item = Self> {
+        OrderedUniverseIter::new(VecDeque::from([self.clone()]), order)
+    }
+
+    /// Returns an iterator over a node and all of its descendants in level order, paired with
+    /// their depth.
+    ///
+    /// The node itself has depth 0, its children depth 1, and so on. Nodes are produced level by
+    /// level, from left to right within each level, so all nodes of a given depth are adjacent.
+    fn level_order(&self) -> impl Iterator<Item = (usize, Self)> {
+        LevelOrderIter::new(VecDeque::from([self.clone()]))
+    }
+
+    /// Returns an iterator over all descendants of a node, not including the node itself.
+    ///
+    /// Nodes are produced lazily in preorder, from left to right.
+    fn descendants(&self) -> impl Iterator<Item = Self> {
+        UniverseIter::new(self.children())
+    }
+
+    /// Returns an iterator over all nodes in the universe that have no children, from left to
+    /// right.
+    ///
+    /// If the node itself has no children, this returns just the node.
+    fn leaves(&self) -> impl Iterator<Item = Self> {
+        LeavesIter::new(VecDeque::from([self.clone()]))
+    }
+
+    /// Returns an iterator over a node and all of its descendants, paired with their paths from
+    /// this node.
+    ///
+    /// Nodes are produced in the same order as [`universe`](Uniplate::universe): a preorder
+    /// traversal from left to right.
+    fn positions(&self) -> impl Iterator<Item = (Path, Self)> {
+        PositionsIter::new(vec![(Path::root(), self.clone())])
+    }
+
+    /// Gets the node at the given path from this node.
+    ///
+    /// Returns `None` if there is no such node.
+    fn get_at(&self, path: &Path) -> Option<Self> {
+        path::get_at(self.clone(), path.indices())
+    }
+
+    /// Replaces the node at the given path with `new`, returning the new root.
+    ///
+    /// Returns an error if there is no node at the given path.
+    fn replace_at(&self, path: &Path, new: Self) -> Result<Self, PathError> {
+        self.modify_at(path, |_| new)
+    }
+
+    /// Applies `f` to the node at the given path, returning the new root.
+    ///
+    /// Returns an error if there is no node at the given path. In that case, `f` is not called.
+    fn modify_at(&self, path: &Path, f: impl FnOnce(Self) -> Self) -> Result<Self, PathError> {
+        path::modify_at(self, path, 0, f)
+    }
+
+    /// Visits a node and its descendants in preorder, letting the callback prune or stop the
+    /// traversal.
+    ///
+    /// For each node, `f` returns:
+    ///
+    /// * [`VisitControl::Continue`] to go on to the node's children;
+    /// * [`VisitControl::SkipChildren`] to skip the node's children, but carry on with the rest of
+    ///   the tree;
+    /// * [`VisitControl::Break`] to stop the traversal immediately.
+    ///
+    /// Returns the value given to `Break`, or `None` if the traversal ran to completion.
+    fn visit<B>(&self, f: &mut impl FnMut(&Self) -> VisitControl<B>) -> Option<B> {
+        visit(VecDeque::from([self.clone()]), f)
+    }
+
+    /// Returns the first node in the universe that satisfies the predicate.
+    ///
+    /// Nodes are checked in preorder, and the traversal stops at the first match.
+    fn find_node(&self, pred: &impl Fn(&Self) -> bool) -> Option<Self> {
+        self.universe_iter().find(|x| pred(x))
+    }
+
+    /// Returns the first non-`None` result of applying `op` to the nodes in the universe.
+    ///
+    /// Nodes are visited in preorder, and the traversal stops at the first `Some`.
+    fn find_map_node<T>(&self, op: &impl Fn(Self) -> Option<T>) -> Option<T> {
+        self.universe_iter().find_map(op)
+    }
+
+    /// Returns true if any node in the universe satisfies the predicate.
+    ///
+    /// The traversal stops at the first node that does.
+    fn any_node(&self, pred: &impl Fn(&Self) -> bool) -> bool {
+        self.universe_iter().any(|x| pred(&x))
+    }
+
+    /// Returns true if every node in the universe satisfies the predicate.
+    ///
+    /// The traversal stops at the first node that does not.
+    fn all_node(&self, pred: &impl Fn(&Self) -> bool) -> bool {
+        self.universe_iter().all(|x| pred(&x))
+    }
+
+    /// Returns the number of nodes in the universe that satisfy the predicate.
+    fn count_node(&self, pred: &impl Fn(&Self) -> bool) -> usize {
+        self.universe_iter().filter(|x| pred(x)).count()
+    }
+
+    /// Returns true if `x` is equal to this node or any of its descendants.
+    ///
+    /// The traversal stops at the first match.
+    fn contains_node(&self, x: &Self) -> bool {
+        self.any_node(&|y| y == x)
+    }
+
+    /// Returns true if this node is equal to `other` or any of its descendants.
+    ///
+    /// This is [`contains_node`](Uniplate::contains_node) with its arguments flipped.
+    fn is_subterm_of(&self, other: &Self) -> bool {
+        other.contains_node(self)
+    }
+
+    /// Returns true if this node is equal to any value of type `Self` inside `other`, or any of
+    /// their descendants.
+    ///
+    /// Biplate variant of [`is_subterm_of`](Uniplate::is_subterm_of): this is
+    /// [`contains_bi`](Biplate::contains_bi) with its arguments flipped.
+    fn is_subterm_of_bi<From: Biplate<Self>>(&self, other: &From) -> bool {
+        other.contains_bi(self)
     }
 
     /// Gets the direct children (maximal substructures) of a node.
     fn children(&self) -> VecDeque<Self> {
-        let (children, _) = self.uniplate();
-        children.list().0.clone()
+        let mut children = Vec::new();
+        self.children_into(&mut children);
+        children.into()
     }
 
     /// Reconstructs the node with the given children.
@@ -49,37 +273,259 @@ where
     /// If there are a different number of children given as there were originally returned by
     /// children().
     fn with_children(&self, children: VecDeque<Self>) -> Self {
-        // 1. Turn old tree into list.
-        // 2. Check lists are same size.
-        // 3. Use the reconstruction function given by old_children.list() to
-        //   create a tree with the same structure but the new lists' elements .
-
-        let (old_children, ctx) = self.uniplate();
-        let (old_children_lst, rebuild) = old_children.list();
-        if old_children_lst.len() != children.len() {
+        let mut children = children.into_iter();
+        let node = self.rebuild(&mut children.by_ref().chain(too_few_children()));
+        if children.next().is_some() {
             panic!("with_children() given an unexpected amount of children");
-        } else {
-            ctx(rebuild(children))
         }
+        node
     }
 
     /// Applies the given function to all nodes bottom up.
     fn transform(&self, f: &impl Fn(Self) -> Self) -> Self {
-        let (children, ctx) = self.uniplate();
-        f(ctx(children.map(&|child| child.transform(f))))
+        f(self.descend(&|child| child.transform(f)))
     }
 
-    /// Rewrites by applying a rule everywhere it can.
-    fn rewrite(&self, f: &impl Fn(Self) -> Option<Self>) -> Self {
-        let (children, ctx) = self.uniplate();
+    /// Applies the given function to all nodes bottom up, reporting each change to `observer`.
+    ///
+    /// A node is reported as changed if `f` returns a different value for it. See
+    /// [`crate::observer`].
+    ///
+    /// Observed variant of [`transform`](Uniplate::transform).
+    fn transform_observed(
+        &self,
+        f: &impl Fn(Self) -> Self,
+        observer: &mut impl RewriteObserver<Self>,
+    ) -> Self {
+        observer::single_pass(self, observer, |root, observer| {
+            observer::transform(root, &mut Path::root(), f, observer)
+        })
+    }
 
-        let new_children = children.map(&|child| child.rewrite(f));
+    /// Applies the given function to all nodes top down.
+    ///
+    /// The function is applied to a node before its children. The traversal then continues into
+    /// the children of the node returned by the function, so any children it introduces are also
+    /// transformed.
+    ///
+    /// If `f` always returns a node with children, this function will not terminate.
+    fn transform_top_down(&self, f: &impl Fn(Self) -> Self) -> Self {
+        f(self.clone()).descend(&|child| child.transform_top_down(f))
+    }
+
+    /// Applies the given function to all nodes top down, letting it decide whether to continue
+    /// into the children of each node.
+    ///
+    /// If `f` returns [`TransformControl::Continue`], the traversal continues into the children
+    /// of the returned node, like [`transform_top_down`](Uniplate::transform_top_down). If it
+    /// returns [`TransformControl::Stop`], the returned node is used as is, and its children are
+    /// not visited.
+    fn transform_with_control(&self, f: &impl Fn(Self) -> TransformControl<Self>) -> Self {
+        match f(self.clone()) {
+            TransformControl::Continue(x) => x.descend(&|child| child.transform_with_control(f)),
+            TransformControl::Stop(x) => x,
+        }
+    }
+
+    /// Applies the given function to all nodes bottom up, giving it mutable access to some state.
+    ///
+    /// Nodes are visited in the same order as [`transform`](Uniplate::transform): postorder, from
+    /// left to right (see [`TraversalOrder::PostOrder`]). That is, `f` sees the state left by all
+    /// the children of a node, and all of its left siblings and their descendants, before the node
+    /// itself. As with `transform`, each node is given to `f` after its children have been
+    /// transformed.
+    ///
+    /// Stateful variant of [`transform`](Uniplate::transform).
+    fn transform_with_state<S>(
+        &self,
+        state: &mut S,
+        f: &mut impl FnMut(&mut S, Self) -> Self,
+    ) -> Self {
+        let node = self.rebuild(
+            &mut self
+                .children()
+                .into_iter()
+                .map(|child| child.transform_with_state(state, f)),
+        );
+        f(state, node)
+    }
+
+    /// Applies the given fallible function to all nodes bottom up.
+    ///
+    /// Stops at the first error, returning it.
+    ///
+    /// Fallible variant of [`transform`](Uniplate::transform).
+    fn try_transform<E>(&self, f: &impl Fn(Self) -> Result<Self, E>) -> Result<Self, E> {
+        f(self.try_descend(&|child| child.try_transform(f))?)
+    }
 
-        match f(ctx(new_children.clone())) {
-            None => ctx(new_children),
+    /// Rewrites by applying a rule everywhere it can, in a single bottom-up pass.
+    ///
+    /// The rule is applied at most once to each node, after its children have been rewritten.
+    /// It is not applied again to the node it produces, so the result may still contain places
+    /// where the rule applies.
+    ///
+    /// To keep rewriting until the rule no longer applies anywhere, use
+    /// [`rewrite_fixpoint`](Uniplate::rewrite_fixpoint).
+    fn rewrite(&self, f: &impl Fn(Self) -> Option<Self>) -> Self {
+        let node = self.descend(&|child| child.rewrite(f));
+        match f(node.clone()) {
+            None => node,
             Some(n) => n,
         }
     }
+
+    /// Rewrites by applying a rule everywhere it can, until it no longer applies anywhere.
+    ///
+    /// This works bottom up. Whenever the rule produces a new node, that node is rewritten again
+    /// (including its children) before continuing. The result is therefore a normal form: the
+    /// rule returns `None` for every node in it.
+    ///
+    /// This is the same as `rewrite` in Haskell's Uniplate. If the rule can be applied
+    /// indefinitely, this function will not terminate.
+    fn rewrite_fixpoint(&self, f: &impl Fn(Self) -> Option<Self>) -> Self {
+        self.transform(&|x| match f(x.clone()) {
+            Some(y) => y.rewrite_fixpoint(f),
+            None => x,
+        })
+    }
+
+    /// Applies a function to all direct children of this, tracking whether anything changed.
+    ///
+    /// `f` returns `None` to leave a child unchanged. If no child changes, this node is not
+    /// rebuilt.
+    ///
+    /// Change-aware variant of [`descend`](Uniplate::descend).
+    fn descend_changed(&self, f: &impl Fn(&Self) -> Option<Self>) -> Transformed<Self> {
+        Transformed::from_option(self, changed::descend(self, f))
+    }
+
+    /// Applies the given function to all nodes bottom up, tracking whether anything changed.
+    ///
+    /// `f` returns `None` to leave a node unchanged. Nodes are only rebuilt if one of their
+    /// children changed, so a pass that changes nothing does not reconstruct the tree, and the
+    /// `changed` flag of the result can be used instead of comparing trees with `Eq`.
+    ///
+    /// Change-aware variant of [`transform`](Uniplate::transform) and
+    /// [`rewrite`](Uniplate::rewrite).
+    fn transform_changed(&self, f: &impl Fn(&Self) -> Option<Self>) -> Transformed<Self> {
+        Transformed::from_option(self, changed::transform(self, f))
+    }
+
+    /// Rewrites by applying a rule everywhere it can, until it no longer applies anywhere,
+    /// tracking whether anything changed.
+    ///
+    /// Nodes are only rebuilt if one of their children changed.
+    ///
+    /// Change-aware variant of [`rewrite_fixpoint`](Uniplate::rewrite_fixpoint).
+    fn rewrite_fixpoint_changed(&self, f: &impl Fn(&Self) -> Option<Self>) -> Transformed<Self> {
+        Transformed::from_option(self, changed::rewrite_fixpoint(self, f))
+    }
+
+    /// Returns all nodes in the tree, in the same order as [`universe`](Uniplate::universe),
+    /// splitting the work across threads.
+    ///
+    /// The children of nodes with at least `threshold` children are processed in parallel.
+    ///
+    /// Parallel variant of [`universe`](Uniplate::universe).
+    #[cfg(feature = "std")]
+    fn par_universe(&self, threshold: usize) -> VecDeque<Self>
+    where
+        Self: Send,
+    {
+        let mut out = VecDeque::new();
+        par::universe(self.clone(), threshold, &mut out);
+        out
+    }
+
+    /// Applies the given function to all nodes bottom up, splitting the work across threads.
+    ///
+    /// The children of nodes with at least `threshold` children are transformed in parallel. The
+    /// result is the same as that of [`transform`](Uniplate::transform).
+    ///
+    /// Parallel variant of [`transform`](Uniplate::transform).
+    #[cfg(feature = "std")]
+    fn par_transform(&self, f: &(impl Fn(Self) -> Self + Sync), threshold: usize) -> Self
+    where
+        Self: Send,
+    {
+        par::transform(self, f, threshold)
+    }
+
+    /// Rewrites by applying a rule everywhere it can, in a single bottom-up pass, splitting the
+    /// work across threads.
+    ///
+    /// The children of nodes with at least `threshold` children are rewritten in parallel. The
+    /// result is the same as that of [`rewrite`](Uniplate::rewrite).
+    ///
+    /// Parallel variant of [`rewrite`](Uniplate::rewrite).
+    #[cfg(feature = "std")]
+    fn par_rewrite(&self, f: &(impl Fn(Self) -> Option<Self> + Sync), threshold: usize) -> Self
+    where
+        Self: Send,
+    {
+        par::rewrite(self, f, threshold)
+    }
+
+    /// Rewrites by applying a rule everywhere it can, until it no longer applies anywhere or one
+    /// of `limits` is reached.
+    ///
+    /// If a limit is reached, returns the partially rewritten tree in the error. See
+    /// [`crate::limits`].
+    ///
+    /// Limited variant of [`rewrite_fixpoint`](Uniplate::rewrite_fixpoint).
+    fn rewrite_with_limits(
+        &self,
+        f: &impl Fn(Self) -> Option<Self>,
+        limits: &Limits,
+    ) -> Result<Self, RewriteLimitExceeded<Self>> {
+        Zipper::new(self.clone()).rewrite_with_limits(f, limits)
+    }
+
+    /// Rewrites by applying a rule everywhere it can, in a single bottom-up pass, reporting each
+    /// application to `observer`.
+    ///
+    /// See [`crate::observer`].
+    ///
+    /// Observed variant of [`rewrite`](Uniplate::rewrite).
+    fn rewrite_observed(
+        &self,
+        f: &impl Fn(Self) -> Option<Self>,
+        observer: &mut impl RewriteObserver<Self>,
+    ) -> Self {
+        observer::single_pass(self, observer, |root, observer| {
+            observer::rewrite(root, &mut Path::root(), f, false, observer)
+        })
+    }
+
+    /// Rewrites by applying a rule everywhere it can, until it no longer applies anywhere,
+    /// reporting each application to `observer`.
+    ///
+    /// See [`crate::observer`].
+    ///
+    /// Observed variant of [`rewrite_fixpoint`](Uniplate::rewrite_fixpoint).
+    fn rewrite_fixpoint_observed(
+        &self,
+        f: &impl Fn(Self) -> Option<Self>,
+        observer: &mut impl RewriteObserver<Self>,
+    ) -> Self {
+        observer::single_pass(self, observer, |root, observer| {
+            observer::rewrite(root, &mut Path::root(), f, true, observer)
+        })
+    }
+
+    /// Rewrites by applying a fallible rule everywhere it can, in a single bottom-up pass.
+    ///
+    /// Stops at the first error, returning it.
+    ///
+    /// Fallible variant of [`rewrite`](Uniplate::rewrite).
+    fn try_rewrite<E>(&self, f: &impl Fn(Self) -> Result<Option<Self>, E>) -> Result<Self, E> {
+        let node = self.try_descend(&|child| child.try_rewrite(f))?;
+        match f(node.clone())? {
+            None => Ok(node),
+            Some(n) => Ok(n),
+        }
+    }
     /// Performs a fold-like computation on each value.
     ///
     /// Working from the bottom up, this applies the given callback function to each nested
@@ -100,13 +546,78 @@ where
         )
     }
 
+    /// Performs a fold-like computation on each value, giving the callback access to the
+    /// original children as well as their folded values.
+    ///
+    /// This is a paramorphism. It is like [`cata`](Uniplate::cata), except that each folded child
+    /// is paired with the child it was computed from.
+    ///
+    /// The meaning of the callback function is the following:
+    ///
+    ///   f(element_to_fold, [(child, folded_child)]) -> folded_element
+    fn para<T>(&self, op: &impl Fn(Self, VecDeque<(Self, T)>) -> T) -> T {
+        let children = self.children();
+        (*op)(
+            self.clone(),
+            children
+                .into_iter()
+                .map(|c| {
+                    let folded = c.para(op);
+                    (c, folded)
+                })
+                .collect(),
+        )
+    }
+
+    /// Builds a value top-down from a seed.
+    ///
+    /// This is an anamorphism, the dual of [`cata`](Uniplate::cata). The callback turns a seed
+    /// into a node, along with one seed for each of its children. Each child is then built from
+    /// its seed, and the node is reconstructed with the new children using
+    /// [`with_children`](Uniplate::with_children).
+    ///
+    /// The meaning of the callback function is the following:
+    ///
+    ///   f(seed) -> (node, child_seeds)
+    ///
+    /// # Panics
+    ///
+    /// If the callback returns a different number of seeds than the node has children.
+    fn ana<S>(seed: S, op: &impl Fn(S) -> (Self, VecDeque<S>)) -> Self {
+        let (node, seeds) = (*op)(seed);
+        node.with_children(seeds.into_iter().map(|s| Self::ana(s, op)).collect())
+    }
+
+    /// Builds a value from a seed and folds it, without constructing the intermediate value.
+    ///
+    /// This is a hylomorphism: an [`ana`](Uniplate::ana) followed by a
+    /// [`cata`](Uniplate::cata), fused together.
+    ///
+    /// The node given to `fold` is the one returned by `unfold`, before its children are built.
+    /// Therefore, `fold` should only inspect the node itself, and use the folded children instead
+    /// of its children.
+    fn hylo<S, T>(
+        seed: S,
+        unfold: &impl Fn(S) -> (Self, VecDeque<S>),
+        fold: &impl Fn(Self, VecDeque<T>) -> T,
+    ) -> T {
+        let (node, seeds) = (*unfold)(seed);
+        (*fold)(
+            node,
+            seeds
+                .into_iter()
+                .map(|s| Self::hylo(s, unfold, fold))
+                .collect(),
+        )
+    }
+
     /// Returns an iterator over all direct children of the input, paired with a function that
     /// "fills the hole" where the child was with a new value.
+    ///
+    /// To store the holes, use [`hole_contexts`](Uniplate::hole_contexts) instead.
     fn holes(&self) -> impl Iterator<Item = (Self, impl Fn(Self) -> Self)> {
-        // must be an iterator as we cannot clone Box<dyn Fn()>'s, so cannot stick them in
-        // vectors, etc
-
-        HolesIter::new(self.clone())
+        self.hole_contexts()
+            .map(|(node, context)| (node, move |x| context.fill(x)))
     }
 
     /// Returns an iterator over the universe of the input, paired with a function that "fills the
@@ -114,26 +625,527 @@ where
     ///
     /// The [`universe`](Uniplate::universe) equivalent of [`holes`](Uniplate::holes).
     ///
-    /// To efficiently update multiple values in a single traversal, use
+    /// To store the contexts, use [`universe_contexts`](Uniplate::universe_contexts) instead. To
+    /// efficiently update multiple values in a single traversal, use
     /// [`Zipper`](crate::zipper::Zipper) instead.
     fn contexts(&self) -> impl Iterator<Item = (Self, impl Fn(Self) -> Self)> {
-        ContextIter::new(self.clone())
+        self.universe_contexts()
+            .map(|(node, context)| (node, move |x| context.fill(x)))
+    }
+
+    /// Returns an iterator over all direct children of the input, paired with their
+    /// [`Context`].
+    ///
+    /// Like [`holes`](Uniplate::holes), but the contexts can be cloned, stored, and filled later.
+    fn hole_contexts(&self) -> impl Iterator<Item = (Self, Context<Self>)> {
+        context::hole_contexts(self.clone())
+    }
+
+    /// Returns an iterator over the universe of the input, paired with their [`Context`].
+    ///
+    /// Like [`contexts`](Uniplate::contexts), but the contexts can be cloned, stored, and filled
+    /// later.
+    fn universe_contexts(&self) -> impl Iterator<Item = (Self, Context<Self>)> {
+        context::universe_contexts(self.clone())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
     use proptest::prelude::*;
 
-    use crate::test_common::paper::proptest_stmts;
+    use crate::test_common::paper::{Expr, Stmt, proptest_exprs, proptest_stmts};
 
     use super::*;
+
+    fn increment_vals(expr: Expr) -> Expr {
+        match expr {
+            Expr::Val(x) => Expr::Val(x.wrapping_add(1)),
+            x => x,
+        }
+    }
+
+    /// Pushes negations inwards, removing double negations.
+    fn push_negation(expr: Expr) -> Option<Expr> {
+        match expr {
+            Expr::Neg(x) => match *x {
+                Expr::Neg(y) => Some(*y),
+                Expr::Add(a, b) => Some(Expr::Add(Box::new(Expr::Neg(a)), Box::new(Expr::Neg(b)))),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Replaces a sequence of sequences with a single sequence.
+    fn flatten_sequences(stmt: Stmt) -> Stmt {
+        match stmt {
+            Stmt::Sequence(xs) => Stmt::Sequence(
+                xs.into_iter()
+                    .flat_map(|x| match x {
+                        Stmt::Sequence(ys) => ys,
+                        x => vec![x],
+                    })
+                    .collect(),
+            ),
+            x => x,
+        }
+    }
+
+    #[test]
+    fn test_transform_top_down_sees_parent_first() {
+        use Expr::*;
+
+        // desugar a - b into a + -b, then remove the resulting double negations.
+        let desugar = |x: Expr| match x {
+            Sub(a, b) => Add(a, Box::new(Neg(b))),
+            Neg(x) => match *x {
+                Neg(y) => *y,
+                y => Neg(Box::new(y)),
+            },
+            x => x,
+        };
+
+        // 1 - -2
+        let expr = Sub(Box::new(Val(1)), Box::new(Neg(Box::new(Val(2)))));
+
+        assert_eq!(
+            expr.transform_top_down(&desugar),
+            Add(Box::new(Val(1)), Box::new(Val(2)))
+        );
+
+        // bottom up, the negation introduced by desugaring Sub is never revisited.
+        assert_eq!(
+            expr.transform(&desugar),
+            Add(
+                Box::new(Val(1)),
+                Box::new(Neg(Box::new(Neg(Box::new(Val(2))))))
+            )
+        );
+    }
+
+    #[test]
+    fn test_transform_with_control_stop_prunes_subtree() {
+        use Expr::*;
+
+        // -(1 + 2) + 3
+        let expr = Add(
+            Box::new(Neg(Box::new(Add(Box::new(Val(1)), Box::new(Val(2)))))),
+            Box::new(Val(3)),
+        );
+
+        let increment_outside_negations = |x: Expr| match x {
+            Val(n) => TransformControl::Continue(Val(n + 1)),
+            Neg(x) => TransformControl::Stop(Neg(x)),
+            x => TransformControl::Continue(x),
+        };
+
+        assert_eq!(
+            expr.transform_with_control(&increment_outside_negations),
+            Add(
+                Box::new(Neg(Box::new(Add(Box::new(Val(1)), Box::new(Val(2)))))),
+                Box::new(Val(4)),
+            )
+        );
+    }
+
+    #[test]
+    fn test_ana_builds_from_seed() {
+        use Expr::*;
+
+        // build 3 - (2 - (1 - 0)), using seeds of the form (n, is_leaf).
+        let expr = Expr::ana((3, false), &|seed: (i32, bool)| match seed {
+            (n, true) | (n @ 0, _) => (Val(n), VecDeque::new()),
+            (n, false) => (
+                Sub(Box::new(Val(0)), Box::new(Val(0))),
+                VecDeque::from([(n, true), (n - 1, false)]),
+            ),
+        });
+
+        let expected = (1..=3).fold(Val(0), |acc, n| Sub(Box::new(Val(n)), Box::new(acc)));
+        assert_eq!(expr, expected);
+    }
+
+    #[test]
+    fn test_universe_iter_is_preorder() {
+        use Expr::*;
+
+        // -(1 + 2) * 3
+        let expr = Mul(
+            Box::new(Neg(Box::new(Add(Box::new(Val(1)), Box::new(Val(2)))))),
+            Box::new(Val(3)),
+        );
+
+        let vals: Vec<i32> = expr
+            .universe_iter()
+            .filter_map(|x| match x {
+                Val(n) => Some(n),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(vals, vec![1, 2, 3]);
+
+        assert_eq!(expr.universe_iter().next(), Some(expr.clone()));
+        assert_eq!(expr.descendants().count(), 5);
+        assert_eq!(
+            expr.leaves().collect::<Vec<_>>(),
+            vec![Val(1), Val(2), Val(3)]
+        );
+    }
+
+    #[test]
+    fn test_traversal_orders() {
+        use Expr::*;
+
+        // -(1 + 2) * 3
+        let expr = Mul(
+            Box::new(Neg(Box::new(Add(Box::new(Val(1)), Box::new(Val(2)))))),
+            Box::new(Val(3)),
+        );
+
+        let vals = |order| -> Vec<i32> {
+            expr.universe_in(order)
+                .map(|x| match x {
+                    Mul(_, _) => -1,
+                    Neg(_) => -2,
+                    Add(_, _) => -3,
+                    Val(n) => n,
+                    _ => unreachable!(),
+                })
+                .collect()
+        };
+
+        assert_eq!(vals(TraversalOrder::PreOrder), vec![-1, -2, -3, 1, 2, 3]);
+        assert_eq!(vals(TraversalOrder::PostOrder), vec![1, 2, -3, -2, 3, -1]);
+        assert_eq!(
+            vals(TraversalOrder::BreadthFirst),
+            vec![-1, -2, 3, -3, 1, 2]
+        );
+
+        let depths: Vec<usize> = expr.level_order().map(|(depth, _)| depth).collect();
+        assert_eq!(depths, vec![0, 1, 1, 2, 3, 3]);
+    }
+
+    #[test]
+    fn test_queries_short_circuit() {
+        use Expr::*;
+        use core::cell::Cell;
+
+        // (1 + 2) * 3
+        let expr = Mul(
+            Box::new(Add(Box::new(Val(1)), Box::new(Val(2)))),
+            Box::new(Val(3)),
+        );
+
+        let visited = Cell::new(0);
+        let is_val = |x: &Expr| {
+            visited.set(visited.get() + 1);
+            matches!(x, Val(_))
+        };
+
+        // Mul, Add, Val(1)
+        assert_eq!(expr.find_node(&is_val), Some(Val(1)));
+        assert_eq!(visited.replace(0), 3);
+
+        assert!(expr.any_node(&is_val));
+        assert_eq!(visited.replace(0), 3);
+
+        assert!(!expr.all_node(&is_val));
+        assert_eq!(visited.replace(0), 1);
+
+        assert_eq!(expr.count_node(&is_val), 3);
+        assert_eq!(visited.replace(0), 5);
+
+        assert_eq!(
+            expr.find_map_node(&|x| match x {
+                Val(n) if n > 1 => Some(n),
+                _ => None,
+            }),
+            Some(2)
+        );
+
+        assert!(expr.contains_node(&Val(2)));
+        assert!(!expr.contains_node(&Val(4)));
+        assert!(Val(3).is_subterm_of(&expr));
+        assert!(expr.is_subterm_of(&expr));
+    }
+
+    /// A list, with `Uniplate` implemented through `uniplate` only.
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    enum ContextList {
+        Nil,
+        Cons(i32, Box<ContextList>),
+    }
+
+    impl Uniplate for ContextList {
+        fn uniplate(&self) -> (Tree<Self>, Box<dyn Fn(Tree<Self>) -> Self>) {
+            match self.clone() {
+                ContextList::Nil => (Tree::Zero, Box::new(|_| ContextList::Nil)),
+                ContextList::Cons(x, xs) => (
+                    Tree::One(*xs),
+                    Box::new(move |tree| {
+                        let xs = tree.into_iter().next().unwrap();
+                        ContextList::Cons(x, Box::new(xs))
+                    }),
+                ),
+            }
+        }
+    }
+
+    /// A list, with `Uniplate` implemented through `children_into` and `rebuild`.
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    enum RebuildList {
+        Nil,
+        Cons(i32, Box<RebuildList>),
+    }
+
+    impl Uniplate for RebuildList {
+        fn uniplate(&self) -> (Tree<Self>, Box<dyn Fn(Tree<Self>) -> Self>) {
+            crate::impl_helpers::uniplate_via_rebuild(self)
+        }
+
+        fn children_into(&self, out: &mut Vec<Self>) {
+            if let RebuildList::Cons(_, xs) = self {
+                out.push((**xs).clone());
+            }
+        }
+
+        fn rebuild(&self, children: &mut impl Iterator<Item = Self>) -> Self {
+            match self {
+                RebuildList::Nil => RebuildList::Nil,
+                RebuildList::Cons(x, _) => {
+                    RebuildList::Cons(*x, Box::new(children.next().unwrap()))
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_instance_with_uniplate_only() {
+        use ContextList::*;
+
+        let list = Cons(1, Box::new(Cons(2, Box::new(Nil))));
+        assert_eq!(list.children(), VecDeque::from([Cons(2, Box::new(Nil))]));
+        assert_eq!(list.universe().len(), 3);
+
+        let mut children = Vec::new();
+        list.children_into(&mut children);
+        assert_eq!(children, Vec::from(list.children()));
+
+        let doubled = list.transform(&|x| match x {
+            Cons(n, xs) => Cons(n * 2, xs),
+            Nil => Nil,
+        });
+        assert_eq!(doubled, Cons(2, Box::new(Cons(4, Box::new(Nil)))));
+        assert_eq!(list.rebuild(&mut list.children().into_iter()), list);
+    }
+
+    #[test]
+    fn test_instance_with_rebuild_only() {
+        use RebuildList::*;
+
+        let list = Cons(1, Box::new(Cons(2, Box::new(Nil))));
+        let (children, ctx) = list.uniplate();
+        assert_eq!(
+            children,
+            Tree::Many(VecDeque::from([Tree::One(Cons(2, Box::new(Nil)))]))
+        );
+        assert_eq!(ctx(children), list);
+
+        let doubled = list.transform(&|x| match x {
+            Cons(n, xs) => Cons(n * 2, xs),
+            Nil => Nil,
+        });
+        assert_eq!(doubled, Cons(2, Box::new(Cons(4, Box::new(Nil)))));
+        assert_eq!(list.universe().len(), 3);
+    }
+
+    /// The number of `CountedList` nodes alive. Only one test uses this.
+    static LIVE_NODES: AtomicUsize = AtomicUsize::new(0);
+
+    /// A list that counts how many of its nodes are alive.
+    #[derive(PartialEq, Eq, Debug)]
+    struct CountedList(Option<Box<CountedList>>);
+
+    impl CountedList {
+        fn new(tail: Option<Box<CountedList>>) -> CountedList {
+            LIVE_NODES.fetch_add(1, Ordering::Relaxed);
+            CountedList(tail)
+        }
+    }
+
+    impl Clone for CountedList {
+        fn clone(&self) -> Self {
+            CountedList::new(self.0.clone())
+        }
+    }
+
+    impl Drop for CountedList {
+        fn drop(&mut self) {
+            LIVE_NODES.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    impl Uniplate for CountedList {
+        fn uniplate(&self) -> (Tree<Self>, Box<dyn Fn(Tree<Self>) -> Self>) {
+            match &self.0 {
+                None => (Tree::Zero, Box::new(|_| CountedList::new(None))),
+                Some(tail) => (
+                    Tree::One((**tail).clone()),
+                    Box::new(|tree| {
+                        let Tree::One(tail) = tree else { panic!() };
+                        CountedList::new(Some(Box::new(tail)))
+                    }),
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_universe_contexts_share_ancestors() {
+        let len = 100;
+        let mut list = CountedList::new(None);
+        for _ in 1..len {
+            list = CountedList::new(Some(Box::new(list)));
+        }
+        assert_eq!(LIVE_NODES.load(Ordering::Relaxed), len);
+
+        // the contexts of all nodes only keep one copy of the list alive between them, instead of
+        // a copy of every suffix.
+        let contexts: Vec<_> = list.universe_contexts().map(|(_, ctx)| ctx).collect();
+        assert_eq!(LIVE_NODES.load(Ordering::Relaxed), 2 * len);
+
+        let last = &contexts[len - 1];
+        assert_eq!(last.depth(), len - 1);
+        assert_eq!(last.fill(CountedList::new(None)), list);
+
+        drop(contexts);
+        assert_eq!(LIVE_NODES.load(Ordering::Relaxed), len);
+    }
+
+    #[test]
+    fn test_queries_do_not_shadow_std_methods() {
+        // String and Vec implement Uniplate, so these would resolve to the trait methods if they
+        // shared their names.
+        let s = String::from("abc");
+        assert_eq!(s.find('b'), Some(1));
+        assert!(s.contains("bc"));
+
+        let v: Vec<i32> = Vec::from([1, 2, 3]);
+        assert!(v.contains(&2));
+    }
+
+    #[test]
+    fn test_visit_skips_and_breaks() {
+        use Expr::*;
+
+        // -(1 + 2) * 3
+        let expr = Mul(
+            Box::new(Neg(Box::new(Add(Box::new(Val(1)), Box::new(Val(2)))))),
+            Box::new(Val(3)),
+        );
+
+        // Values outside negations.
+        let mut vals = Vec::new();
+        let result: Option<()> = expr.visit(&mut |x| match x {
+            Neg(_) => VisitControl::SkipChildren,
+            Val(n) => {
+                vals.push(*n);
+                VisitControl::Continue
+            }
+            _ => VisitControl::Continue,
+        });
+        assert_eq!(result, None);
+        assert_eq!(vals, vec![3]);
+
+        // First value greater than 1.
+        let mut visited = 0;
+        let result = expr.visit(&mut |x| {
+            visited += 1;
+            match x {
+                Val(n) if *n > 1 => VisitControl::Break(*n),
+                _ => VisitControl::Continue,
+            }
+        });
+        assert_eq!(result, Some(2));
+        assert_eq!(visited, 5);
+    }
+
+    #[test]
+    fn test_transform_with_state_numbers_values() {
+        use Expr::*;
+
+        // -(0 + 0) * 0
+        let expr = Mul(
+            Box::new(Neg(Box::new(Add(Box::new(Val(0)), Box::new(Val(0)))))),
+            Box::new(Val(0)),
+        );
+
+        let mut counter = 0;
+        let numbered = expr.transform_with_state(&mut counter, &mut |n, x| match x {
+            Val(_) => {
+                *n += 1;
+                Val(*n)
+            }
+            x => x,
+        });
+
+        assert_eq!(counter, 3);
+        assert_eq!(
+            numbered,
+            Mul(
+                Box::new(Neg(Box::new(Add(Box::new(Val(1)), Box::new(Val(2)))))),
+                Box::new(Val(3)),
+            )
+        );
+    }
+
+    #[test]
+    fn test_rewrite_is_single_pass() {
+        use Expr::*;
+
+        // -(-1 + 2)
+        let expr = Neg(Box::new(Add(
+            Box::new(Neg(Box::new(Val(1)))),
+            Box::new(Val(2)),
+        )));
+
+        // the double negation created by the rule is not removed by a single pass.
+        assert_eq!(
+            expr.rewrite(&push_negation),
+            Add(
+                Box::new(Neg(Box::new(Neg(Box::new(Val(1)))))),
+                Box::new(Neg(Box::new(Val(2))))
+            )
+        );
+
+        assert_eq!(
+            expr.rewrite_fixpoint(&push_negation),
+            Add(Box::new(Val(1)), Box::new(Neg(Box::new(Val(2)))))
+        );
+    }
+
     proptest! {
         #[test]
         fn test_context_same_as_universe(ast in proptest_stmts()) {
             prop_assert_eq!(ast.universe(),ast.contexts().map(|(elem,_)| elem).collect::<VecDeque<_>>());
         }
 
+        #[test]
+        fn test_descendants_same_as_universe_without_root(ast in proptest_stmts()) {
+            let mut universe = ast.universe();
+            universe.pop_front();
+            prop_assert_eq!(ast.descendants().collect::<VecDeque<_>>(), universe);
+        }
+
+        #[test]
+        fn test_leaves_same_as_childless_universe(ast in proptest_stmts()) {
+            let expected: VecDeque<_> = ast.universe().into_iter().filter(|x| x.children().is_empty()).collect();
+            prop_assert_eq!(ast.leaves().collect::<VecDeque<_>>(), expected);
+        }
+
         #[test]
         fn test_holes_same_as_children(ast in proptest_stmts()) {
             prop_assert_eq!(ast.children(),ast.holes().map(|(elem,_)| elem).collect::<VecDeque<_>>());
@@ -145,5 +1157,296 @@ mod tests {
                 prop_assert_eq!(c(e.clone()),ast.clone())
             }
         }
+
+        #[test]
+        fn test_universe_contexts_stored_same_as_replace_at(ast in proptest_stmts()) {
+            let new = Stmt::Sequence(vec![]);
+            let contexts: Vec<_> = ast.universe_contexts().collect();
+            for ((path, _), (_, c)) in ast.positions().zip(contexts.iter().cloned()) {
+                prop_assert_eq!(c.path(), path.clone());
+                prop_assert_eq!(c.depth(), path.depth());
+                prop_assert_eq!(c.fill(new.clone()), ast.replace_at(&path, new.clone()).unwrap());
+            }
+            prop_assert!(contexts.into_iter().eq(ast.universe_contexts()));
+        }
+
+        #[test]
+        fn test_hole_contexts_same_as_holes(ast in proptest_stmts()) {
+            let new = Stmt::Sequence(vec![]);
+            for (i, ((e1, c1), (e2, c2))) in ast.hole_contexts().zip(ast.holes()).enumerate() {
+                prop_assert_eq!(e1, e2);
+                prop_assert_eq!(c1.path(), Path::from([i]));
+                prop_assert_eq!(c1.fill(new.clone()), c2(new.clone()));
+            }
+        }
+
+        #[test]
+        fn test_rebuild_takes_only_its_children(ast in proptest_stmts()) {
+            let mut children = Vec::new();
+            ast.children_into(&mut children);
+            prop_assert_eq!(&children, &Vec::from(ast.children()));
+
+            let extra = Stmt::Sequence(vec![]);
+            let mut children = children.into_iter().chain([extra.clone()]);
+            prop_assert_eq!(ast.rebuild(&mut children), ast.clone());
+            prop_assert_eq!(children.next(), Some(extra));
+        }
+
+        #[test]
+        fn test_try_descend_ok_same_as_descend(ast in proptest_stmts()) {
+            let actual: Result<_, ()> = ast.try_descend(&|x| Ok(x));
+            prop_assert_eq!(actual, Ok(ast.descend(&|x| x)));
+        }
+
+        #[test]
+        fn test_try_transform_ok_same_as_transform(ast in proptest_stmts()) {
+            let actual: Result<_, ()> = ast.try_transform(&|x| Ok(flatten_sequences(x)));
+            prop_assert_eq!(actual, Ok(ast.transform(&flatten_sequences)));
+        }
+
+        #[test]
+        fn test_try_rewrite_ok_same_as_rewrite(ast in proptest_stmts()) {
+            let rule = |x: Stmt| match x {
+                Stmt::Sequence(xs) if xs.len() == 1 => xs.into_iter().next(),
+                _ => None,
+            };
+            let actual: Result<_, ()> = ast.try_rewrite(&|x| Ok(rule(x)));
+            prop_assert_eq!(actual, Ok(ast.rewrite(&rule)));
+        }
+
+        #[test]
+        fn test_rewrite_fixpoint_is_normal_form(expr in proptest_exprs()) {
+            let result = expr.rewrite_fixpoint(&push_negation);
+            for x in result.universe() {
+                prop_assert_eq!(push_negation(x), None);
+            }
+        }
+
+        #[test]
+        fn test_rewrite_fixpoint_is_idempotent(expr in proptest_exprs()) {
+            let result = expr.rewrite_fixpoint(&push_negation);
+            prop_assert_eq!(result.rewrite_fixpoint(&push_negation), result);
+        }
+
+        #[test]
+        fn test_transform_top_down_leaf_map_same_as_transform(expr in proptest_exprs()) {
+            prop_assert_eq!(expr.transform_top_down(&increment_vals), expr.transform(&increment_vals));
+        }
+
+        #[test]
+        fn test_transform_with_control_continue_same_as_transform_top_down(expr in proptest_exprs()) {
+            let f = |x: Expr| TransformControl::Continue(increment_vals(x));
+            prop_assert_eq!(expr.transform_with_control(&f), expr.transform_top_down(&increment_vals));
+        }
+
+        #[test]
+        fn test_para_ignoring_children_same_as_cata(expr in proptest_exprs()) {
+            let size_cata = expr.cata(&|_, xs: VecDeque<usize>| 1 + xs.iter().sum::<usize>());
+            let size_para = expr.para(&|_, xs: VecDeque<(Expr, usize)>| {
+                1 + xs.iter().map(|(_, x)| x).sum::<usize>()
+            });
+            prop_assert_eq!(size_cata, size_para);
+        }
+
+        #[test]
+        fn test_para_pairs_folded_children_with_originals(expr in proptest_exprs()) {
+            // every folded child should be the size of the child it is paired with.
+            let ok = expr.para(&|_, xs: VecDeque<(Expr, (usize, bool))>| {
+                let ok = xs.iter().all(|(c, (n, ok))| *ok && c.universe().len() == *n);
+                (1 + xs.iter().map(|(_, (n, _))| n).sum::<usize>(), ok)
+            });
+            prop_assert!(ok.1);
+        }
+
+        #[test]
+        fn test_ana_children_is_identity(expr in proptest_exprs()) {
+            let rebuilt = Expr::ana(expr.clone(), &|x: Expr| {
+                let children = x.children();
+                (x, children)
+            });
+            prop_assert_eq!(rebuilt, expr);
+        }
+
+        #[test]
+        fn test_hylo_same_as_ana_then_cata(expr in proptest_exprs()) {
+            let unfold = |x: Expr| {
+                let children = x.children();
+                (x, children)
+            };
+            let count = |_, xs: VecDeque<usize>| 1 + xs.iter().sum::<usize>();
+            prop_assert_eq!(Expr::hylo(expr.clone(), &unfold, &count), Expr::ana(expr, &unfold).cata(&count));
+        }
+
+        #[test]
+        fn test_universe_in_preorder_same_as_universe(ast in proptest_stmts()) {
+            prop_assert_eq!(ast.universe_in(TraversalOrder::PreOrder).collect::<VecDeque<_>>(), ast.universe());
+        }
+
+        #[test]
+        fn test_universe_in_postorder_same_as_cata(ast in proptest_stmts()) {
+            let expected = ast.cata(&|node, children: VecDeque<Vec<Stmt>>| {
+                let mut nodes: Vec<Stmt> = children.into_iter().flatten().collect();
+                nodes.push(node);
+                nodes
+            });
+            prop_assert_eq!(ast.universe_in(TraversalOrder::PostOrder).collect::<Vec<_>>(), expected);
+        }
+
+        #[test]
+        fn test_universe_in_breadth_first_same_as_level_order(ast in proptest_stmts()) {
+            let (depths, nodes): (Vec<usize>, Vec<Stmt>) = ast.level_order().unzip();
+            prop_assert!(depths.is_sorted());
+            prop_assert_eq!(nodes.len(), ast.universe().len());
+            prop_assert_eq!(ast.universe_in(TraversalOrder::BreadthFirst).collect::<Vec<_>>(), nodes);
+        }
+
+        #[test]
+        fn test_level_order_depths_match_children(ast in proptest_stmts()) {
+            // the children of each node appear exactly one level below it.
+            let levels: Vec<(usize, Stmt)> = ast.level_order().collect();
+            for (depth, node) in &levels {
+                for child in node.children() {
+                    prop_assert!(levels.contains(&(depth + 1, child)));
+                }
+            }
+        }
+
+        #[test]
+        fn test_queries_same_as_universe(ast in proptest_stmts()) {
+            let universe = ast.universe();
+            let is_assign = |x: &Stmt| matches!(x, Stmt::Assign(_, _));
+
+            prop_assert_eq!(ast.find_node(&is_assign), universe.iter().find(|x| is_assign(x)).cloned());
+            prop_assert_eq!(ast.any_node(&is_assign), universe.iter().any(is_assign));
+            prop_assert_eq!(ast.all_node(&is_assign), universe.iter().all(is_assign));
+            prop_assert_eq!(ast.count_node(&is_assign), universe.iter().filter(|x| is_assign(x)).count());
+
+            let assigned_to = |x: Stmt| match x {
+                Stmt::Assign(name, _) => Some(name),
+                _ => None,
+            };
+            prop_assert_eq!(ast.find_map_node(&assigned_to), universe.iter().cloned().find_map(assigned_to));
+        }
+
+        #[test]
+        fn test_visit_continue_same_as_universe(ast in proptest_stmts()) {
+            let mut visited = VecDeque::new();
+            let result: Option<()> = ast.visit(&mut |x| {
+                visited.push_back(x.clone());
+                VisitControl::Continue
+            });
+            prop_assert_eq!(result, None);
+            prop_assert_eq!(visited, ast.universe());
+        }
+
+        #[test]
+        fn test_visit_skip_children_only_visits_root(ast in proptest_stmts()) {
+            let mut visited = Vec::new();
+            let result: Option<()> = ast.visit(&mut |x| {
+                visited.push(x.clone());
+                VisitControl::SkipChildren
+            });
+            prop_assert_eq!(result, None);
+            prop_assert_eq!(visited, vec![ast]);
+        }
+
+        #[test]
+        fn test_visit_break_same_as_find(ast in proptest_stmts()) {
+            let is_assign = |x: &Stmt| matches!(x, Stmt::Assign(_, _));
+            let result = ast.visit(&mut |x| {
+                if is_assign(x) {
+                    VisitControl::Break(x.clone())
+                } else {
+                    VisitControl::Continue
+                }
+            });
+            prop_assert_eq!(result, ast.find_node(&is_assign));
+        }
+
+        #[test]
+        fn test_transform_with_state_visits_in_postorder(ast in proptest_stmts()) {
+            let mut visited = Vec::new();
+            let result = ast.transform_with_state(&mut visited, &mut |visited, x| {
+                visited.push(x.clone());
+                x
+            });
+            prop_assert_eq!(&result, &ast);
+            prop_assert_eq!(visited, ast.universe_in(TraversalOrder::PostOrder).collect::<Vec<_>>());
+
+            let mut n = 0;
+            let result = ast.transform_with_state(&mut n, &mut |n, x| {
+                *n += 1;
+                flatten_sequences(x)
+            });
+            prop_assert_eq!(result, ast.transform(&flatten_sequences));
+            prop_assert_eq!(n, ast.universe().len());
+        }
+
+        #[test]
+        fn test_descend_accum_threads_state_through_children(ast in proptest_stmts()) {
+            let (n, result) = ast.descend_accum(0, &mut |n, x| (n + 1, flatten_sequences(x)));
+            prop_assert_eq!(n, ast.children().len());
+            prop_assert_eq!(result, ast.descend(&flatten_sequences));
+        }
+
+        #[test]
+        fn test_positions_same_as_universe(ast in proptest_stmts()) {
+            let (paths, nodes): (Vec<Path>, VecDeque<Stmt>) = ast.positions().unzip();
+            prop_assert_eq!(nodes, ast.universe());
+            for (path, node) in ast.positions() {
+                prop_assert_eq!(ast.get_at(&path), Some(node));
+            }
+
+            // paths are unique, and every node below the root has its parent before it.
+            for (i, path) in paths.iter().enumerate() {
+                prop_assert!(!paths[..i].contains(path));
+                if let Some(parent) = path.parent() {
+                    prop_assert!(paths[..i].contains(&parent));
+                }
+            }
+        }
+
+        #[test]
+        fn test_replace_at_same_as_get_at(ast in proptest_stmts()) {
+            let new = Stmt::Sequence(vec![]);
+            for (path, node) in ast.positions() {
+                prop_assert_eq!(ast.replace_at(&path, node), Ok(ast.clone()));
+
+                let replaced = ast.replace_at(&path, new.clone()).unwrap();
+                prop_assert_eq!(replaced.get_at(&path), Some(new.clone()));
+            }
+        }
+
+        #[test]
+        fn test_modify_at_invalid_path(ast in proptest_stmts()) {
+            for (path, node) in ast.positions() {
+                let invalid = path.child(node.children().len());
+                prop_assert_eq!(ast.get_at(&invalid), None);
+
+                let err = ast.modify_at(&invalid, |_| unreachable!()).unwrap_err();
+                prop_assert_eq!(err.path(), &invalid);
+                prop_assert_eq!(err.valid_prefix(), path);
+            }
+        }
+
+        #[test]
+        fn test_universe_is_subterm_of(ast in proptest_stmts()) {
+            for x in ast.universe() {
+                prop_assert!(ast.contains_node(&x));
+                prop_assert!(x.is_subterm_of(&ast));
+            }
+        }
+
+        #[test]
+        fn test_try_transform_returns_first_error(ast in proptest_stmts()) {
+            // the first error should come from the first node to be transformed, which is the
+            // left-most leaf.
+            let mut first = ast.clone();
+            while let Some(child) = first.children().pop_front() {
+                first = child;
+            }
+
+            prop_assert_eq!(ast.try_transform(&|x| Err::<Stmt, _>(x)), Err(first));
+        }
     }
 }
@@ -0,0 +1,97 @@
+/// `UniplateMut` for type `T` operates in place over all values of type `T` within `T`.
+///
+/// This is a mutable companion to [`Uniplate`](crate::Uniplate). Its operations hand out `&mut`
+/// references to children instead of rebuilding the tree through a context, so small edits to
+/// large trees do not reallocate every parent.
+///
+/// The derive macro generates `UniplateMut` and [`BiplateMut`](crate::BiplateMut) instances
+/// alongside `Uniplate` and `Biplate` for types marked `#[uniplate(by_mut)]`. Every field type
+/// that has a `Biplate` instance for the target type must then have a `BiplateMut` instance as
+/// well, or the derived code fails to compile.
+pub trait UniplateMut
+where
+    Self: Sized + 'static,
+{
+    /// Definition of a `UniplateMut`.
+    ///
+    /// Calls `f` on each direct child of this node, from left to right.
+    ///
+    /// This method is only useful for defining a `UniplateMut`.
+    fn for_each_child_mut(&mut self, f: &mut impl FnMut(&mut Self));
+
+    /// Applies a function to all direct children of this node, in place.
+    ///
+    /// In-place variant of [`Uniplate::descend`](crate::Uniplate::descend).
+    fn descend_mut(&mut self, f: &mut impl FnMut(&mut Self)) {
+        self.for_each_child_mut(f);
+    }
+
+    /// Applies the given function to all nodes bottom up, in place.
+    ///
+    /// In-place variant of [`Uniplate::transform`](crate::Uniplate::transform). Each node is
+    /// visited after all of its children have been.
+    fn transform_mut(&mut self, f: &mut impl FnMut(&mut Self)) {
+        self.for_each_child_mut(&mut |child| child.transform_mut(f));
+        f(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::Uniplate;
+    use crate::test_common::paper::{Expr, proptest_exprs};
+
+    use super::*;
+
+    fn negate(expr: Expr) -> Expr {
+        match expr {
+            Expr::Val(x) => Expr::Val(x.wrapping_neg()),
+            x => x,
+        }
+    }
+
+    fn negate_mut(expr: &mut Expr) {
+        if let Expr::Val(x) = expr {
+            *x = x.wrapping_neg();
+        }
+    }
+
+    /// Collects the nodes of a tree in postorder, from left to right.
+    fn postorder(x: &Expr, out: &mut Vec<Expr>) {
+        for child in x.children() {
+            postorder(&child, out);
+        }
+        out.push(x.clone());
+    }
+
+    proptest! {
+        #[test]
+        fn descend_mut_same_as_descend(ast in proptest_exprs()) {
+            let expected = ast.descend(&|x| Expr::Neg(Box::new(x)));
+            let mut actual = ast.clone();
+            actual.descend_mut(&mut |x| *x = Expr::Neg(Box::new(x.clone())));
+            prop_assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn transform_mut_same_as_transform(ast in proptest_exprs()) {
+            let expected = ast.transform(&negate);
+            let mut actual = ast.clone();
+            actual.transform_mut(&mut negate_mut);
+            prop_assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn transform_mut_is_bottom_up(ast in proptest_exprs()) {
+            let mut visited = Vec::new();
+            let mut ast = ast;
+            ast.transform_mut(&mut |x| visited.push(x.clone()));
+
+            let mut expected = Vec::new();
+            postorder(&ast, &mut expected);
+            prop_assert_eq!(visited, expected);
+        }
+    }
+}
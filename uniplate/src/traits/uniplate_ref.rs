@@ -0,0 +1,84 @@
+use super::universe::UniverseRefIter;
+use crate::path::Path;
+use alloc::{vec, vec::Vec};
+
+/// `UniplateRef` for type `T` operates over references to all values of type `T` within `T`.
+///
+/// This is a borrowing companion to [`Uniplate`](crate::Uniplate). Its operations visit children
+/// by reference instead of cloning them, making them much cheaper for read-only analyses of large
+/// trees.
+///
+/// The derive macro generates `UniplateRef` and [`BiplateRef`](crate::BiplateRef) instances
+/// alongside `Uniplate` and `Biplate` for types marked `#[uniplate(by_ref)]`. Every field type
+/// that has a `Biplate` instance for the target type must then have a `BiplateRef` instance as
+/// well, or the derived code fails to compile.
+pub trait UniplateRef
+where
+    Self: Sized + 'static,
+{
+    /// Definition of a `UniplateRef`.
+    ///
+    /// Calls `f` on each direct child of this node, from left to right.
+    ///
+    /// This method is only useful for defining a `UniplateRef`.
+    fn for_each_child<'a>(&'a self, f: &mut impl FnMut(&'a Self));
+
+    /// Borrows the direct children (maximal substructures) of a node.
+    ///
+    /// Borrowing variant of [`Uniplate::children`](crate::Uniplate::children).
+    fn children_ref(&self) -> Vec<&Self> {
+        let mut children = Vec::new();
+        self.for_each_child(&mut |child| children.push(child));
+        children
+    }
+
+    /// Returns an iterator over references to a node and all of its descendants.
+    ///
+    /// Borrowing variant of [`Uniplate::universe_iter`](crate::Uniplate::universe_iter). Like
+    /// the original, this does a preorder traversal, from left to right.
+    fn universe_ref(&self) -> impl Iterator<Item = &Self> {
+        UniverseRefIter::new(vec![self])
+    }
+
+    /// Borrows the node at the given path from this node.
+    ///
+    /// Borrowing variant of [`Uniplate::get_at`](crate::Uniplate::get_at).
+    fn get_at_ref(&self, path: &Path) -> Option<&Self> {
+        path.indices()
+            .iter()
+            .try_fold(self, |node, &index| node.children_ref().get(index).copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::Uniplate;
+    use crate::test_common::paper::{proptest_exprs, proptest_stmts};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn children_ref_same_as_children(ast in proptest_stmts()) {
+            let expected: Vec<_> = ast.children().into_iter().collect();
+            let actual: Vec<_> = ast.children_ref().into_iter().cloned().collect();
+            prop_assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn get_at_ref_same_as_get_at(ast in proptest_stmts()) {
+            for (path, node) in ast.positions() {
+                prop_assert_eq!(ast.get_at_ref(&path), Some(&node));
+            }
+        }
+
+        #[test]
+        fn universe_ref_same_as_universe(ast in proptest_exprs()) {
+            let expected: Vec<_> = ast.universe().into_iter().collect();
+            let actual: Vec<_> = ast.universe_ref().cloned().collect();
+            prop_assert_eq!(actual, expected);
+        }
+    }
+}
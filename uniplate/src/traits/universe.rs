@@ -0,0 +1,223 @@
+//! The underlying iterators for `Uniplate::universe_iter()` and friends.
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use crate::path::Path;
+
+use super::{TraversalOrder, Uniplate, UniplateRef, VisitControl};
+
+/// Preorder iterator over the universes of a list of nodes, using an explicit stack.
+///
+/// This is lazy, but not allocation-free: each call to `next` clones the children of the node it
+/// returns into a new `VecDeque`, and pushes them onto the stack.
+pub(super) struct UniverseIter<T: Uniplate> {
+    /// Nodes still to visit, next node last.
+    stack: Vec<T>,
+}
+
+impl<T: Uniplate> UniverseIter<T> {
+    /// Creates an iterator over the universes of the given nodes, from left to right.
+    pub(super) fn new(roots: VecDeque<T>) -> UniverseIter<T> {
+        UniverseIter {
+            stack: roots.into_iter().rev().collect(),
+        }
+    }
+}
+
+impl<T: Uniplate> Iterator for UniverseIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.children().into_iter().rev());
+        Some(node)
+    }
+}
+
+/// Visits the universes of a list of nodes in preorder, from left to right, as directed by `f`.
+///
+/// Returns the value of the first [`VisitControl::Break`], if any.
+pub(super) fn visit<T: Uniplate, B>(
+    roots: VecDeque<T>,
+    f: &mut impl FnMut(&T) -> VisitControl<B>,
+) -> Option<B> {
+    // Nodes still to visit, next node last.
+    let mut stack: Vec<T> = roots.into_iter().rev().collect();
+    while let Some(node) = stack.pop() {
+        match f(&node) {
+            VisitControl::Continue => stack.extend(node.children().into_iter().rev()),
+            VisitControl::SkipChildren => {}
+            VisitControl::Break(b) => return Some(b),
+        }
+    }
+    None
+}
+
+/// Iterator over the universes of a list of nodes in a given [`TraversalOrder`].
+pub(super) struct OrderedUniverseIter<T: Uniplate> {
+    order: TraversalOrder,
+
+    /// Nodes still to visit, and whether their children have already been queued.
+    ///
+    /// For depth-first orders this is a stack (next node last); for breadth-first order this is
+    /// a queue (next node first).
+    nodes: VecDeque<(T, bool)>,
+}
+
+impl<T: Uniplate> OrderedUniverseIter<T> {
+    /// Creates an iterator over the universes of the given nodes, from left to right.
+    pub(super) fn new(roots: VecDeque<T>, order: TraversalOrder) -> OrderedUniverseIter<T> {
+        let roots = roots.into_iter().map(|x| (x, false));
+        let nodes = match order {
+            TraversalOrder::PreOrder | TraversalOrder::PostOrder => roots.rev().collect(),
+            TraversalOrder::BreadthFirst => roots.collect(),
+        };
+        OrderedUniverseIter { order, nodes }
+    }
+}
+
+impl<T: Uniplate> Iterator for OrderedUniverseIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.order {
+            TraversalOrder::PreOrder => {
+                let (node, _) = self.nodes.pop_back()?;
+                let children = node.children().into_iter().rev();
+                self.nodes.extend(children.map(|x| (x, false)));
+                Some(node)
+            }
+            TraversalOrder::PostOrder => loop {
+                let (node, expanded) = self.nodes.pop_back()?;
+                if expanded {
+                    return Some(node);
+                }
+
+                // Revisit this node once all its children have been produced.
+                let children = node.children().into_iter().rev();
+                self.nodes.push_back((node, true));
+                self.nodes.extend(children.map(|x| (x, false)));
+            },
+            TraversalOrder::BreadthFirst => {
+                let (node, _) = self.nodes.pop_front()?;
+                let children = node.children().into_iter();
+                self.nodes.extend(children.map(|x| (x, false)));
+                Some(node)
+            }
+        }
+    }
+}
+
+/// Level-order iterator over the universes of a list of nodes, yielding each node with its depth.
+pub(super) struct LevelOrderIter<T: Uniplate> {
+    /// Nodes still to visit with their depths, next node first.
+    queue: VecDeque<(usize, T)>,
+}
+
+impl<T: Uniplate> LevelOrderIter<T> {
+    /// Creates an iterator over the universes of the given nodes, which are all at depth 0.
+    pub(super) fn new(roots: VecDeque<T>) -> LevelOrderIter<T> {
+        LevelOrderIter {
+            queue: roots.into_iter().map(|x| (0, x)).collect(),
+        }
+    }
+}
+
+impl<T: Uniplate> Iterator for LevelOrderIter<T> {
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, node) = self.queue.pop_front()?;
+        let children = node.children().into_iter();
+        self.queue.extend(children.map(|x| (depth + 1, x)));
+        Some((depth, node))
+    }
+}
+
+/// Preorder iterator over the universes of a list of nodes, yielding each node with its path.
+pub(super) struct PositionsIter<T: Uniplate> {
+    /// Nodes still to visit with their paths, next node last.
+    stack: Vec<(Path, T)>,
+}
+
+impl<T: Uniplate> PositionsIter<T> {
+    /// Creates an iterator over the universes of the given nodes at the given paths, from left to
+    /// right.
+    pub(super) fn new(roots: Vec<(Path, T)>) -> PositionsIter<T> {
+        PositionsIter {
+            stack: roots.into_iter().rev().collect(),
+        }
+    }
+}
+
+impl<T: Uniplate> Iterator for PositionsIter<T> {
+    type Item = (Path, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, node) = self.stack.pop()?;
+        let children = node.children().into_iter().enumerate().rev();
+        self.stack
+            .extend(children.map(|(i, child)| (path.child(i), child)));
+        Some((path, node))
+    }
+}
+
+/// Iterator over the nodes with no children in the universes of a list of nodes, from left to
+/// right.
+pub(super) struct LeavesIter<T: Uniplate> {
+    /// Nodes still to visit, next node last.
+    stack: Vec<T>,
+}
+
+impl<T: Uniplate> LeavesIter<T> {
+    /// Creates an iterator over the leaves of the given nodes, from left to right.
+    pub(super) fn new(roots: VecDeque<T>) -> LeavesIter<T> {
+        LeavesIter {
+            stack: roots.into_iter().rev().collect(),
+        }
+    }
+}
+
+impl<T: Uniplate> Iterator for LeavesIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let node = self.stack.pop()?;
+            let children = node.children();
+            if children.is_empty() {
+                return Some(node);
+            }
+            self.stack.extend(children.into_iter().rev());
+        }
+    }
+}
+
+/// Preorder iterator over references to the nodes in the universes of a list of nodes.
+pub(super) struct UniverseRefIter<'a, T: UniplateRef> {
+    /// Nodes still to visit, next node last.
+    stack: Vec<&'a T>,
+}
+
+impl<'a, T: UniplateRef> UniverseRefIter<'a, T> {
+    /// Creates an iterator over the universes of the given nodes, from left to right.
+    pub(super) fn new(mut roots: Vec<&'a T>) -> UniverseRefIter<'a, T> {
+        roots.reverse();
+        UniverseRefIter { stack: roots }
+    }
+}
+
+impl<'a, T: UniplateRef> Iterator for UniverseRefIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        let n = self.stack.len();
+        node.for_each_child(&mut |child| self.stack.push(child));
+
+        // children were pushed left to right, but the next node should be the left-most child.
+        self.stack[n..].reverse();
+        Some(node)
+    }
+}
@@ -1,4 +1,12 @@
-use std::collections::VecDeque;
+//! The [`Tree`] type, used to implement [`Uniplate`](crate::Uniplate) and
+//! [`Biplate`](crate::Biplate) instances by hand.
+
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use core::slice;
 
 use self::Tree::*;
 
@@ -8,6 +16,11 @@ use self::Tree::*;
 /// It is primarily used for implementing [`Uniplate`](super::Uniplate) and
 /// [`Biplate`](super::Biplate) instances.
 ///
+/// A `Tree` is stored as it is written: each [`Many`] node owns a `VecDeque` of its subtrees,
+/// so building a tree allocates once per `Many`. Only [`list`](Tree::list) uses a flat
+/// representation, remembering the structure of the tree in a single buffer that is stored
+/// inline for small trees.
+///
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Tree<T: Sized + Eq> {
     /// This element cannot contains no children.
@@ -20,10 +33,6 @@ pub enum Tree<T: Sized + Eq> {
     Many(VecDeque<Tree<T>>),
 }
 
-// NOTE (niklasdewally): This converts the entire tree into a list. Therefore this is only really
-// worth it when we use all the children returned. This is what we use this for inside Uniplate.
-// Because of this, I think a .iter() / IntoIterator for Tree<&T> is a bad idea.
-
 impl<T: Eq> Tree<T> {
     /// Returns true if the tree contains any `One` variants, false otherwise.
     pub fn is_empty(&self) -> bool {
@@ -33,17 +42,72 @@ impl<T: Eq> Tree<T> {
             Tree::Many(children) => children.iter().all(|tr| tr.is_empty()),
         }
     }
+
+    /// Applies `op` to each child from left to right, keeping the structure of the tree.
+    ///
+    /// Stops at the first error, returning it.
+    pub(crate) fn try_map_children<U: Eq, E>(
+        self,
+        op: &mut impl FnMut(T) -> Result<U, E>,
+    ) -> Result<Tree<U>, E> {
+        match self {
+            Zero => Ok(Zero),
+            One(t) => Ok(One(op(t)?)),
+            Many(ts) => Ok(Many(
+                ts.into_iter()
+                    .map(|t| t.try_map_children(op))
+                    .collect::<Result<_, _>>()?,
+            )),
+        }
+    }
+}
+
+/// Collects children into a [`Many`] of [`One`]s.
+impl<T: Eq> FromIterator<T> for Tree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Many(iter.into_iter().map(One).collect())
+    }
+}
+
+/// An iterator over the children of a [`Tree`], from left to right.
+///
+/// This is created by the [`into_iter`](IntoIterator::into_iter) method on [`Tree`].
+pub struct IntoIter<T: Eq> {
+    /// The subtrees left to visit, with the next one last.
+    stack: Vec<Tree<T>>,
+}
+
+impl<T: Eq> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while let Some(tree) = self.stack.pop() {
+            match tree {
+                Zero => {}
+                One(x) => return Some(x),
+                Many(ts) => self.stack.extend(ts.into_iter().rev()),
+            }
+        }
+        None
+    }
 }
 
-impl<T: Sized + Eq + 'static> IntoIterator for Tree<T> {
+impl<T: Eq> FusedIterator for IntoIter<T> {}
+
+// NOTE (niklasdewally): This converts the entire tree into a list. Therefore this is only really
+// worth it when we use all the children returned. This is what we use this for inside Uniplate.
+// Because of this, I think a .iter() / IntoIterator for Tree<&T> is a bad idea.
+
+impl<T: Sized + Eq> IntoIterator for Tree<T> {
     type Item = T;
 
-    type IntoIter = std::collections::vec_deque::IntoIter<T>;
+    type IntoIter = IntoIter<T>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.list().0.into_iter()
+        IntoIter { stack: vec![self] }
     }
 }
+
 impl<T: Sized + Eq + 'static> Tree<T> {
     /// Returns the tree as a list alongside a function to reconstruct the tree from a list.
     ///
@@ -52,78 +116,164 @@ impl<T: Sized + Eq + 'static> Tree<T> {
     pub fn list(self) -> (VecDeque<T>, Box<dyn Fn(VecDeque<T>) -> Tree<T>>) {
         // inspired by the Uniplate Haskell equivalent Data.Generics.Str::strStructure
         // https://github.com/ndmitchell/uniplate/blob/master/Data/Generics/Str.hs#L85
+        let mut children = VecDeque::new();
+        let mut shape = Shape::new();
+        shape.flatten(self, &mut children);
+
+        (
+            children,
+            Box::new(move |xs| shape.rebuild(&mut xs.into_iter())),
+        )
+    }
+
+    /// Applies a function over all elements in the tree.
+    pub fn map(self, op: &impl Fn(T) -> T) -> Tree<T> {
+        match self {
+            Zero => Zero,
+            One(t) => One(op(t)),
+            Many(ts) => Many(ts.into_iter().map(|t| t.map(op)).collect::<_>()),
+        }
+    }
 
-        fn flatten<T: Sized + Eq>(t: Tree<T>, xs: VecDeque<T>) -> VecDeque<T> {
-            match (t, xs) {
-                (Zero, xs) => xs,
-                (One(x), mut xs1) => {
-                    xs1.push_back(x);
-                    xs1
+    /// Applies a fallible function over all elements in the tree, from left to right.
+    ///
+    /// Stops at the first error, returning it.
+    pub fn try_map<E>(self, op: &impl Fn(T) -> Result<T, E>) -> Result<Tree<T>, E> {
+        match self {
+            Zero => Ok(Zero),
+            One(t) => Ok(One(op(t)?)),
+            Many(ts) => Ok(Many(
+                ts.into_iter()
+                    .map(|t| t.try_map(op))
+                    .collect::<Result<_, _>>()?,
+            )),
+        }
+    }
+
+    /// Applies a function over all elements in the tree from left to right, threading an
+    /// accumulator through each call.
+    ///
+    /// Returns the final accumulator and the new tree. This is `mapAccumL` over the elements of
+    /// the tree.
+    pub fn map_accum<S>(self, state: S, op: &mut impl FnMut(S, T) -> (S, T)) -> (S, Tree<T>) {
+        match self {
+            Zero => (state, Zero),
+            One(t) => {
+                let (state, t) = op(state, t);
+                (state, One(t))
+            }
+            Many(ts) => {
+                let mut state = state;
+                let mut new_ts = VecDeque::with_capacity(ts.len());
+                for t in ts {
+                    let (new_state, t) = t.map_accum(state, op);
+                    state = new_state;
+                    new_ts.push_back(t);
                 }
-                (Many(ts), xs) => ts.into_iter().fold(xs, |xs, t| flatten(t, xs)),
+                (state, Many(new_ts))
             }
         }
+    }
+}
+
+/// The number of nodes a [`Shape`] stores without allocating.
+///
+/// This fits `Zero`, `One(x)`, and `Many` of two `One`s: the trees of most leaves and binary
+/// nodes.
+const INLINE_NODES: usize = 3;
+
+/// A node of a [`Shape`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ShapeNode {
+    Zero,
+    One,
+
+    /// A `Many` node, followed by this many subtrees.
+    Many(usize),
+}
+
+/// The structure of a [`Tree`] without its children, as remembered by [`Tree::list`].
+///
+/// This is a flat list of nodes in pre-order, stored inline if there are few enough of them, so
+/// that the shape of a small tree takes no allocations and that of a large tree takes one.
+#[derive(Clone, Debug)]
+enum Shape {
+    /// The first `len` nodes of the array.
+    Inline(usize, [ShapeNode; INLINE_NODES]),
+    Heap(Vec<ShapeNode>),
+}
+
+impl Shape {
+    const fn new() -> Shape {
+        Shape::Inline(0, [ShapeNode::Zero; INLINE_NODES])
+    }
+
+    fn as_slice(&self) -> &[ShapeNode] {
+        match self {
+            Shape::Inline(len, nodes) => &nodes[..*len],
+            Shape::Heap(nodes) => nodes,
+        }
+    }
 
-        #[derive(PartialEq, Eq)]
-        struct DummyType;
-
-        // given a tree, construct a tree with the same structure but zero sized arguments.
-        fn construct_dummy_tree<T: Sized + Eq>(t: &Tree<T>) -> Tree<DummyType> {
-            match t {
-                Zero => Tree::Zero,
-                One(_) => Tree::One(DummyType {}),
-                Many(trees) => Tree::Many(
-                    trees
-                        .iter()
-                        .map(construct_dummy_tree)
-                        .collect::<VecDeque<Tree<DummyType>>>(),
-                ),
+    fn push(&mut self, node: ShapeNode) {
+        match self {
+            Shape::Inline(len, nodes) if *len < INLINE_NODES => {
+                nodes[*len] = node;
+                *len += 1;
             }
+            Shape::Inline(_, nodes) => {
+                let mut heap = Vec::with_capacity(2 * INLINE_NODES);
+                heap.extend_from_slice(nodes);
+                heap.push(node);
+                *self = Shape::Heap(heap);
+            }
+            Shape::Heap(nodes) => nodes.push(node),
         }
+    }
 
-        // Iterate over both the old tree and the new list.
-        // We use the node types of the old tree to know what node types to use for the new tree.
-        fn recons<T: Sized + Eq>(
-            old_tree: &Tree<DummyType>,
-            xs: VecDeque<T>,
-        ) -> (Tree<T>, VecDeque<T>) {
-            #[allow(clippy::unwrap_used)]
-            match (old_tree, xs) {
-                (Zero, xs) => (Zero, xs),
-                (One(_), mut xs1) => (One(xs1.pop_front().unwrap()), xs1),
-                (Many(ts), xs) => {
-                    let (ts1, xs1) =
-                        ts.into_iter()
-                            .fold((VecDeque::new(), xs), |(mut ts1, xs), t| {
-                                let (t1, xs1) = recons(t, xs);
-                                ts1.push_back(t1);
-                                (ts1, xs1)
-                            });
-                    (Many(ts1), xs1)
+    /// Appends the shape of `tree` to this shape, and its children to `children`.
+    fn flatten<T: Eq>(&mut self, tree: Tree<T>, children: &mut VecDeque<T>) {
+        match tree {
+            Zero => self.push(ShapeNode::Zero),
+            One(x) => {
+                self.push(ShapeNode::One);
+                children.push_back(x);
+            }
+            Many(ts) => {
+                self.push(ShapeNode::Many(ts.len()));
+                for t in ts {
+                    self.flatten(t, children);
                 }
             }
         }
-
-        let dummy_tree = construct_dummy_tree(&self);
-        (
-            flatten(self, VecDeque::new()),
-            Box::new(move |xs| recons(&dummy_tree, xs).0),
-        )
     }
 
-    /// Applies a function over all elements in the tree.
-    pub fn map(self, op: &impl Fn(T) -> T) -> Tree<T> {
-        match self {
-            Zero => Zero,
-            One(t) => One(op(t)),
-            Many(ts) => Many(ts.into_iter().map(|t| t.map(op)).collect::<_>()),
+    /// Builds a tree of this shape, taking its children from `children`.
+    ///
+    /// # Panics
+    ///
+    /// If `children` runs out.
+    fn rebuild<T: Eq>(&self, children: &mut impl Iterator<Item = T>) -> Tree<T> {
+        fn go<T: Eq>(
+            nodes: &mut slice::Iter<'_, ShapeNode>,
+            children: &mut impl Iterator<Item = T>,
+        ) -> Tree<T> {
+            match nodes.next() {
+                Some(ShapeNode::Zero) | None => Zero,
+                Some(ShapeNode::One) => {
+                    One(children.next().expect("list() given too few children"))
+                }
+                Some(ShapeNode::Many(n)) => Many((0..*n).map(|_| go(nodes, children)).collect()),
+            }
         }
+
+        go(&mut self.as_slice().iter(), children)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::iter::zip;
+    use core::iter::zip;
 
     use proptest::prelude::*;
 
@@ -153,6 +303,28 @@ mod tests {
             prop_assert_eq!(new_tree,tree);
         }
 
+        #[test]
+        fn into_iter_same_as_list(tree in proptest_integer_trees()) {
+            let (children, _) = tree.clone().list();
+            prop_assert_eq!(tree.into_iter().collect::<VecDeque<_>>(), children);
+        }
+
+        #[test]
+        fn try_map_ok_same_as_map(tree in proptest_integer_trees(), diff in -100i32..100i32) {
+            let expected = tree.clone().map(&|a| a.wrapping_add(diff));
+            let actual: Result<_, ()> = tree.try_map(&|a| Ok(a.wrapping_add(diff)));
+            prop_assert_eq!(actual, Ok(expected));
+        }
+
+        #[test]
+        fn map_accum_numbers_elements_left_to_right(tree in proptest_integer_trees()) {
+            let (n, numbered) = tree.clone().map_accum(0, &mut |i, _| (i + 1, i));
+            let (old_children, _) = tree.list();
+            let (new_children, _) = numbered.list();
+            prop_assert_eq!(n as usize, old_children.len());
+            prop_assert_eq!(new_children, (0..n).collect::<VecDeque<_>>());
+        }
+
         #[test]
         fn map_add(tree in proptest_integer_trees(), diff in -100i32..100i32) {
             let new_tree = tree.clone().map(&|a| a+diff);
@@ -163,7 +335,14 @@ mod tests {
                 prop_assert_eq!(old+diff,new);
             }
         }
+
+        #[test]
+        fn collect_same_as_many_of_ones(children in proptest::collection::vec(any::<i32>(), 0..20)) {
+            let expected = Tree::Many(children.iter().copied().map(Tree::One).collect());
+            prop_assert_eq!(children.into_iter().collect::<Tree<_>>(), expected);
+        }
     }
+
     #[test]
     fn list_preserves_ordering() {
         let my_tree: Tree<i32> = Many(VecDeque::from([
@@ -180,8 +359,27 @@ mod tests {
 
         let flat = my_tree.list().0;
 
-        for i in 0..5 {
-            assert_eq!(flat[i], i.try_into().unwrap());
+        for (i, x) in flat.into_iter().enumerate() {
+            assert_eq!(x, i.try_into().unwrap());
         }
     }
+
+    #[test]
+    fn shape_of_binary_node_is_inline() {
+        let mut shape = Shape::new();
+        shape.flatten(Many(VecDeque::from([One(1), One(2)])), &mut VecDeque::new());
+        assert!(matches!(shape, Shape::Inline(3, _)));
+
+        shape.push(ShapeNode::Zero);
+        assert!(matches!(shape, Shape::Heap(_)));
+        assert_eq!(
+            shape.as_slice(),
+            [
+                ShapeNode::Many(2),
+                ShapeNode::One,
+                ShapeNode::One,
+                ShapeNode::Zero
+            ]
+        );
+    }
 }
@@ -17,18 +17,30 @@
 //!
 //!   - [this explanatory blog post](https://pavpanchekha.com/blog/zippers/huet.html)
 
-use std::{collections::VecDeque, sync::Arc};
+use alloc::{collections::VecDeque, sync::Arc, vec, vec::Vec};
+use core::iter;
 
-use crate::{Biplate, Tree, Uniplate};
+use crate::limits::{Budget, Limit, Limits, RewriteLimitExceeded};
+use crate::path::Path;
+use crate::{Biplate, Uniplate};
 
 /// A Zipper over `Uniplate` types.
 ///
+/// The zipper keeps track of which nodes have changed since it moved down into them. Moving up
+/// out of an unchanged node returns the original parent without rebuilding it, and moving back
+/// down reuses its children, so traversals that only read the tree are cheap.
+///
 /// See the module-level documentation.
 #[derive(Clone)]
 pub struct Zipper<T: Uniplate> {
     /// The current node
     focus: T,
 
+    /// The children of the focus, if they are already known.
+    ///
+    /// This is cleared whenever the focus changes.
+    focus_children: Option<VecDeque<T>>,
+
     /// The path back to the top, immediate parent last.
     ///
     /// If empty, the focus is the top level node.
@@ -43,15 +55,15 @@ struct PathSegment<T: Uniplate> {
     /// Right siblings of the node
     right: VecDeque<T>,
 
-    // A possibility was to store parent and use with_children instead of doing it by hand this
-    // way. However, that would store an old copy of the subtree we are currently in, which is
-    // unnecessary.
+    // This keeps an old copy of the subtree we are currently in. If nothing changes, it is
+    // returned as is when moving up; otherwise, rebuilding the parent from it is much cheaper than
+    // building and calling a context closure.
     //
-    /// Function to convert this layer back into a tree given a full list of children
-    rebuild_tree: Arc<dyn Fn(VecDeque<T>) -> Tree<T>>,
+    /// The parent node, as it was before we moved down into it.
+    parent: Arc<T>,
 
-    // Function to create the parent node, given its children as a tree
-    ctx: Arc<dyn Fn(Tree<T>) -> T>,
+    /// Whether any children of the parent have changed since we moved down into it.
+    changed: bool,
 }
 
 impl<T: Uniplate> Zipper<T> {
@@ -61,6 +73,7 @@ impl<T: Uniplate> Zipper<T> {
     pub fn new(root: T) -> Self {
         Zipper {
             focus: root,
+            focus_children: None,
             path: Vec::new(),
         }
     }
@@ -71,13 +84,25 @@ impl<T: Uniplate> Zipper<T> {
     }
 
     /// Mutably borrows the current focus.
+    ///
+    /// The focus is considered changed, even if it is not modified through the returned reference.
     pub fn focus_mut(&mut self) -> &mut T {
+        self.mark_changed();
         &mut self.focus
     }
 
     /// Replaces the focus of the [Zipper], returning the old focus.
     pub fn replace_focus(&mut self, new_focus: T) -> T {
-        std::mem::replace(&mut self.focus, new_focus)
+        self.mark_changed();
+        core::mem::replace(&mut self.focus, new_focus)
+    }
+
+    /// Records that the focus has changed, so its parent must be rebuilt when moving up.
+    fn mark_changed(&mut self) {
+        self.focus_children = None;
+        if let Some(path_segment) = self.path.last_mut() {
+            path_segment.changed = true;
+        }
     }
 
     /// Rebuilds the root node, consuming the [`Zipper`].
@@ -100,16 +125,20 @@ impl<T: Uniplate> Zipper<T> {
     }
 
     /// Sets the focus to the parent of the focus (if it exists).
+    ///
+    /// The parent is only rebuilt if one of its children has changed.
     pub fn go_up(&mut self) -> Option<()> {
-        let mut path_seg = self.path.pop()?;
-
-        // TODO: get rid of the clone if possible
-        path_seg.left.push_back(self.focus.clone());
-        path_seg.left.append(&mut path_seg.right);
-
-        let tree = (path_seg.rebuild_tree)(path_seg.left);
-
-        self.focus = (path_seg.ctx)(tree);
+        let PathSegment {
+            left,
+            right,
+            parent,
+            changed,
+        } = self.path.pop()?;
+
+        self.focus_children = go_up(&mut self.focus, left, right, parent, changed);
+        if changed {
+            self.mark_changed();
+        }
 
         Some(())
     }
@@ -121,33 +150,32 @@ impl<T: Uniplate> Zipper<T> {
 
     /// Sets the focus to the left-most child of the focus (if it exists).
     pub fn go_down(&mut self) -> Option<()> {
-        let (children, ctx) = self.focus.uniplate();
-        let (mut siblings, rebuild_tree) = children.list();
-        let new_focus = siblings.pop_front()?;
-        let new_segment = PathSegment {
+        let (new_focus, siblings) = go_down(&self.focus, &mut self.focus_children)?;
+        let parent = core::mem::replace(&mut self.focus, new_focus);
+        self.path.push(PathSegment {
             left: VecDeque::new(),
             right: siblings,
-            rebuild_tree: rebuild_tree.into(),
-            ctx: ctx.into(),
-        };
-
-        self.path.push(new_segment);
-        self.focus = new_focus;
+            parent: Arc::new(parent),
+            changed: false,
+        });
         Some(())
     }
 
     /// Check if the focus has children
     pub fn has_down(&self) -> bool {
-        let (children, _) = self.focus.uniplate();
-        !children.is_empty()
+        match &self.focus_children {
+            Some(children) => !children.is_empty(),
+            None => !self.focus.children().is_empty(),
+        }
     }
 
     /// Sets the focus to the left sibling of the focus (if it exists).
     pub fn go_left(&mut self) -> Option<()> {
         let path_segment = self.path.last_mut()?;
-        let new_focus = path_segment.left.pop_front()?;
-        let old_focus = std::mem::replace(&mut self.focus, new_focus);
-        path_segment.right.push_back(old_focus);
+        let new_focus = path_segment.left.pop_back()?;
+        let old_focus = core::mem::replace(&mut self.focus, new_focus);
+        path_segment.right.push_front(old_focus);
+        self.focus_children = None;
         Some(())
     }
 
@@ -163,8 +191,9 @@ impl<T: Uniplate> Zipper<T> {
     pub fn go_right(&mut self) -> Option<()> {
         let path_segment = self.path.last_mut()?;
         let new_focus = path_segment.right.pop_front()?;
-        let old_focus = std::mem::replace(&mut self.focus, new_focus);
+        let old_focus = core::mem::replace(&mut self.focus, new_focus);
         path_segment.left.push_back(old_focus);
+        self.focus_children = None;
         Some(())
     }
 
@@ -176,6 +205,36 @@ impl<T: Uniplate> Zipper<T> {
         !path_segment.right.is_empty()
     }
 
+    /// Returns the path from the root to the focus.
+    pub fn path(&self) -> Path {
+        self.path.iter().map(|seg| seg.left.len()).collect()
+    }
+
+    /// Sets the focus to the node at the given path from the root.
+    ///
+    /// If there is no node at the given path, returns `None` and leaves the focus where it was.
+    pub fn go_to(&mut self, path: &Path) -> Option<()> {
+        let old_path = self.path();
+        while self.go_up().is_some() {}
+        if self.go_down_path(path.indices()).is_none() {
+            while self.go_up().is_some() {}
+            self.go_down_path(old_path.indices())
+                .expect("the old path should still exist");
+            return None;
+        }
+        Some(())
+    }
+
+    /// Follows the given child indices down from the focus.
+    fn go_down_path(&mut self, indices: &[usize]) -> Option<()> {
+        for &index in indices {
+            self.go_down()?;
+            let seg = self.path.last_mut()?;
+            go_to_sibling(&mut self.focus, &mut seg.left, &mut seg.right, index)?;
+        }
+        Some(())
+    }
+
     /// Returns an iterator over the left siblings of the focus, in left-right order.
     pub fn iter_left_siblings(&self) -> impl Iterator<Item = &T> {
         self.path
@@ -199,7 +258,7 @@ impl<T: Uniplate> Zipper<T> {
     /// The iterator will yield left siblings first, then the focus, then right siblings.
     pub fn iter_siblings(&self) -> impl Iterator<Item = &T> {
         self.iter_left_siblings()
-            .chain(std::iter::once(self.focus()))
+            .chain(core::iter::once(self.focus()))
             .chain(self.iter_right_siblings())
     }
 
@@ -212,6 +271,30 @@ impl<T: Uniplate> Zipper<T> {
             zipper: self.clone(),
         }
     }
+
+    /// Rewrites the whole tree by applying a rule everywhere it can, until it no longer applies
+    /// anywhere or one of `limits` is reached, then rebuilds the root.
+    ///
+    /// The position of the focus does not matter. See [`crate::limits`].
+    ///
+    /// Zipper variant of [`Uniplate::rewrite_with_limits`].
+    pub fn rewrite_with_limits(
+        mut self,
+        f: &impl Fn(T) -> Option<T>,
+        limits: &Limits,
+    ) -> Result<T, RewriteLimitExceeded<T>> {
+        while self.go_up().is_some() {}
+
+        let mut budget = Budget::new(limits);
+        match rewrite_with_limits(&mut self, f, &mut budget) {
+            Ok(()) => Ok(self.rebuild_root()),
+            Err(limit) => Err(RewriteLimitExceeded {
+                partial_result: self.rebuild_root(),
+                steps: budget.steps(),
+                limit,
+            }),
+        }
+    }
 }
 
 struct AncestorsIter<T: Uniplate> {
@@ -233,12 +316,19 @@ impl<T: Uniplate> Iterator for AncestorsIter<T> {
 /// Unlike [`Zipper`], the root node can never be focused on (as it is not of type `To`). Instead,
 /// the initial node is the left-most child.
 ///
+/// Like [`Zipper`], this only rebuilds nodes that have changed.
+///
 /// See the module-level documentation.
 #[derive(Clone)]
 pub struct ZipperBi<To: Uniplate, From: Biplate<To>> {
     /// The current node
     focus: To,
 
+    /// The children of the focus, if they are already known.
+    ///
+    /// This is cleared whenever the focus changes.
+    focus_children: Option<VecDeque<To>>,
+
     /// The path back to the top, immediate parent last.
     ///
     /// If empty, the focus is the top level node.
@@ -255,11 +345,11 @@ enum PathSegmentBi<To: Uniplate, From: Biplate<To>> {
         /// Right siblings of the node
         right: VecDeque<To>,
 
-        /// Function to convert this layer back into a tree given a full list of children
-        rebuild_tree: Arc<dyn Fn(VecDeque<To>) -> Tree<To>>,
+        /// The root node, as it was before we moved down into it.
+        parent: Arc<From>,
 
-        // Function to create the parent node, given its children as a tree
-        ctx: Arc<dyn Fn(Tree<To>) -> From>,
+        /// Whether any children of the root have changed since we moved down into it.
+        changed: bool,
     },
 
     /// After the first level of the tree (where we call biplate), we use uniplate to traverse the
@@ -273,11 +363,11 @@ enum PathSegmentBi<To: Uniplate, From: Biplate<To>> {
         /// Right siblings of the node
         right: VecDeque<To>,
 
-        /// Function to convert this layer back into a tree given a full list of children
-        rebuild_tree: Arc<dyn Fn(VecDeque<To>) -> Tree<To>>,
+        /// The parent node, as it was before we moved down into it.
+        parent: Arc<To>,
 
-        // Function to create the parent node, given its children as a tree
-        ctx: Arc<dyn Fn(Tree<To>) -> To>,
+        /// Whether any children of the parent have changed since we moved down into it.
+        changed: bool,
     },
 }
 
@@ -290,18 +380,18 @@ impl<To: Uniplate, From: Biplate<To>> ZipperBi<To, From> {
     pub fn new(top: From) -> Option<Self> {
         // we can never focus on the top level node, just its immediate children.
 
-        let (children, ctx) = top.biplate();
-        let (mut siblings, rebuild_tree) = children.list();
+        let mut siblings = top.children_bi();
         let focus = siblings.pop_front()?;
         let segment = PathSegmentBi::Top {
             left: VecDeque::new(),
             right: siblings,
-            rebuild_tree: rebuild_tree.into(),
-            ctx: ctx.into(),
+            parent: Arc::new(top),
+            changed: false,
         };
 
         Some(ZipperBi {
             focus,
+            focus_children: None,
             path: vec![segment],
         })
     }
@@ -312,13 +402,28 @@ impl<To: Uniplate, From: Biplate<To>> ZipperBi<To, From> {
     }
 
     /// Mutably borrows the current focus.
+    ///
+    /// The focus is considered changed, even if it is not modified through the returned reference.
     pub fn focus_mut(&mut self) -> &mut To {
+        self.mark_changed();
         &mut self.focus
     }
 
     /// Replaces the focus, returning the old focus.
     pub fn replace_focus(&mut self, new_focus: To) -> To {
-        std::mem::replace(&mut self.focus, new_focus)
+        self.mark_changed();
+        core::mem::replace(&mut self.focus, new_focus)
+    }
+
+    /// Records that the focus has changed, so its parent must be rebuilt when moving up.
+    fn mark_changed(&mut self) {
+        self.focus_children = None;
+        match self.path.last_mut() {
+            Some(PathSegmentBi::Top { changed, .. } | PathSegmentBi::Node { changed, .. }) => {
+                *changed = true;
+            }
+            None => {}
+        }
     }
 
     /// Rebuilds the root node, consuming the [`ZipperBi`]
@@ -326,22 +431,26 @@ impl<To: Uniplate, From: Biplate<To>> ZipperBi<To, From> {
         while self.go_up().is_some() {}
 
         let Some(PathSegmentBi::Top {
-            mut left,
-            mut right,
-            rebuild_tree,
-            ctx,
+            left,
+            right,
+            mut parent,
+            changed,
         }) = self.path.pop()
         else {
             // go_up should leave us with a single PathSegmentBi::Top in the path
             unreachable!();
         };
 
-        left.push_back(self.focus.clone());
-        left.append(&mut right);
-
-        let tree = (rebuild_tree)(left);
+        if !changed {
+            // nothing has changed, so the root is still valid.
+            match Arc::try_unwrap(parent) {
+                Ok(root) => return root,
+                Err(shared) => parent = shared,
+            }
+        }
 
-        (ctx)(tree)
+        let mut children = left.into_iter().chain(iter::once(self.focus)).chain(right);
+        parent.rebuild_bi(&mut children)
     }
 
     /// Returns the depth of the focus from the root.
@@ -349,100 +458,335 @@ impl<To: Uniplate, From: Biplate<To>> ZipperBi<To, From> {
         self.path.len()
     }
 
+    /// Returns the path from the root to the focus.
+    ///
+    /// As the root is of type `From`, the first index of the path is into
+    /// [`children_bi`](Biplate::children_bi) of the root, and the path is never empty.
+    pub fn path(&self) -> Path {
+        self.path
+            .iter()
+            .map(|seg| match seg {
+                PathSegmentBi::Top { left, .. } => left.len(),
+                PathSegmentBi::Node { left, .. } => left.len(),
+            })
+            .collect()
+    }
+
+    /// Sets the focus to the node at the given path from the root.
+    ///
+    /// The first index of the path is into [`children_bi`](Biplate::children_bi) of the root.
+    ///
+    /// If there is no node of type `To` at the given path, returns `None` and leaves the focus
+    /// where it was. In particular, this is the case for the empty path, as the root is not of
+    /// type `To`.
+    pub fn go_to(&mut self, path: &Path) -> Option<()> {
+        let old_path = self.path();
+        if self.go_to_inner(path.indices()).is_none() {
+            self.go_to_inner(old_path.indices())
+                .expect("the old path should still exist");
+            return None;
+        }
+        Some(())
+    }
+
+    fn go_to_inner(&mut self, indices: &[usize]) -> Option<()> {
+        while self.go_up().is_some() {}
+        let (&first, rest) = indices.split_first()?;
+
+        let Some(PathSegmentBi::Top { left, right, .. }) = self.path.last_mut() else {
+            // go_up should leave us with a single PathSegmentBi::Top in the path
+            unreachable!();
+        };
+        if first != left.len() {
+            self.focus_children = None;
+        }
+        go_to_sibling(&mut self.focus, left, right, first)?;
+
+        for &index in rest {
+            self.go_down()?;
+            let Some(PathSegmentBi::Node { left, right, .. }) = self.path.last_mut() else {
+                unreachable!();
+            };
+            go_to_sibling(&mut self.focus, left, right, index)?;
+        }
+        Some(())
+    }
+
     /// Sets the focus to the parent of the focus, if it exists and is of type `To.
     ///
     /// To get the topmost node (of type `From`), use [`rebuild_root`](ZipperBi::rebuild_root).
+    ///
+    /// The parent is only rebuilt if one of its children has changed.
     pub fn go_up(&mut self) -> Option<()> {
-        let Some(PathSegmentBi::Node {
-            left: _,
-            right: _,
-            rebuild_tree: _,
-            ctx: _,
-        }) = self.path.last()
-        else {
+        let Some(PathSegmentBi::Node { .. }) = self.path.last() else {
             return None;
         };
 
         // the above ensures that we do not commit to the pop unless the let passes
         let Some(PathSegmentBi::Node {
-            mut left,
-            mut right,
-            rebuild_tree,
-            ctx,
+            left,
+            right,
+            parent,
+            changed,
         }) = self.path.pop()
         else {
             unreachable!();
         };
 
-        // TODO: get rid of the clone if possible
-        left.push_back(self.focus.clone());
-        left.append(&mut right);
-
-        let tree = (rebuild_tree)(left);
-
-        self.focus = (ctx)(tree);
+        self.focus_children = go_up(&mut self.focus, left, right, parent, changed);
+        if changed {
+            self.mark_changed();
+        }
 
         Some(())
     }
 
     /// Sets the focus to the left-most child of the focus (if it exists).
     pub fn go_down(&mut self) -> Option<()> {
-        let (children, ctx) = self.focus.uniplate();
-        let (mut siblings, rebuild_tree) = children.list();
-        let new_focus = siblings.pop_front()?;
-        let new_segment = PathSegmentBi::Node {
+        let (new_focus, siblings) = go_down(&self.focus, &mut self.focus_children)?;
+        let parent = core::mem::replace(&mut self.focus, new_focus);
+        self.path.push(PathSegmentBi::Node {
             left: VecDeque::new(),
             right: siblings,
-            rebuild_tree: rebuild_tree.into(),
-            ctx: ctx.into(),
-        };
-
-        self.path.push(new_segment);
-        self.focus = new_focus;
+            parent: Arc::new(parent),
+            changed: false,
+        });
         Some(())
     }
 
     /// Sets the focus to the left sibling of the focus (if it exists).
     pub fn go_left(&mut self) -> Option<()> {
         let (left, right) = match self.path.last_mut()? {
-            PathSegmentBi::Top {
-                left,
-                right,
-                rebuild_tree: _,
-                ctx: _,
-            } => (left, right),
-            PathSegmentBi::Node {
-                left,
-                right,
-                rebuild_tree: _,
-                ctx: _,
-            } => (left, right),
+            PathSegmentBi::Top { left, right, .. } => (left, right),
+            PathSegmentBi::Node { left, right, .. } => (left, right),
         };
-        let new_focus = left.pop_front()?;
-        let old_focus = std::mem::replace(&mut self.focus, new_focus);
-        right.push_back(old_focus);
+        let new_focus = left.pop_back()?;
+        let old_focus = core::mem::replace(&mut self.focus, new_focus);
+        right.push_front(old_focus);
+        self.focus_children = None;
         Some(())
     }
 
     /// Sets the focus to the right sibling of the focus (if it exists).
     pub fn go_right(&mut self) -> Option<()> {
         let (left, right) = match self.path.last_mut()? {
-            PathSegmentBi::Top {
-                left,
-                right,
-                rebuild_tree: _,
-                ctx: _,
-            } => (left, right),
-            PathSegmentBi::Node {
-                left,
-                right,
-                rebuild_tree: _,
-                ctx: _,
-            } => (left, right),
+            PathSegmentBi::Top { left, right, .. } => (left, right),
+            PathSegmentBi::Node { left, right, .. } => (left, right),
         };
         let new_focus = right.pop_front()?;
-        let old_focus = std::mem::replace(&mut self.focus, new_focus);
+        let old_focus = core::mem::replace(&mut self.focus, new_focus);
         left.push_back(old_focus);
+        self.focus_children = None;
         Some(())
     }
+
+    /// Rewrites all values of type `To` in the tree by applying a rule everywhere it can, until it
+    /// no longer applies anywhere or one of `limits` is reached, then rebuilds the root.
+    ///
+    /// The position of the focus does not matter. See [`crate::limits`].
+    ///
+    /// Zipper variant of [`Biplate::rewrite_bi_with_limits`].
+    pub fn rewrite_with_limits(
+        mut self,
+        f: &impl Fn(To) -> Option<To>,
+        limits: &Limits,
+    ) -> Result<From, RewriteLimitExceeded<From>> {
+        self.go_to_inner(&[0])
+            .expect("the root should have at least one child");
+
+        let mut budget = Budget::new(limits);
+        match rewrite_with_limits(&mut self, f, &mut budget) {
+            Ok(()) => Ok(self.rebuild_root()),
+            Err(limit) => Err(RewriteLimitExceeded {
+                partial_result: self.rebuild_root(),
+                steps: budget.steps(),
+                limit,
+            }),
+        }
+    }
+}
+
+/// The zipper operations used by [`rewrite_with_limits`].
+trait Cursor<T> {
+    fn focus(&self) -> &T;
+    fn replace_focus(&mut self, new_focus: T) -> T;
+    fn depth(&self) -> usize;
+    fn go_up(&mut self) -> Option<()>;
+    fn go_down(&mut self) -> Option<()>;
+    fn go_right(&mut self) -> Option<()>;
+}
+
+impl<T: Uniplate> Cursor<T> for Zipper<T> {
+    fn focus(&self) -> &T {
+        Zipper::focus(self)
+    }
+
+    fn replace_focus(&mut self, new_focus: T) -> T {
+        Zipper::replace_focus(self, new_focus)
+    }
+
+    fn depth(&self) -> usize {
+        Zipper::depth(self)
+    }
+
+    fn go_up(&mut self) -> Option<()> {
+        Zipper::go_up(self)
+    }
+
+    fn go_down(&mut self) -> Option<()> {
+        Zipper::go_down(self)
+    }
+
+    fn go_right(&mut self) -> Option<()> {
+        Zipper::go_right(self)
+    }
+}
+
+impl<To: Uniplate, From: Biplate<To>> Cursor<To> for ZipperBi<To, From> {
+    fn focus(&self) -> &To {
+        ZipperBi::focus(self)
+    }
+
+    fn replace_focus(&mut self, new_focus: To) -> To {
+        ZipperBi::replace_focus(self, new_focus)
+    }
+
+    fn depth(&self) -> usize {
+        ZipperBi::depth(self)
+    }
+
+    fn go_up(&mut self) -> Option<()> {
+        ZipperBi::go_up(self)
+    }
+
+    fn go_down(&mut self) -> Option<()> {
+        ZipperBi::go_down(self)
+    }
+
+    fn go_right(&mut self) -> Option<()> {
+        ZipperBi::go_right(self)
+    }
+}
+
+/// Rewrites the tree under `cursor` to a fixpoint, starting at the focus and finishing at the
+/// top-most level.
+///
+/// Nodes are visited in the same order as [`Uniplate::rewrite_fixpoint`]: bottom up, rewriting
+/// the result of each rule application again before moving on.
+fn rewrite_with_limits<T: Uniplate>(
+    cursor: &mut impl Cursor<T>,
+    f: &impl Fn(T) -> Option<T>,
+    budget: &mut Budget,
+) -> Result<(), Limit> {
+    go_to_first_leaf(cursor, budget)?;
+    loop {
+        // the children of the focus are already in normal form.
+        budget.check()?;
+        if let Some(new_focus) = f(cursor.focus().clone()) {
+            budget.step()?;
+            cursor.replace_focus(new_focus);
+            go_to_first_leaf(cursor, budget)?;
+        } else if cursor.go_right().is_some() {
+            go_to_first_leaf(cursor, budget)?;
+        } else if cursor.go_up().is_none() {
+            return Ok(());
+        }
+    }
+}
+
+/// Moves the focus to the left-most leaf below it.
+fn go_to_first_leaf<T>(cursor: &mut impl Cursor<T>, budget: &Budget) -> Result<(), Limit> {
+    budget.check_depth(cursor.depth())?;
+    while cursor.go_down().is_some() {
+        budget.check_depth(cursor.depth())?;
+    }
+    Ok(())
+}
+
+/// Splits the children of `focus` into its left-most child and the rest, using `focus_children`
+/// if they are known.
+///
+/// Returns `None`, leaving `focus_children` filled in, if `focus` has no children.
+fn go_down<T: Uniplate>(
+    focus: &T,
+    focus_children: &mut Option<VecDeque<T>>,
+) -> Option<(T, VecDeque<T>)> {
+    let mut children = focus_children.take().unwrap_or_else(|| focus.children());
+    let Some(first) = children.pop_front() else {
+        *focus_children = Some(children);
+        return None;
+    };
+    Some((first, children))
+}
+
+/// Replaces `focus` with its parent, given its siblings and the parent as it was before moving
+/// down into it.
+///
+/// If nothing has `changed`, this is the original parent, and its children are returned so that
+/// they can be reused. Otherwise, the parent is rebuilt with the new children.
+fn go_up<T: Uniplate>(
+    focus: &mut T,
+    mut left: VecDeque<T>,
+    mut right: VecDeque<T>,
+    parent: Arc<T>,
+    changed: bool,
+) -> Option<VecDeque<T>> {
+    match Arc::try_unwrap(parent) {
+        Ok(parent) if !changed => {
+            left.push_back(core::mem::replace(focus, parent));
+            left.append(&mut right);
+            Some(left)
+        }
+        Ok(parent) => {
+            let child = core::mem::replace(focus, parent);
+            let mut children = left.into_iter().chain(iter::once(child)).chain(right);
+            *focus = focus.rebuild(&mut children);
+            None
+        }
+        // the parent is shared with a clone of this zipper.
+        Err(parent) => {
+            let mut children = left
+                .into_iter()
+                .chain(iter::once(focus.clone()))
+                .chain(right);
+            *focus = parent.rebuild(&mut children);
+            None
+        }
+    }
+}
+
+/// Moves the focus to the sibling at `index`, counting from the left-most sibling.
+///
+/// `left` and `right` are the siblings of the focus, in left-right order. Returns `None`, leaving
+/// everything unchanged, if there is no such sibling.
+fn go_to_sibling<T>(
+    focus: &mut T,
+    left: &mut VecDeque<T>,
+    right: &mut VecDeque<T>,
+    index: usize,
+) -> Option<()> {
+    match index.cmp(&left.len()) {
+        core::cmp::Ordering::Equal => {}
+        core::cmp::Ordering::Less => {
+            // left = [.., target, moved..], focus, right = [..]
+            let mut moved = left.split_off(index);
+            let new_focus = moved.pop_front()?;
+            moved.push_back(core::mem::replace(focus, new_focus));
+            moved.append(right);
+            *right = moved;
+        }
+        core::cmp::Ordering::Greater => {
+            // left = [..], focus, right = [moved.., target, rest..]
+            let offset = index - left.len() - 1;
+            if offset >= right.len() {
+                return None;
+            }
+            let mut rest = right.split_off(offset);
+            let new_focus = rest.pop_front()?;
+            left.push_back(core::mem::replace(focus, new_focus));
+            left.append(right);
+            *right = rest;
+        }
+    }
+    Some(())
 }
@@ -0,0 +1,34 @@
+//! A derived mutable instance cannot silently skip a field that only has a `Biplate` instance.
+use uniplate::{Biplate, Tree, Uniplate};
+
+#[derive(Eq, PartialEq, Clone, Debug, Uniplate)]
+#[uniplate(by_mut)]
+enum Expr {
+    Val(i32),
+    Wrap(Manual),
+}
+
+/// Only has hand-written `Uniplate` and `Biplate` instances.
+#[derive(Eq, PartialEq, Clone, Debug)]
+struct Manual(Box<Expr>);
+
+impl Uniplate for Manual {
+    fn uniplate(&self) -> (Tree<Self>, Box<dyn Fn(Tree<Self>) -> Self>) {
+        let this = self.clone();
+        (Tree::Zero, Box::new(move |_| this.clone()))
+    }
+}
+
+impl Biplate<Expr> for Manual {
+    fn biplate(&self) -> (Tree<Expr>, Box<dyn Fn(Tree<Expr>) -> Self>) {
+        (
+            Tree::One((*self.0).clone()),
+            Box::new(|tree| {
+                let Tree::One(x) = tree else { panic!() };
+                Manual(Box::new(x))
+            }),
+        )
+    }
+}
+
+pub fn main() {}
@@ -0,0 +1,30 @@
+//! Types that are not Send or Sync can derive Uniplate.
+
+use std::rc::Rc;
+
+use uniplate::Uniplate;
+
+#[derive(Eq, PartialEq, Uniplate, Clone, Debug)]
+#[uniplate()]
+enum BinaryTree<T: Eq + Clone> {
+    Leaf(T),
+    Branch(Box<BinaryTree<T>>, Box<BinaryTree<T>>),
+}
+
+pub fn main() {
+    let tree = BinaryTree::Branch(
+        Box::new(BinaryTree::Leaf(Rc::new(1))),
+        Box::new(BinaryTree::Leaf(Rc::new(2))),
+    );
+    assert_eq!(tree.children().len(), 2);
+
+    let (_, hole) = tree.holes().next().unwrap();
+    let new_tree = hole(BinaryTree::Leaf(Rc::new(3)));
+    assert_eq!(
+        new_tree,
+        BinaryTree::Branch(
+            Box::new(BinaryTree::Leaf(Rc::new(3))),
+            Box::new(BinaryTree::Leaf(Rc::new(2))),
+        )
+    );
+}
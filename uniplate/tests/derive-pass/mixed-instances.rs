@@ -0,0 +1,140 @@
+//! Derived instances can contain types with hand-written instances.
+use uniplate::{Biplate, BiplateMut, BiplateRef, Tree, Uniplate, UniplateMut, UniplateRef};
+
+#[derive(Eq, PartialEq, Clone, Debug, Uniplate)]
+#[uniplate()]
+#[biplate(to=Manual)]
+enum Expr {
+    Val(i32),
+    Wrap(Manual),
+}
+
+/// Only has hand-written `Uniplate` and `Biplate` instances.
+#[derive(Eq, PartialEq, Clone, Debug)]
+struct Manual(Box<Expr>);
+
+impl Uniplate for Manual {
+    fn uniplate(&self) -> (Tree<Self>, Box<dyn Fn(Tree<Self>) -> Self>) {
+        let this = self.clone();
+        (Tree::Zero, Box::new(move |_| this.clone()))
+    }
+}
+
+impl Biplate<Expr> for Manual {
+    fn biplate(&self) -> (Tree<Expr>, Box<dyn Fn(Tree<Expr>) -> Self>) {
+        (
+            Tree::One((*self.0).clone()),
+            Box::new(|tree| {
+                let Tree::One(x) = tree else { panic!() };
+                Manual(Box::new(x))
+            }),
+        )
+    }
+}
+
+/// Like `Expr`, but also derives borrowing instances.
+#[derive(Eq, PartialEq, Clone, Debug, Uniplate)]
+#[uniplate(by_ref)]
+enum RefExpr {
+    Val(i32),
+    Wrap(ManualRef),
+}
+
+/// Has hand-written owning and borrowing instances.
+#[derive(Eq, PartialEq, Clone, Debug)]
+struct ManualRef(Box<RefExpr>);
+
+impl Uniplate for ManualRef {
+    fn uniplate(&self) -> (Tree<Self>, Box<dyn Fn(Tree<Self>) -> Self>) {
+        let this = self.clone();
+        (Tree::Zero, Box::new(move |_| this.clone()))
+    }
+}
+
+impl Biplate<RefExpr> for ManualRef {
+    fn biplate(&self) -> (Tree<RefExpr>, Box<dyn Fn(Tree<RefExpr>) -> Self>) {
+        (
+            Tree::One((*self.0).clone()),
+            Box::new(|tree| {
+                let Tree::One(x) = tree else { panic!() };
+                ManualRef(Box::new(x))
+            }),
+        )
+    }
+}
+
+impl UniplateRef for ManualRef {
+    fn for_each_child<'a>(&'a self, _: &mut impl FnMut(&'a Self)) {}
+}
+
+impl BiplateRef<RefExpr> for ManualRef {
+    fn for_each_child_bi<'a>(&'a self, f: &mut impl FnMut(&'a RefExpr)) {
+        f(&self.0)
+    }
+}
+
+/// Like `Expr`, but also derives mutable instances.
+#[derive(Eq, PartialEq, Clone, Debug, Uniplate)]
+#[uniplate(by_mut)]
+enum MutExpr {
+    Val(i32),
+    Wrap(ManualMut),
+}
+
+/// Has hand-written owning and mutable instances.
+#[derive(Eq, PartialEq, Clone, Debug)]
+struct ManualMut(Box<MutExpr>);
+
+impl Uniplate for ManualMut {
+    fn uniplate(&self) -> (Tree<Self>, Box<dyn Fn(Tree<Self>) -> Self>) {
+        let this = self.clone();
+        (Tree::Zero, Box::new(move |_| this.clone()))
+    }
+}
+
+impl Biplate<MutExpr> for ManualMut {
+    fn biplate(&self) -> (Tree<MutExpr>, Box<dyn Fn(Tree<MutExpr>) -> Self>) {
+        (
+            Tree::One((*self.0).clone()),
+            Box::new(|tree| {
+                let Tree::One(x) = tree else { panic!() };
+                ManualMut(Box::new(x))
+            }),
+        )
+    }
+}
+
+impl UniplateMut for ManualMut {
+    fn for_each_child_mut(&mut self, _: &mut impl FnMut(&mut Self)) {}
+}
+
+impl BiplateMut<MutExpr> for ManualMut {
+    fn for_each_child_bi_mut(&mut self, f: &mut impl FnMut(&mut MutExpr)) {
+        f(&mut self.0)
+    }
+}
+
+pub fn main() {
+    let expr = Expr::Wrap(Manual(Box::new(Expr::Val(1))));
+    assert_eq!(expr.children(), [Expr::Val(1)]);
+    assert_eq!(expr.universe().len(), 2);
+    assert_eq!(
+        Biplate::<Manual>::children_bi(&expr),
+        [Manual(Box::new(Expr::Val(1)))]
+    );
+
+    let expr = RefExpr::Wrap(ManualRef(Box::new(RefExpr::Val(1))));
+    assert_eq!(expr.children(), [RefExpr::Val(1)]);
+    assert_eq!(expr.children_ref(), [&RefExpr::Val(1)]);
+    assert_eq!(expr.universe_ref().count(), expr.universe().len());
+
+    let mut expr = MutExpr::Wrap(ManualMut(Box::new(MutExpr::Val(1))));
+    let increment = |x: MutExpr| match x {
+        MutExpr::Val(n) => MutExpr::Val(n + 1),
+        x => x,
+    };
+    let expected = expr.transform(&increment);
+    expr.transform_mut(&mut |x| *x = increment(x.clone()));
+    assert_eq!(expr, expected);
+    assert_eq!(expr, MutExpr::Wrap(ManualMut(Box::new(MutExpr::Val(2)))));
+}
@@ -0,0 +1,63 @@
+// The derived instances should not depend on std or its prelude.
+#![no_std]
+
+extern crate alloc;
+extern crate std;
+
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use uniplate::{Biplate, Uniplate};
+
+#[derive(Eq, PartialEq, Clone, Debug, Uniplate)]
+#[uniplate()]
+#[biplate(to=String)]
+#[biplate(to=i32)]
+enum Expr {
+    Val(i32),
+    Var(String),
+    Neg(Box<Expr>),
+    Add(alloc::boxed::Box<Expr>, Box<Expr>),
+    Sum(Vec<Expr>),
+    Pair(Box<(Expr, i32)>),
+}
+
+#[derive(Eq, PartialEq, Clone, Debug, Uniplate)]
+#[uniplate()]
+#[biplate(to=Expr)]
+#[biplate(to=String)]
+struct Assign {
+    name: String,
+    value: Expr,
+}
+
+pub fn main() {
+    use Expr::*;
+
+    let expr = Add(
+        Box::new(Neg(Box::new(Var(String::from("x"))))),
+        Box::new(Sum(vec![Val(1), Pair(Box::new((Val(2), 3)))])),
+    );
+
+    assert_eq!(expr.universe().len(), 7);
+
+    let ints: VecDeque<i32> = expr.universe_bi();
+    assert_eq!(ints, vec![1, 2, 3]);
+
+    let doubled = expr.transform(&|x| match x {
+        Val(n) => Val(n * 2),
+        x => x,
+    });
+    let ints: VecDeque<i32> = doubled.universe_bi();
+    assert_eq!(ints, vec![2, 4, 3]);
+
+    let assign = Assign {
+        name: String::from("y"),
+        value: expr,
+    };
+    let names: VecDeque<String> = assign.universe_bi();
+    assert_eq!(names, vec![String::from("y"), String::from("x")]);
+    assert_eq!(Biplate::<Expr>::children_bi(&assign).len(), 1);
+}
@@ -0,0 +1,47 @@
+//! In-place traversals are derived alongside Uniplate and Biplate with `#[uniplate(by_mut)]`.
+use uniplate::{BiplateMut, Uniplate, UniplateMut};
+
+#[derive(Eq, PartialEq, Clone, Debug, Uniplate)]
+#[biplate(to=i32)]
+#[uniplate(by_mut)]
+enum Expr {
+    Val(i32),
+    Add(Box<Expr>, Box<Expr>),
+    Pair(Box<(Expr, i32)>),
+    Named { name: String, value: Box<Expr> },
+}
+
+pub fn main() {
+    let mut expr = Expr::Add(
+        Box::new(Expr::Val(1)),
+        Box::new(Expr::Pair(Box::new((
+            Expr::Named {
+                name: "x".into(),
+                value: Box::new(Expr::Val(2)),
+            },
+            3,
+        )))),
+    );
+
+    BiplateMut::<i32>::transform_bi_mut(&mut expr, &mut |x| *x *= 10);
+
+    let expected = Expr::Add(
+        Box::new(Expr::Val(10)),
+        Box::new(Expr::Pair(Box::new((
+            Expr::Named {
+                name: "x".into(),
+                value: Box::new(Expr::Val(20)),
+            },
+            30,
+        )))),
+    );
+    assert_eq!(expr, expected);
+
+    // Replace every addition with its left operand.
+    expr.transform_mut(&mut |x| {
+        if let Expr::Add(a, _) = x {
+            *x = (**a).clone();
+        }
+    });
+    assert_eq!(expr, Expr::Val(10));
+}
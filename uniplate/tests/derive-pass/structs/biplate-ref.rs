@@ -0,0 +1,51 @@
+//! Borrowing traversals are derived alongside Uniplate and Biplate with `#[uniplate(by_ref)]`.
+use uniplate::{BiplateRef, Uniplate, UniplateRef};
+
+#[derive(Eq, PartialEq, Clone, Debug, Uniplate)]
+#[biplate(to=i32)]
+#[biplate(to=B)]
+#[uniplate(by_ref)]
+struct A {
+    value: i32,
+    children: Vec<B>,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug, Uniplate)]
+#[biplate(to=i32)]
+#[biplate(to=A)]
+#[uniplate(by_ref)]
+struct B {
+    value: (i32, i32),
+    child: Box<(A, i32)>,
+}
+
+pub fn main() {
+    let a = A {
+        value: 1,
+        children: vec![B {
+            value: (2, 2),
+            child: Box::new((
+                A {
+                    value: 3,
+                    children: vec![],
+                },
+                3,
+            )),
+        }],
+    };
+
+    let ints: Vec<&i32> = BiplateRef::<i32>::universe_bi_ref(&a).collect();
+    assert_eq!(ints, vec![&1, &2, &2, &3, &3]);
+
+    let bs: Vec<&B> = a.children_bi_ref();
+    assert_eq!(bs, vec![&a.children[0]]);
+
+    // Uniplate walks through B to find the nested A.
+    let inner_a = &a.children[0].child.0;
+    assert_eq!(a.children_ref(), vec![inner_a]);
+    assert_eq!(a.universe_ref().collect::<Vec<_>>(), vec![&a, inner_a]);
+
+    let b = &a.children[0];
+    let as_in_b: Vec<&A> = b.children_bi_ref();
+    assert_eq!(as_in_b, vec![&b.child.0]);
+}
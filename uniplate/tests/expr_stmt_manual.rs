@@ -3,7 +3,7 @@
 use std::collections::VecDeque;
 use std::iter::zip;
 //use uniplate::test_common::paper::*;
-use uniplate::{Biplate, Tree, Uniplate};
+use uniplate::{Biplate, TraversalOrder, Tree, Uniplate};
 
 use self::Expr::*;
 
@@ -451,3 +451,120 @@ fn universe_bi_multitype() {
     let Var(_) = children[6] else { panic!() };
     let Val(_) = children[7] else { panic!() };
 }
+
+#[test]
+fn universe_in_orders() {
+    // (x + 10) * (y - 2)
+    let expr = Mul(
+        Box::new(Add(Box::new(Var("x".to_owned())), Box::new(Val(10)))),
+        Box::new(Sub(Box::new(Var("y".to_owned())), Box::new(Val(2)))),
+    );
+
+    let preorder: VecDeque<Expr> = expr.universe_in(TraversalOrder::PreOrder).collect();
+    assert_eq!(preorder, expr.universe());
+
+    let postorder: Vec<Expr> = expr.universe_in(TraversalOrder::PostOrder).collect();
+    assert_eq!(7, postorder.len());
+    let Var(_) = postorder[0] else { panic!() };
+    let Val(_) = postorder[1] else { panic!() };
+    let Add(_, _) = postorder[2] else { panic!() };
+    let Var(_) = postorder[3] else { panic!() };
+    let Val(_) = postorder[4] else { panic!() };
+    let Sub(_, _) = postorder[5] else { panic!() };
+    let Mul(_, _) = postorder[6] else { panic!() };
+
+    let breadth_first: Vec<Expr> = expr.universe_in(TraversalOrder::BreadthFirst).collect();
+    assert_eq!(7, breadth_first.len());
+    let Mul(_, _) = breadth_first[0] else {
+        panic!()
+    };
+    let Add(_, _) = breadth_first[1] else {
+        panic!()
+    };
+    let Sub(_, _) = breadth_first[2] else {
+        panic!()
+    };
+    let Var(_) = breadth_first[3] else { panic!() };
+    let Val(_) = breadth_first[4] else { panic!() };
+    let Var(_) = breadth_first[5] else { panic!() };
+    let Val(_) = breadth_first[6] else { panic!() };
+
+    let depths: Vec<usize> = expr.level_order().map(|(depth, _)| depth).collect();
+    assert_eq!(depths, vec![0, 1, 1, 2, 2, 2, 2]);
+}
+
+#[test]
+fn universe_bi_in_orders_multitype() {
+    let my_stmt = Sequence(vec![
+        While(
+            Val(0),
+            Box::new(Assign(
+                "x".to_owned(),
+                Add(Box::new(Var("x".to_owned())), Box::new(Val(10))),
+            )),
+        ),
+        If(
+            Var("x".to_string()),
+            Box::new(Assign(
+                "x".to_string(),
+                Add(Box::new(Var("x".to_owned())), Box::new(Val(10))),
+            )),
+            Box::new(Sequence(vec![])),
+        ),
+    ]);
+
+    let preorder: VecDeque<Expr> =
+        Biplate::<Expr>::universe_bi_in(&my_stmt, TraversalOrder::PreOrder).collect();
+    assert_eq!(preorder, Biplate::<Expr>::universe_bi(&my_stmt));
+
+    let postorder: Vec<Expr> =
+        Biplate::<Expr>::universe_bi_in(&my_stmt, TraversalOrder::PostOrder).collect();
+    assert_eq!(8, postorder.len());
+    let Val(_) = postorder[0] else { panic!() };
+    let Var(_) = postorder[1] else { panic!() };
+    let Val(_) = postorder[2] else { panic!() };
+    let Add(_, _) = postorder[3] else { panic!() };
+    let Var(_) = postorder[4] else { panic!() };
+    let Var(_) = postorder[5] else { panic!() };
+    let Val(_) = postorder[6] else { panic!() };
+    let Add(_, _) = postorder[7] else { panic!() };
+
+    // The top-most expressions are all at depth 0, and come first.
+    let levels: Vec<(usize, Expr)> = Biplate::<Expr>::level_order_bi(&my_stmt).collect();
+    let depths: Vec<usize> = levels.iter().map(|(depth, _)| *depth).collect();
+    assert_eq!(depths, vec![0, 0, 0, 0, 1, 1, 1, 1]);
+    let top_most: VecDeque<Expr> = levels[..4].iter().map(|(_, x)| x.clone()).collect();
+    assert_eq!(top_most, Biplate::<Expr>::children_bi(&my_stmt));
+
+    let breadth_first: Vec<Expr> =
+        Biplate::<Expr>::universe_bi_in(&my_stmt, TraversalOrder::BreadthFirst).collect();
+    let level_order: Vec<Expr> = levels.into_iter().map(|(_, x)| x).collect();
+    assert_eq!(breadth_first, level_order);
+}
+
+#[test]
+fn rebuild_provided_by_uniplate() {
+    // x + 10
+    let expr = Add(Box::new(Var("x".to_owned())), Box::new(Val(10)));
+
+    let mut children = Vec::new();
+    expr.children_into(&mut children);
+    assert_eq!(children, vec![Var("x".to_owned()), Val(10)]);
+
+    // only two children should be taken.
+    let mut new_children = vec![Val(1), Val(2), Val(3)].into_iter();
+    assert_eq!(
+        expr.rebuild(&mut new_children),
+        Add(Box::new(Val(1)), Box::new(Val(2)))
+    );
+    assert_eq!(new_children.next(), Some(Val(3)));
+
+    let stmt = Assign("x".to_owned(), expr.clone());
+    let mut children = Vec::new();
+    Biplate::<Expr>::children_bi_into(&stmt, &mut children);
+    assert_eq!(children, vec![expr]);
+    assert_eq!(
+        stmt.rebuild_bi(&mut std::iter::once(Val(1))),
+        Assign("x".to_owned(), Val(1))
+    );
+}
@@ -1,4 +1,8 @@
-use uniplate::{Uniplate, zipper::Zipper};
+use uniplate::{
+    Biplate, Uniplate,
+    path::Path,
+    zipper::{Zipper, ZipperBi},
+};
 
 #[derive(Clone, PartialEq, Eq, Debug, Uniplate)]
 enum Tree {
@@ -161,3 +165,275 @@ fn zipper_has_right() {
     zipper.go_right();
     assert!(!zipper.has_right());
 }
+
+#[test]
+fn zipper_path() {
+    let tree = Tree::Many(
+        0,
+        vec![
+            Tree::Leaf(1),
+            Tree::Many(2, vec![Tree::Leaf(3), Tree::Leaf(4)]),
+        ],
+    );
+    let mut zipper = Zipper::new(tree);
+    assert_eq!(zipper.path(), Path::root());
+
+    zipper.go_down();
+    zipper.go_right();
+    zipper.go_down();
+    zipper.go_right();
+    assert_eq!(zipper.focus().value(), 4);
+    assert_eq!(zipper.path(), Path::from([1, 1]));
+}
+
+#[test]
+fn zipper_go_to() {
+    let tree = Tree::Many(
+        0,
+        vec![
+            Tree::Leaf(1),
+            Tree::Many(2, vec![Tree::Leaf(3), Tree::Leaf(4), Tree::Leaf(5)]),
+            Tree::Leaf(6),
+        ],
+    );
+    let mut zipper = Zipper::new(tree.clone());
+
+    for (path, node) in tree.positions() {
+        assert!(zipper.go_to(&path).is_some());
+        assert_eq!(zipper.focus(), &node);
+        assert_eq!(zipper.path(), path);
+    }
+
+    // Moving around must not reorder siblings.
+    zipper.go_to(&Path::from([1, 2])).unwrap();
+    zipper.go_to(&Path::from([1, 0])).unwrap();
+    zipper.go_to(&Path::from([2])).unwrap();
+    assert_eq!(zipper.rebuild_root(), tree);
+}
+
+#[test]
+fn zipper_go_to_invalid_path() {
+    let tree = Tree::Many(0, vec![Tree::Leaf(1), Tree::Leaf(2)]);
+    let mut zipper = Zipper::new(tree.clone());
+    zipper.go_to(&Path::from([1])).unwrap();
+    zipper.replace_focus(Tree::Leaf(20));
+
+    assert!(zipper.go_to(&Path::from([2])).is_none());
+    assert!(zipper.go_to(&Path::from([0, 0])).is_none());
+
+    // The focus stays where it was, and changes are kept.
+    assert_eq!(zipper.path(), Path::from([1]));
+    assert_eq!(zipper.focus(), &Tree::Leaf(20));
+    assert_eq!(
+        zipper.rebuild_root(),
+        Tree::Many(0, vec![Tree::Leaf(1), Tree::Leaf(20)])
+    );
+}
+
+#[test]
+fn zipper_bi_path_and_go_to() {
+    let forest = vec![
+        Tree::Leaf(1),
+        Tree::Many(2, vec![Tree::Leaf(3), Tree::Leaf(4)]),
+        Tree::Leaf(5),
+    ];
+    let mut zipper: ZipperBi<Tree, Vec<Tree>> = ZipperBi::new(forest.clone()).unwrap();
+    assert_eq!(zipper.path(), Path::from([0]));
+
+    for (path, node) in forest.positions_bi() {
+        assert!(zipper.go_to(&path).is_some());
+        assert_eq!(zipper.focus(), &node);
+        assert_eq!(zipper.path(), path);
+    }
+
+    // The root is not of type Tree, so the empty path is not valid.
+    assert!(zipper.go_to(&Path::root()).is_none());
+    assert!(zipper.go_to(&Path::from([3])).is_none());
+    assert_eq!(zipper.path(), Path::from([2]));
+
+    zipper.go_to(&Path::from([1, 1])).unwrap();
+    zipper.replace_focus(Tree::Leaf(40));
+    zipper.go_to(&Path::from([0])).unwrap();
+    assert_eq!(
+        zipper.rebuild_root(),
+        vec![
+            Tree::Leaf(1),
+            Tree::Many(2, vec![Tree::Leaf(3), Tree::Leaf(40)]),
+            Tree::Leaf(5),
+        ]
+    );
+}
+
+#[test]
+fn zipper_go_left_path_and_go_to() {
+    let tree = Tree::Many(0, vec![Tree::Leaf(1), Tree::Leaf(2), Tree::Leaf(3)]);
+    let mut zipper = Zipper::new(tree.clone());
+    zipper.go_to(&Path::from([2])).unwrap();
+
+    zipper.go_left().unwrap();
+    assert_eq!(zipper.focus(), &Tree::Leaf(2));
+    assert_eq!(zipper.path(), Path::from([1]));
+    assert!(zipper.iter_left_siblings().map(Tree::value).eq(1..2));
+    assert!(zipper.iter_right_siblings().map(Tree::value).eq(3..4));
+
+    zipper.go_to(&Path::from([2])).unwrap();
+    assert_eq!(zipper.focus(), &Tree::Leaf(3));
+    zipper.go_left().unwrap();
+    zipper.go_left().unwrap();
+    assert_eq!(zipper.focus(), &Tree::Leaf(1));
+    assert_eq!(zipper.path(), Path::from([0]));
+    assert!(zipper.go_left().is_none());
+
+    assert_eq!(zipper.rebuild_root(), tree);
+}
+
+#[test]
+fn zipper_bi_go_left_path_and_go_to() {
+    let forest = vec![Tree::Leaf(1), Tree::Leaf(2), Tree::Leaf(3)];
+    let mut zipper: ZipperBi<Tree, Vec<Tree>> = ZipperBi::new(forest.clone()).unwrap();
+    zipper.go_to(&Path::from([2])).unwrap();
+
+    zipper.go_left().unwrap();
+    assert_eq!(zipper.focus(), &Tree::Leaf(2));
+    assert_eq!(zipper.path(), Path::from([1]));
+
+    zipper.go_to(&Path::from([2])).unwrap();
+    assert_eq!(zipper.focus(), &Tree::Leaf(3));
+    zipper.go_left().unwrap();
+    zipper.go_left().unwrap();
+    assert_eq!(zipper.focus(), &Tree::Leaf(1));
+    assert_eq!(zipper.path(), Path::from([0]));
+
+    assert_eq!(zipper.rebuild_root(), forest);
+}
+
+/// Returns the address of the boxed child of a `Tree::One`.
+fn boxed_child(tree: &Tree) -> *const Tree {
+    match tree {
+        Tree::One(_, child) => &**child,
+        _ => panic!("expected Tree::One"),
+    }
+}
+
+#[test]
+fn zipper_go_up_unchanged_keeps_parent() {
+    let tree = Tree::One(0, Box::new(Tree::One(1, Box::new(Tree::Leaf(2)))));
+    let mut zipper = Zipper::new(tree.clone());
+    let child = boxed_child(zipper.focus());
+
+    // the parent is not rebuilt, so its box is not reallocated.
+    zipper.go_down().unwrap();
+    let grandchild = boxed_child(zipper.focus());
+    zipper.go_down().unwrap();
+    assert!(!zipper.has_down());
+    zipper.go_up().unwrap();
+    zipper.go_up().unwrap();
+    assert_eq!(boxed_child(zipper.focus()), child);
+
+    // moving back down reuses the children of the parent, rather than cloning them again.
+    zipper.go_down().unwrap();
+    assert_eq!(boxed_child(zipper.focus()), grandchild);
+    assert_eq!(zipper.rebuild_root(), tree);
+}
+
+#[test]
+fn zipper_go_up_changed_rebuilds_ancestors() {
+    let tree = Tree::Many(
+        0,
+        vec![
+            Tree::One(1, Box::new(Tree::Leaf(2))),
+            Tree::One(3, Box::new(Tree::Leaf(4))),
+        ],
+    );
+    let mut zipper = Zipper::new(tree.clone());
+    zipper.go_down().unwrap();
+    zipper.go_down().unwrap();
+    *zipper.focus_mut() = Tree::Leaf(20);
+
+    // moving around after a change keeps it.
+    zipper.go_up().unwrap();
+    zipper.go_right().unwrap();
+    zipper.go_down().unwrap();
+    zipper.go_up().unwrap();
+    zipper.go_left().unwrap();
+    assert_eq!(zipper.focus(), &Tree::One(1, Box::new(Tree::Leaf(20))));
+
+    // a clone shares the unchanged parents, but not the changes.
+    let mut clone = zipper.clone();
+    clone.go_right().unwrap();
+    clone.replace_focus(Tree::Leaf(30));
+    assert_eq!(
+        clone.rebuild_root(),
+        Tree::Many(
+            0,
+            vec![Tree::One(1, Box::new(Tree::Leaf(20))), Tree::Leaf(30)]
+        )
+    );
+    assert_eq!(
+        zipper.rebuild_root(),
+        Tree::Many(
+            0,
+            vec![
+                Tree::One(1, Box::new(Tree::Leaf(20))),
+                Tree::One(3, Box::new(Tree::Leaf(4))),
+            ]
+        )
+    );
+}
+
+#[test]
+fn zipper_bi_rebuild_root_unchanged_keeps_root() {
+    let forest = vec![Tree::One(1, Box::new(Tree::Leaf(2))), Tree::Leaf(3)];
+    let copy = forest.clone();
+    let child = boxed_child(&copy[0]);
+
+    let mut zipper: ZipperBi<Tree, Vec<Tree>> = ZipperBi::new(copy).unwrap();
+    zipper.go_down().unwrap();
+    zipper.go_up().unwrap();
+    zipper.go_right().unwrap();
+    let new_forest = zipper.rebuild_root();
+    assert_eq!(new_forest, forest);
+    assert_eq!(boxed_child(&new_forest[0]), child);
+}
+
+#[test]
+fn zipper_send_to_thread() {
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    let tree = Tree::Many(
+        1,
+        vec![Tree::Leaf(2), Tree::One(3, Box::new(Tree::Leaf(4)))],
+    );
+
+    let mut zipper = Zipper::new(tree.clone());
+    zipper.go_down().unwrap();
+    assert_send_sync(&zipper);
+
+    let new_tree = std::thread::spawn(move || {
+        zipper.go_right().unwrap();
+        zipper.go_down().unwrap();
+        zipper.replace_focus(Tree::Leaf(40));
+        zipper.rebuild_root()
+    })
+    .join()
+    .unwrap();
+
+    assert_eq!(
+        new_tree,
+        Tree::Many(
+            1,
+            vec![Tree::Leaf(2), Tree::One(3, Box::new(Tree::Leaf(40)))]
+        )
+    );
+
+    let zipper = ZipperBi::<Tree, _>::new(vec![tree.clone()]).unwrap();
+    assert_send_sync(&zipper);
+
+    let (child, hole) = tree.holes().next().unwrap();
+    assert_send_sync(&hole);
+    let rebuilt = std::thread::scope(|s| s.spawn(move || hole(child)).join().unwrap());
+    assert_eq!(rebuilt, tree);
+
+    let (_, ctx) = tree.contexts().next().unwrap();
+    assert_send_sync(&ctx);
+}